@@ -1,8 +1,24 @@
 use crate::models::{Candle, CandlesResponse}; // Importing necessary models.
 use crate::utils::BASE_URL; // Importing the base URL for API requests.
-use anyhow::Result; // Importing Result type for error handling.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
 use chrono::{DateTime, Utc}; // Importing DateTime and Utc types for date and time handling.
 
+// Candle granularities offered by the API and their length in seconds.
+pub const GRANULARITIES: [(&str, i64); 8] = [
+    ("ONE_MINUTE", 60),
+    ("FIVE_MINUTE", 300),
+    ("FIFTEEN_MINUTE", 900),
+    ("THIRTY_MINUTE", 1800),
+    ("ONE_HOUR", 3600),
+    ("TWO_HOUR", 7200),
+    ("SIX_HOUR", 21600),
+    ("ONE_DAY", 86400),
+];
+// Largest number of candles the API returns for one request.
+pub const MAX_CANDLES_PER_REQUEST: i64 = 300;
+// Largest number of requests made for one history fetch.
+const MAX_HISTORY_REQUESTS: i64 = 100;
+
 // Asynchronously fetches candle data for a specific product within a given time range and granularity.
 pub async fn fetch_candles(
     product_id: &str,  // The ID of the product to fetch data for.
@@ -33,3 +49,56 @@ pub async fn fetch_candles(
     // Returning the candles data.
     Ok(candles_response.candles)
 }
+
+// Returns the length in seconds of an API granularity, or None when it is unknown.
+pub fn granularity_seconds(granularity: &str) -> Option<i64> {
+    GRANULARITIES
+        .iter()
+        .find(|(name, _)| *name == granularity)
+        .map(|(_, seconds)| *seconds)
+}
+
+// Asynchronously fetches candles over a range longer than one request allows, one page at a time.
+//
+// Candles are returned newest first, like a single request, without duplicates at page edges.
+pub async fn fetch_candle_history(
+    product_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    granularity: &str,
+) -> Result<Vec<Candle>> {
+    let seconds = granularity_seconds(granularity)
+        .ok_or_else(|| anyhow!("unknown granularity {}", granularity))?;
+    let page = seconds * MAX_CANDLES_PER_REQUEST;
+    let (start_ts, end_ts) = (start.timestamp(), end.timestamp());
+    if (end_ts - start_ts) / page >= MAX_HISTORY_REQUESTS {
+        return Err(anyhow!(
+            "range needs more than {} requests at {}, use a larger granularity",
+            MAX_HISTORY_REQUESTS,
+            granularity
+        ));
+    }
+
+    let mut candles: Vec<Candle> = Vec::new();
+    let mut page_start = start_ts;
+    while page_start < end_ts {
+        let page_end = (page_start + page).min(end_ts);
+        let page_from = DateTime::from_timestamp(page_start, 0).unwrap_or(start);
+        let page_to = DateTime::from_timestamp(page_end, 0).unwrap_or(end);
+        candles.extend(
+            fetch_candles(
+                product_id,
+                &page_from.to_rfc3339(),
+                &page_to.to_rfc3339(),
+                granularity,
+            )
+            .await?,
+        );
+        page_start = page_end;
+    }
+
+    // Newest first, dropping candles repeated at page boundaries.
+    candles.sort_by_key(|candle| std::cmp::Reverse(candle.start.parse::<i64>().unwrap_or(0)));
+    candles.dedup_by(|a, b| a.start == b.start);
+    Ok(candles)
+}
@@ -1,4 +1,4 @@
-use crate::models::{MarketTrade, MarketTradesResponse}; // Importing necessary models.
+use crate::models::{BestBidAsk, MarketTrade, MarketTradesResponse}; // Importing necessary models.
 use crate::utils::BASE_URL; // Importing the base URL for API requests.
 use anyhow::Result; // Importing Result type for error handling.
 
@@ -18,3 +18,20 @@ pub async fn fetch_market_trades(product_id: &str) -> Result<Vec<MarketTrade>> {
     // Returning the trades data.
     Ok(market_trades_response.trades)
 }
+
+// Asynchronously fetches the best bid and ask for a specific product from the ticker endpoint.
+pub async fn fetch_best_bid_ask(product_id: &str) -> Result<BestBidAsk> {
+    // Returns a Result with a BestBidAsk object or an error.
+
+    // Constructing the API request URL, asking for a single trade to keep the response small.
+    let url = format!("{}/market/products/{}/ticker?limit=1", BASE_URL, product_id);
+
+    // Sending the GET request to the API.
+    let response = reqwest::get(&url).await?;
+
+    // Attempting to deserialize the JSON response into BestBidAsk.
+    let best_bid_ask: BestBidAsk = response.json().await?;
+
+    // Returning the best bid and ask.
+    Ok(best_bid_ask)
+}
@@ -15,3 +15,17 @@ pub async fn fetch_product_book(product_id: &str) -> Result<ProductBook, Error>
     // Returning the deserialized ProductBook object.
     Ok(response)
 }
+
+// Asynchronously fetches up to `limit` levels per side of the product book for a specific product.
+pub async fn fetch_product_book_depth(product_id: &str, limit: u32) -> Result<ProductBook, Error> {
+    // Constructing the API request URL with the requested depth.
+    let url = format!(
+        "{}/market/product_book?product_id={}&limit={}",
+        BASE_URL, product_id, limit
+    );
+
+    // Sending the GET request to the API and deserializing the JSON response.
+    let response = reqwest::get(&url).await?.json::<ProductBook>().await?;
+
+    Ok(response)
+}
@@ -18,3 +18,27 @@ pub async fn fetch_products() -> Result<Vec<Product>> {
     // Returning the deserialized vector of Product objects.
     Ok(products_response.products)
 }
+
+// Asynchronously fetches the given products in a single request using the product_ids filter.
+pub async fn fetch_products_by_ids(product_ids: &[String]) -> Result<Vec<Product>> {
+    // Returns a Result with a vector of Product objects or an error.
+
+    // Building the query string with one product_ids parameter per product.
+    let query = product_ids
+        .iter()
+        .map(|id| format!("product_ids={}", id))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    // Constructing the API request URL.
+    let url = format!("{}/market/products?{}", BASE_URL, query);
+
+    // Sending the GET request to the API.
+    let response = reqwest::get(&url).await?;
+
+    // Attempting to deserialize the JSON response into a ProductsResponse object.
+    let products_response: ProductsResponse = response.json().await?;
+
+    // Returning the deserialized vector of Product objects.
+    Ok(products_response.products)
+}
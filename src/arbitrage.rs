@@ -0,0 +1,420 @@
+use crate::models::Product; // Importing the Product model.
+use serde::{Deserialize, Serialize}; // Importing serde traits for the saved settings.
+use std::collections::{BTreeMap, BTreeSet, HashMap}; // Importing maps and sets for the currency graph.
+
+// Triangular arbitrage settings saved in the config file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArbitrageSettings {
+    pub fee_bps: f64,             // Fee charged on each leg, in basis points.
+    pub threshold_bps: f64,       // Round-trip edge above which a triangle is highlighted.
+    pub requests_per_second: f64, // Largest rate of book requests.
+    #[serde(default)]
+    pub currencies: Vec<String>, // Currencies the triangles may use, empty for all.
+    #[serde(default = "default_max_quote_age_secs")]
+    pub max_quote_age_secs: f64, // Age beyond which a leg's quote is too old to price a triangle.
+}
+
+impl Default for ArbitrageSettings {
+    fn default() -> Self {
+        ArbitrageSettings {
+            fee_bps: 60.0,
+            threshold_bps: 0.0,
+            requests_per_second: 5.0,
+            currencies: Vec::new(),
+            max_quote_age_secs: default_max_quote_age_secs(),
+        }
+    }
+}
+
+// Default largest quote age: a few seconds, enough to poll a triangle's three legs.
+fn default_max_quote_age_secs() -> f64 {
+    5.0
+}
+
+// A spot market between two currencies.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Market {
+    pub product_id: String, // Product ID, e.g. ETH-BTC.
+    pub base: String,       // Currency bought and sold.
+    pub quote: String,      // Currency the price is in.
+}
+
+impl Market {
+    // Returns the currency on the other side of the market.
+    fn other(&self, currency: &str) -> &str {
+        if self.base == currency {
+            &self.quote
+        } else {
+            &self.base
+        }
+    }
+}
+
+// Best bid and ask of a market.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TopOfBook {
+    pub bid: f64, // Best bid price.
+    pub ask: f64, // Best ask price.
+}
+
+impl TopOfBook {
+    // Returns the mid price.
+    pub fn mid(&self) -> f64 {
+        (self.bid + self.ask) / 2.0
+    }
+}
+
+// Three markets linking three currencies.
+//
+// The anchor is the currency two of the markets share as their quote when there is one, e.g.
+// USD for ETH-USD, BTC-USD and ETH-BTC; the cross is the market between the other two
+// currencies, whose direct price is compared with the one implied through the anchor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triangle {
+    pub anchor: String,    // Currency each round trip starts and ends in.
+    pub cross: Market,     // Market between the two other currencies.
+    pub base_leg: Market,  // Market between the cross's base and the anchor.
+    pub quote_leg: Market, // Market between the cross's quote and the anchor.
+}
+
+// Prices and round-trip results of a triangle at one moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriangleReport {
+    pub direct: f64,           // Mid price of the cross market.
+    pub implied: f64,          // Cross price implied by the two anchor markets' mids.
+    pub deviation_bps: f64,    // Direct over implied price, in basis points.
+    pub forward_edge_bps: f64, // Anchor to base to quote to anchor, after fees.
+    pub reverse_edge_bps: f64, // Anchor to quote to base to anchor, after fees.
+}
+
+impl TriangleReport {
+    // Returns the better of the two round trips and whether it is the reverse one.
+    pub fn best_edge_bps(&self) -> (f64, bool) {
+        if self.reverse_edge_bps > self.forward_edge_bps {
+            (self.reverse_edge_bps, true)
+        } else {
+            (self.forward_edge_bps, false)
+        }
+    }
+}
+
+impl Triangle {
+    // Returns the products the triangle trades.
+    pub fn product_ids(&self) -> [&str; 3] {
+        [
+            &self.base_leg.product_id,
+            &self.cross.product_id,
+            &self.quote_leg.product_id,
+        ]
+    }
+
+    // Returns the currencies of a round trip, starting and ending at the anchor.
+    pub fn cycle(&self, reverse: bool) -> [&str; 4] {
+        let (first, second) = if reverse {
+            (&self.cross.quote, &self.cross.base)
+        } else {
+            (&self.cross.base, &self.cross.quote)
+        };
+        [&self.anchor, first, second, &self.anchor]
+    }
+
+    // Compares the direct and implied cross prices and works out both round trips when all
+    // three books have a positive bid and ask.
+    pub fn evaluate(
+        &self,
+        quotes: &HashMap<String, TopOfBook>,
+        fee_bps: f64,
+    ) -> Option<TriangleReport> {
+        let quote = |market: &Market| {
+            quotes
+                .get(&market.product_id)
+                .copied()
+                .filter(|book| book.bid > 0.0 && book.ask > 0.0)
+        };
+        let (base_book, cross_book, quote_book) = (
+            quote(&self.base_leg)?,
+            quote(&self.cross)?,
+            quote(&self.quote_leg)?,
+        );
+
+        // Anchor units per unit of the cross's base and quote currencies, at mid.
+        let base_value = mid_rate(&self.base_leg, &base_book, &self.cross.base);
+        let quote_value = mid_rate(&self.quote_leg, &quote_book, &self.cross.quote);
+        let implied = base_value / quote_value;
+        let direct = cross_book.mid();
+
+        let fee = 1.0 - fee_bps / 10_000.0;
+        let legs = [
+            (&self.base_leg, base_book),
+            (&self.cross, cross_book),
+            (&self.quote_leg, quote_book),
+        ];
+        let round_trip = |path: [&str; 4], order: [usize; 3]| {
+            let mut amount = 1.0;
+            for (step, leg) in order.iter().enumerate() {
+                let (market, book) = &legs[*leg];
+                amount = convert(amount, market, book, path[step]) * fee;
+            }
+            (amount - 1.0) * 10_000.0
+        };
+
+        Some(TriangleReport {
+            direct,
+            implied,
+            deviation_bps: (direct / implied - 1.0) * 10_000.0,
+            forward_edge_bps: round_trip(self.cycle(false), [0, 1, 2]),
+            reverse_edge_bps: round_trip(self.cycle(true), [2, 1, 0]),
+        })
+    }
+}
+
+// Builds the spot markets that can be traded, keeping only those between the given currencies
+// when any are given.
+//
+// Products that are aliases of another product share its book, so they are left out.
+pub fn tradable_markets(products: &[Product], currencies: &[String]) -> Vec<Market> {
+    let allowed = |currency: &str| {
+        currencies.is_empty()
+            || currencies
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(currency))
+    };
+    let mut markets: Vec<Market> = products
+        .iter()
+        .filter(|product| {
+            product.status.eq_ignore_ascii_case("online")
+                && product.product_type.eq_ignore_ascii_case("spot")
+                && !product.trading_disabled
+                && !product.is_disabled
+                && !product.cancel_only
+                && !product.view_only
+                && product.alias.is_empty()
+        })
+        .filter(|product| {
+            !product.base_currency_id.is_empty()
+                && !product.quote_currency_id.is_empty()
+                && allowed(&product.base_currency_id)
+                && allowed(&product.quote_currency_id)
+        })
+        .map(|product| Market {
+            product_id: product.product_id.clone(),
+            base: product.base_currency_id.clone(),
+            quote: product.quote_currency_id.clone(),
+        })
+        .collect();
+    markets.sort();
+    markets
+}
+
+// Finds every set of three currencies linked pairwise by markets.
+//
+// When two markets link the same pair of currencies, the first product ID is used.
+pub fn discover_triangles(markets: &[Market]) -> Vec<Triangle> {
+    // Markets by unordered currency pair, and each currency's neighbours.
+    let mut pairs: HashMap<(&str, &str), &Market> = HashMap::new();
+    let mut neighbours: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for market in markets {
+        if market.base == market.quote {
+            continue;
+        }
+        pairs
+            .entry(pair_key(&market.base, &market.quote))
+            .or_insert(market);
+        neighbours
+            .entry(&market.base)
+            .or_default()
+            .insert(&market.quote);
+        neighbours
+            .entry(&market.quote)
+            .or_default()
+            .insert(&market.base);
+    }
+
+    let mut triangles = Vec::new();
+    for (a, a_neighbours) in &neighbours {
+        for b in a_neighbours.iter().filter(|b| *b > a) {
+            for c in neighbours[b].iter().filter(|c| *c > b) {
+                if !a_neighbours.contains(c) {
+                    continue;
+                }
+                let legs = [
+                    pairs[&pair_key(a, b)],
+                    pairs[&pair_key(b, c)],
+                    pairs[&pair_key(a, c)],
+                ];
+                triangles.push(orient(legs));
+            }
+        }
+    }
+    triangles
+}
+
+// Picks the anchor and cross of three linked markets.
+fn orient(legs: [&Market; 3]) -> Triangle {
+    // The anchor is quoted by two markets when possible, the cross being the third.
+    let cross_index = (0..3)
+        .find(|&i| {
+            let (x, y) = (legs[(i + 1) % 3], legs[(i + 2) % 3]);
+            x.quote == y.quote
+        })
+        .unwrap_or(0);
+    let cross = legs[cross_index].clone();
+    let (x, y) = (legs[(cross_index + 1) % 3], legs[(cross_index + 2) % 3]);
+    // The anchor is the currency the two other markets share.
+    let anchor = if x.base == y.base || x.base == y.quote {
+        x.base.clone()
+    } else {
+        x.quote.clone()
+    };
+    let (base_leg, quote_leg) = if x.other(&anchor) == cross.base {
+        (x.clone(), y.clone())
+    } else {
+        (y.clone(), x.clone())
+    };
+    Triangle {
+        anchor,
+        cross,
+        base_leg,
+        quote_leg,
+    }
+}
+
+// Returns the key of an unordered currency pair.
+fn pair_key<'a>(a: &'a str, b: &'a str) -> (&'a str, &'a str) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+// Returns the value of one unit of `currency` in the market's other currency, at mid.
+fn mid_rate(market: &Market, book: &TopOfBook, currency: &str) -> f64 {
+    if market.base == currency {
+        book.mid()
+    } else {
+        1.0 / book.mid()
+    }
+}
+
+// Converts an amount of `from` through a market, selling base at the bid or buying it at the
+// ask, before fees.
+fn convert(amount: f64, market: &Market, book: &TopOfBook, from: &str) -> f64 {
+    if market.base == from {
+        amount * book.bid
+    } else {
+        amount / book.ask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(product_id: &str) -> Market {
+        let (base, quote) = product_id.split_once('-').unwrap();
+        Market {
+            product_id: product_id.to_string(),
+            base: base.to_string(),
+            quote: quote.to_string(),
+        }
+    }
+
+    fn quotes(books: &[(&str, f64, f64)]) -> HashMap<String, TopOfBook> {
+        books
+            .iter()
+            .map(|&(product_id, bid, ask)| (product_id.to_string(), TopOfBook { bid, ask }))
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} instead of {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn the_shared_quote_is_the_anchor() {
+        let triangles =
+            discover_triangles(&[market("BTC-USD"), market("ETH-BTC"), market("ETH-USD")]);
+        assert_eq!(
+            triangles,
+            vec![Triangle {
+                anchor: "USD".to_string(),
+                cross: market("ETH-BTC"),
+                base_leg: market("ETH-USD"),
+                quote_leg: market("BTC-USD"),
+            }]
+        );
+        assert_eq!(triangles[0].cycle(false), ["USD", "ETH", "BTC", "USD"]);
+        assert_eq!(triangles[0].cycle(true), ["USD", "BTC", "ETH", "USD"]);
+    }
+
+    #[test]
+    fn worked_example_after_fees() {
+        let triangle =
+            &discover_triangles(&[market("BTC-USD"), market("ETH-BTC"), market("ETH-USD")])[0];
+        let quotes = quotes(&[
+            ("ETH-USD", 2000.0, 2001.0),
+            ("BTC-USD", 40_000.0, 40_010.0),
+            ("ETH-BTC", 0.0502, 0.0503),
+        ]);
+        let report = triangle.evaluate(&quotes, 10.0).unwrap();
+
+        // Implied ETH-BTC is 2000.5 / 40005, the direct mid 0.05025.
+        assert_close(report.implied, 2000.5 / 40_005.0);
+        assert_close(report.direct, 0.05025);
+        assert_close(report.deviation_bps, 48.744064);
+        // Forward: buy ETH at 2001, sell it for BTC at 0.0502, sell BTC at 40000, 10 bps a leg.
+        assert_close(report.forward_edge_bps, 4.907656);
+        // Reverse: buy BTC at 40010, buy ETH at 0.0503, sell ETH at 2000.
+        assert_close(report.reverse_edge_bps, -91.910432);
+        assert_eq!(report.best_edge_bps(), (report.forward_edge_bps, false));
+    }
+
+    #[test]
+    fn triangles_without_a_shared_quote_use_inverse_legs() {
+        let triangles =
+            discover_triangles(&[market("ETH-BTC"), market("BTC-USDC"), market("USDC-ETH")]);
+        assert_eq!(
+            triangles,
+            vec![Triangle {
+                anchor: "USDC".to_string(),
+                cross: market("ETH-BTC"),
+                base_leg: market("USDC-ETH"),
+                quote_leg: market("BTC-USDC"),
+            }]
+        );
+
+        // Consistent prices without spread or fees leave nothing to gain either way.
+        let quotes = quotes(&[
+            ("USDC-ETH", 0.0005, 0.0005),
+            ("BTC-USDC", 40_000.0, 40_000.0),
+            ("ETH-BTC", 0.05, 0.05),
+        ]);
+        let report = triangles[0].evaluate(&quotes, 0.0).unwrap();
+        assert_close(report.implied, 0.05);
+        assert_close(report.deviation_bps, 0.0);
+        assert_close(report.forward_edge_bps, 0.0);
+        assert_close(report.reverse_edge_bps, 0.0);
+    }
+
+    #[test]
+    fn missing_or_empty_books_are_not_evaluated() {
+        let triangle =
+            &discover_triangles(&[market("BTC-USD"), market("ETH-BTC"), market("ETH-USD")])[0];
+        let mut quotes = quotes(&[("ETH-USD", 2000.0, 2001.0), ("BTC-USD", 40_000.0, 40_010.0)]);
+        assert_eq!(triangle.evaluate(&quotes, 10.0), None);
+        quotes.insert(
+            "ETH-BTC".to_string(),
+            TopOfBook {
+                bid: 0.0,
+                ask: 0.0503,
+            },
+        );
+        assert_eq!(triangle.evaluate(&quotes, 10.0), None);
+    }
+}
@@ -0,0 +1,556 @@
+use crate::models::{Candle, MarketTrade}; // Importing the Candle and MarketTrade models.
+use crate::resample::MissingCandles; // Importing the empty interval policy.
+use crate::resample::Timeframe; // Importing timeframes for time bar specs.
+use crate::trade_flow::FlowTrade; // Importing the parsed trade.
+use chrono::{DateTime, Utc}; // Importing chrono types for bar times.
+use std::fmt; // Importing fmt to describe bar rules.
+
+// Unit in which activity is measured for imbalance bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowUnit {
+    Ticks,    // One per trade.
+    Volume,   // Base size.
+    Notional, // Quote value.
+}
+
+impl FlowUnit {
+    // Returns a trade's size in this unit.
+    fn measure(&self, trade: &FlowTrade) -> f64 {
+        match self {
+            FlowUnit::Ticks => 1.0,
+            FlowUnit::Volume => trade.size,
+            FlowUnit::Notional => trade.notional(),
+        }
+    }
+}
+
+// When a bar closes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarRule {
+    // Every `n` seconds of clock time.
+    Time(i64),
+    // Every `n` trades.
+    Tick(usize),
+    // Once `n` base units have traded.
+    Volume(f64),
+    // Once `n` of quote notional has traded.
+    Dollar(f64),
+    // Once buy minus sell activity, in the unit, reaches `threshold` either way.
+    Imbalance { unit: FlowUnit, threshold: f64 },
+}
+
+impl BarRule {
+    // Parses a rule such as 5s, tick:500, volume:10, dollar:1000000, imbalance:50,
+    // volume-imbalance:5 or dollar-imbalance:100000.
+    pub fn parse(spec: &str) -> Option<BarRule> {
+        let spec = spec.trim();
+        let Some((kind, value)) = spec.split_once(':') else {
+            return match Timeframe::parse(spec)? {
+                Timeframe::Fixed(seconds) => Some(BarRule::Time(seconds)),
+                _ => None,
+            };
+        };
+        let threshold: f64 = value.parse().ok().filter(|v: &f64| *v > 0.0)?;
+        let imbalance = |unit| Some(BarRule::Imbalance { unit, threshold });
+        match kind.to_lowercase().as_str() {
+            "time" => BarRule::parse(value),
+            "tick" => value.parse().ok().filter(|n| *n > 0).map(BarRule::Tick),
+            "volume" => Some(BarRule::Volume(threshold)),
+            "dollar" => Some(BarRule::Dollar(threshold)),
+            "imbalance" | "tick-imbalance" => imbalance(FlowUnit::Ticks),
+            "volume-imbalance" => imbalance(FlowUnit::Volume),
+            "dollar-imbalance" => imbalance(FlowUnit::Notional),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BarRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BarRule::Time(seconds) => write!(f, "{}s", seconds),
+            BarRule::Tick(trades) => write!(f, "{} trade", trades),
+            BarRule::Volume(volume) => write!(f, "{} volume", volume),
+            BarRule::Dollar(notional) => write!(f, "{} dollar", notional),
+            BarRule::Imbalance { unit, threshold } => {
+                let unit = match unit {
+                    FlowUnit::Ticks => "tick",
+                    FlowUnit::Volume => "volume",
+                    FlowUnit::Notional => "dollar",
+                };
+                write!(f, "{} {} imbalance", threshold, unit)
+            }
+        }
+    }
+}
+
+// An OHLCV bar built from trades.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeBar {
+    pub start: DateTime<Utc>, // Start of the interval, or time of the first trade.
+    pub end: DateTime<Utc>,   // End of the interval, or time of the last trade.
+    pub open: f64,            // First trade price.
+    pub high: f64,            // Highest trade price.
+    pub low: f64,             // Lowest trade price.
+    pub close: f64,           // Last trade price.
+    pub volume: f64,          // Base volume traded.
+    pub notional: f64,        // Quote volume traded.
+    pub trade_count: usize,   // Number of trades.
+}
+
+impl TradeBar {
+    // Opens a bar with its first trade.
+    pub fn open_with(start: DateTime<Utc>, end: DateTime<Utc>, trade: &FlowTrade) -> Self {
+        TradeBar {
+            start,
+            end,
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: trade.size,
+            notional: trade.notional(),
+            trade_count: 1,
+        }
+    }
+
+    // Opens a bar without trades, flat at the given price.
+    pub fn flat(start: DateTime<Utc>, end: DateTime<Utc>, price: f64) -> Self {
+        TradeBar {
+            start,
+            end,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: 0.0,
+            notional: 0.0,
+            trade_count: 0,
+        }
+    }
+
+    // Adds a trade to the bar.
+    pub fn add(&mut self, trade: &FlowTrade) {
+        if self.trade_count == 0 {
+            self.open = trade.price;
+            self.high = trade.price;
+            self.low = trade.price;
+        } else {
+            self.high = self.high.max(trade.price);
+            self.low = self.low.min(trade.price);
+        }
+        self.close = trade.price;
+        self.volume += trade.size;
+        self.notional += trade.notional();
+        self.trade_count += 1;
+    }
+
+    // Returns the volume weighted average price, or None when nothing traded.
+    pub fn vwap(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.notional / self.volume)
+    }
+
+    // Converts the bar to the API's candle format.
+    pub fn to_candle(&self) -> Candle {
+        Candle {
+            start: self.start.timestamp().to_string(),
+            low: self.low.to_string(),
+            high: self.high.to_string(),
+            open: self.open.to_string(),
+            close: self.close.to_string(),
+            volume: self.volume.to_string(),
+        }
+    }
+}
+
+// Builds time bars of a fixed interval from trades, with a bar that forms as trades arrive.
+//
+// Intervals are aligned to the Unix epoch, so 5s bars start at :00, :05 and so on. Trades are
+// expected in chronological order; a trade before the end of the last emitted bar is counted as
+// late and dropped, because the bar it belongs to has already been emitted.
+#[derive(Debug)]
+pub struct TimeBarBuilder {
+    interval: i64,                        // Bar length in seconds.
+    empty: MissingCandles,                // What to do with intervals without trades.
+    current: Option<TradeBar>,            // Bar still forming.
+    last_close: Option<f64>,              // Close of the last bar, used for flat bars.
+    emitted_until: Option<DateTime<Utc>>, // End of the last emitted bar.
+    late_trades: usize,                   // Trades dropped because their bar was already emitted.
+}
+
+impl TimeBarBuilder {
+    // Creates a builder of bars `interval` seconds long.
+    pub fn new(interval: i64, empty: MissingCandles) -> Self {
+        TimeBarBuilder {
+            interval: interval.max(1),
+            empty,
+            current: None,
+            last_close: None,
+            emitted_until: None,
+            late_trades: 0,
+        }
+    }
+
+    // Adds a trade, returning the bars it completed, oldest first.
+    pub fn push(&mut self, trade: &MarketTrade) -> Vec<TradeBar> {
+        let Some(trade) = FlowTrade::from_market_trade(trade) else {
+            return Vec::new();
+        };
+        let start = self.bucket_start(trade.time);
+        // With no forming bar, e.g. after `advance` closed it and empty intervals are skipped,
+        // only the end of the last emitted bar tells a late trade apart.
+        let late = self.current.as_ref().is_some_and(|bar| start < bar.start)
+            || self.emitted_until.is_some_and(|end| trade.time < end);
+        if late {
+            self.late_trades += 1;
+            return Vec::new();
+        }
+
+        let completed = self.close_until(start);
+        match &mut self.current {
+            Some(bar) if bar.start == start => bar.add(&trade),
+            _ => {
+                self.current = Some(TradeBar::open_with(start, self.bucket_end(start), &trade));
+            }
+        }
+        completed
+    }
+
+    // Closes the bars whose interval ended by `now`, returning them oldest first.
+    //
+    // Call it periodically so bars close, and empty intervals are emitted, while no trade arrives.
+    pub fn advance(&mut self, now: DateTime<Utc>) -> Vec<TradeBar> {
+        let start = self.bucket_start(now);
+        let completed = self.close_until(start);
+        if self.current.is_none() && self.empty == MissingCandles::FillFlat {
+            if let Some(close) = self.last_close {
+                self.current = Some(TradeBar::flat(start, self.bucket_end(start), close));
+            }
+        }
+        completed
+    }
+
+    // Returns the bar still forming, if any.
+    pub fn current(&self) -> Option<&TradeBar> {
+        self.current.as_ref()
+    }
+
+    // Returns the bar length in seconds.
+    pub fn interval(&self) -> i64 {
+        self.interval
+    }
+
+    // Returns the number of trades dropped because they arrived after their bar was emitted.
+    pub fn late_trades(&self) -> usize {
+        self.late_trades
+    }
+
+    // Closes the forming bar and any empty intervals before the bar starting at `start`.
+    fn close_until(&mut self, start: DateTime<Utc>) -> Vec<TradeBar> {
+        let mut completed = Vec::new();
+        let Some(bar) = self.current.take_if(|bar| bar.start < start) else {
+            return completed;
+        };
+        let mut next = bar.end;
+        self.last_close = Some(bar.close);
+        completed.push(bar);
+
+        if self.empty == MissingCandles::FillFlat {
+            while next < start {
+                let close = self.last_close.unwrap_or_default();
+                let end = self.bucket_end(next);
+                completed.push(TradeBar::flat(next, end, close));
+                next = end;
+            }
+        }
+        self.emitted_until = Some(next);
+        completed
+    }
+
+    // Returns the start of the interval holding a time.
+    fn bucket_start(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = time.timestamp().div_euclid(self.interval) * self.interval;
+        DateTime::from_timestamp(seconds, 0).unwrap_or(time)
+    }
+
+    // Returns the end of the interval starting at `start`.
+    fn bucket_end(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        start + chrono::Duration::seconds(self.interval)
+    }
+}
+
+// Builds bars that close on trading activity rather than time.
+//
+// A trade is never split across bars, so a bar closes on the trade that reaches the threshold and
+// may overshoot it. Imbalance bars sign each trade by its taker side and close once the signed
+// flow of the bar reaches a fixed threshold either way, rather than an expected imbalance
+// estimated from earlier bars.
+#[derive(Debug)]
+pub struct ActivityBarBuilder {
+    rule: BarRule,             // Activity rule, never BarRule::Time.
+    current: Option<TradeBar>, // Bar still forming.
+    progress: f64,             // Activity in the forming bar, signed for imbalance bars.
+}
+
+impl ActivityBarBuilder {
+    // Creates a builder for an activity rule.
+    pub fn new(rule: BarRule) -> Self {
+        ActivityBarBuilder {
+            rule,
+            current: None,
+            progress: 0.0,
+        }
+    }
+
+    // Adds a trade, returning the bar it completed, if any.
+    pub fn push(&mut self, trade: &MarketTrade) -> Option<TradeBar> {
+        let trade = FlowTrade::from_market_trade(trade)?;
+        match &mut self.current {
+            Some(bar) => {
+                bar.add(&trade);
+                bar.end = trade.time;
+            }
+            None => self.current = Some(TradeBar::open_with(trade.time, trade.time, &trade)),
+        }
+
+        let done = match self.rule {
+            BarRule::Time(_) => false,
+            BarRule::Tick(trades) => {
+                self.progress += 1.0;
+                self.progress >= trades as f64
+            }
+            BarRule::Volume(volume) => {
+                self.progress += trade.size;
+                self.progress >= volume
+            }
+            BarRule::Dollar(notional) => {
+                self.progress += trade.notional();
+                self.progress >= notional
+            }
+            BarRule::Imbalance { unit, threshold } => {
+                let sign = if trade.is_buy { 1.0 } else { -1.0 };
+                self.progress += sign * unit.measure(&trade);
+                self.progress.abs() >= threshold
+            }
+        };
+        if !done {
+            return None;
+        }
+        self.progress = 0.0;
+        self.current.take()
+    }
+
+    // Returns the bar still forming, if any.
+    pub fn current(&self) -> Option<&TradeBar> {
+        self.current.as_ref()
+    }
+}
+
+// Builds bars of any rule from trades.
+#[derive(Debug)]
+pub enum BarBuilder {
+    Time(TimeBarBuilder),         // Clock-time bars.
+    Activity(ActivityBarBuilder), // Tick, volume, dollar and imbalance bars.
+}
+
+impl BarBuilder {
+    // Creates a builder for a rule, filling empty time intervals as asked.
+    pub fn new(rule: BarRule, empty: MissingCandles) -> Self {
+        match rule {
+            BarRule::Time(seconds) => BarBuilder::Time(TimeBarBuilder::new(seconds, empty)),
+            rule => BarBuilder::Activity(ActivityBarBuilder::new(rule)),
+        }
+    }
+
+    // Adds a trade, returning the bars it completed, oldest first.
+    pub fn push(&mut self, trade: &MarketTrade) -> Vec<TradeBar> {
+        match self {
+            BarBuilder::Time(builder) => builder.push(trade),
+            BarBuilder::Activity(builder) => builder.push(trade).into_iter().collect(),
+        }
+    }
+
+    // Closes time bars whose interval ended by `now`. Activity bars only close on trades.
+    pub fn advance(&mut self, now: DateTime<Utc>) -> Vec<TradeBar> {
+        match self {
+            BarBuilder::Time(builder) => builder.advance(now),
+            BarBuilder::Activity(_) => Vec::new(),
+        }
+    }
+
+    // Returns the bar still forming, if any.
+    pub fn current(&self) -> Option<&TradeBar> {
+        match self {
+            BarBuilder::Time(builder) => builder.current(),
+            BarBuilder::Activity(builder) => builder.current(),
+        }
+    }
+
+    // Returns the number of trades dropped because they arrived after their bar was emitted.
+    pub fn late_trades(&self) -> usize {
+        match self {
+            BarBuilder::Time(builder) => builder.late_trades(),
+            BarBuilder::Activity(_) => 0,
+        }
+    }
+}
+
+// Builds bars from recorded trades, sorted oldest first without duplicate trade IDs.
+//
+// The bar still forming when the trades run out is returned last.
+pub fn build_bars(trades: &[MarketTrade], rule: BarRule, empty: MissingCandles) -> Vec<TradeBar> {
+    let mut trades: Vec<&MarketTrade> = trades.iter().collect();
+    trades.sort_by_key(|trade| {
+        DateTime::parse_from_rfc3339(&trade.time)
+            .map(|time| time.with_timezone(&Utc))
+            .ok()
+    });
+    let mut seen = std::collections::HashSet::new();
+    trades.retain(|trade| seen.insert(trade.trade_id.as_str()));
+
+    let mut builder = BarBuilder::new(rule, empty);
+    let mut bars: Vec<TradeBar> = trades
+        .into_iter()
+        .flat_map(|trade| builder.push(trade))
+        .collect();
+    bars.extend(builder.current().cloned());
+    bars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A buy of one unit at `price`, `second` seconds after the epoch.
+    fn trade(second: i64, price: f64) -> MarketTrade {
+        MarketTrade {
+            trade_id: second.to_string(),
+            product_id: "BTC-USD".to_string(),
+            price: price.to_string(),
+            size: "1".to_string(),
+            time: DateTime::from_timestamp(second, 0).unwrap().to_rfc3339(),
+            side: "BUY".to_string(),
+            bid: None,
+            ask: None,
+        }
+    }
+
+    #[test]
+    fn trades_for_an_emitted_bar_are_late_after_advance() {
+        let mut builder = TimeBarBuilder::new(5, MissingCandles::Skip);
+        assert!(builder.push(&trade(1, 100.0)).is_empty());
+
+        let closed = builder.advance(DateTime::from_timestamp(7, 0).unwrap());
+        assert_eq!(closed.len(), 1);
+        assert!(builder.current().is_none());
+
+        // The bar for 0-5s was emitted, so a trade at 3s must not open it again.
+        assert!(builder.push(&trade(3, 101.0)).is_empty());
+        assert!(builder.current().is_none());
+        assert_eq!(builder.late_trades(), 1);
+
+        assert!(builder.push(&trade(8, 102.0)).is_empty());
+        assert_eq!(builder.current().map(|bar| bar.open), Some(102.0));
+    }
+
+    // A trade of `size` at `price` and `second` seconds, bought or sold by the taker.
+    fn sized(second: i64, price: f64, size: f64, side: &str) -> MarketTrade {
+        MarketTrade {
+            size: size.to_string(),
+            side: side.to_string(),
+            ..trade(second, price)
+        }
+    }
+
+    // Pushes trades into an activity builder, returning the trade counts of the bars closed and
+    // of the bar still forming.
+    fn trade_counts(rule: BarRule, trades: &[MarketTrade]) -> (Vec<usize>, Option<usize>) {
+        let mut builder = ActivityBarBuilder::new(rule);
+        let closed = trades
+            .iter()
+            .filter_map(|trade| builder.push(trade))
+            .map(|bar| bar.trade_count)
+            .collect();
+        (closed, builder.current().map(|bar| bar.trade_count))
+    }
+
+    #[test]
+    fn tick_bars_close_every_n_trades() {
+        let trades: Vec<MarketTrade> = (0..7).map(|i| trade(i, 100.0 + i as f64)).collect();
+        let mut builder = ActivityBarBuilder::new(BarRule::Tick(3));
+        let closed: Vec<TradeBar> = trades.iter().filter_map(|t| builder.push(t)).collect();
+        assert_eq!(closed.len(), 2);
+        assert_eq!((closed[0].open, closed[0].close), (100.0, 102.0));
+        assert_eq!(closed[1].start.timestamp(), 3);
+        assert_eq!(closed[1].end.timestamp(), 5);
+        assert_eq!(builder.current().map(|bar| bar.trade_count), Some(1));
+    }
+
+    #[test]
+    fn volume_bars_close_on_the_trade_reaching_the_threshold() {
+        // 2 + 2 falls short of 5, the third trade overshoots to 7 and closes the bar whole.
+        let trades = [
+            sized(0, 100.0, 2.0, "BUY"),
+            sized(1, 100.0, 2.0, "SELL"),
+            sized(2, 100.0, 3.0, "BUY"),
+            sized(3, 100.0, 5.0, "SELL"),
+            sized(4, 100.0, 1.0, "BUY"),
+        ];
+        assert_eq!(
+            trade_counts(BarRule::Volume(5.0), &trades),
+            (vec![3, 1], Some(1))
+        );
+    }
+
+    #[test]
+    fn dollar_bars_close_on_quote_notional() {
+        // Notionals of 500, 300, 400 and 1000 against a 1000 threshold.
+        let trades = [
+            sized(0, 100.0, 5.0, "BUY"),
+            sized(1, 150.0, 2.0, "BUY"),
+            sized(2, 200.0, 2.0, "SELL"),
+            sized(3, 250.0, 4.0, "SELL"),
+        ];
+        let mut builder = ActivityBarBuilder::new(BarRule::Dollar(1000.0));
+        let closed: Vec<TradeBar> = trades.iter().filter_map(|t| builder.push(t)).collect();
+        assert_eq!(closed.len(), 2);
+        assert_eq!((closed[0].trade_count, closed[0].notional), (3, 1200.0));
+        assert_eq!(closed[0].vwap(), Some(1200.0 / 9.0));
+        assert_eq!((closed[1].trade_count, closed[1].notional), (1, 1000.0));
+        assert!(builder.current().is_none());
+    }
+
+    #[test]
+    fn imbalance_bars_close_on_signed_flow_either_way() {
+        // Tick imbalance with a threshold of 2: +1 -1 +1 +1 closes at +2, then -1 -1 at -2.
+        let sides = ["BUY", "SELL", "BUY", "BUY", "SELL", "SELL", "BUY"];
+        let trades: Vec<MarketTrade> = sides
+            .iter()
+            .enumerate()
+            .map(|(i, side)| sized(i as i64, 100.0, 1.0, side))
+            .collect();
+        let rule = BarRule::parse("imbalance:2").unwrap();
+        assert_eq!(trade_counts(rule, &trades), (vec![4, 2], Some(1)));
+
+        // Volume imbalance: 3 bought and 4 sold nets -1, then 3 more sold reaches -4.
+        let trades = [
+            sized(0, 100.0, 3.0, "BUY"),
+            sized(1, 100.0, 4.0, "SELL"),
+            sized(2, 100.0, 3.0, "SELL"),
+        ];
+        let rule = BarRule::parse("volume-imbalance:4").unwrap();
+        assert_eq!(trade_counts(rule, &trades), (vec![3], None));
+    }
+
+    #[test]
+    fn empty_intervals_are_filled_flat() {
+        let mut builder = TimeBarBuilder::new(5, MissingCandles::FillFlat);
+        builder.push(&trade(1, 100.0));
+        let closed = builder.push(&trade(16, 101.0));
+        let starts: Vec<i64> = closed.iter().map(|bar| bar.start.timestamp()).collect();
+        assert_eq!(starts, [0, 5, 10]);
+        assert_eq!(closed[2].close, 100.0);
+        assert_eq!(closed[2].trade_count, 0);
+        assert_eq!(builder.late_trades(), 0);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::models::ProductBook; // Importing the REST book model.
+use crate::order_book::{parse_levels, BookError, OrderBook}; // Importing the book engine, level parsing and book errors.
+use rust_decimal::prelude::ToPrimitive; // Importing conversions from Decimal to f64.
+use rust_decimal::Decimal; // Importing Decimal for exact prices and sizes.
+
+// Distances from the mid, in basis points, for which cumulative depth is reported.
+pub const DEPTH_BANDS_BPS: [u32; 4] = [10, 25, 50, 100];
+// Number of levels per side used for the imbalance by default.
+pub const DEFAULT_IMBALANCE_LEVELS: usize = 5;
+
+// Cumulative size resting within a distance of the mid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthBand {
+    pub bps: u32,          // Distance from the mid in basis points.
+    pub bid_size: Decimal, // Bid size priced at or above mid - bps.
+    pub ask_size: Decimal, // Ask size priced at or below mid + bps.
+}
+
+// Analytics computed from one order book snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookStats {
+    pub best_bid: Decimal,       // Highest bid price.
+    pub best_ask: Decimal,       // Lowest ask price.
+    pub spread: Decimal,         // Best ask minus best bid.
+    pub spread_bps: f64,         // Spread relative to the mid, in basis points.
+    pub mid: Decimal,            // Average of the best bid and ask.
+    pub microprice: Decimal,     // Top of book prices weighted by the opposite size.
+    pub imbalance_levels: usize, // Number of levels per side used for the imbalance.
+    pub imbalance: f64,          // (bids - asks) / (bids + asks) over the top levels.
+    pub depth: Vec<DepthBand>,   // Cumulative depth within each band of DEPTH_BANDS_BPS.
+    pub skew: f64,               // ln(bid depth / ask depth) within the widest band.
+}
+
+impl ProductBook {
+    // Computes spread, mid, microprice, imbalance over the top `imbalance_levels` levels,
+    // depth bands and skew for this snapshot. Both sides must have at least one level.
+    pub fn analytics(&self, imbalance_levels: usize) -> Result<BookStats, BookError> {
+        let bids = parse_levels(&self.pricebook.bids)?;
+        let asks = parse_levels(&self.pricebook.asks)?;
+        book_stats(&bids, &asks, imbalance_levels)
+    }
+}
+
+impl OrderBook {
+    // Computes the same analytics as `ProductBook::analytics` over every level of the book, so
+    // depth bands are not cut short by a display depth.
+    pub fn analytics(&self, imbalance_levels: usize) -> Result<BookStats, BookError> {
+        book_stats(
+            &self.top_bids(usize::MAX),
+            &self.top_asks(usize::MAX),
+            imbalance_levels,
+        )
+    }
+}
+
+// Computes the analytics from levels sorted best price first on each side.
+pub fn book_stats(
+    bids: &[(Decimal, Decimal)],
+    asks: &[(Decimal, Decimal)],
+    imbalance_levels: usize,
+) -> Result<BookStats, BookError> {
+    let (&(best_bid, bid_size), &(best_ask, ask_size)) = match (bids.first(), asks.first()) {
+        (Some(bid), Some(ask)) => (bid, ask),
+        _ => return Err(BookError::OneSided),
+    };
+
+    let spread = best_ask - best_bid;
+    let mid = (best_bid + best_ask) / Decimal::TWO;
+    let spread_bps = ratio(spread, mid) * 10_000.0;
+    let top_size = bid_size + ask_size;
+    let microprice = if top_size.is_zero() {
+        mid
+    } else {
+        (best_bid * ask_size + best_ask * bid_size) / top_size
+    };
+
+    let top_bids: Decimal = bids.iter().take(imbalance_levels).map(|(_, s)| *s).sum();
+    let top_asks: Decimal = asks.iter().take(imbalance_levels).map(|(_, s)| *s).sum();
+    let imbalance = ratio(top_bids - top_asks, top_bids + top_asks);
+
+    let depth: Vec<DepthBand> = DEPTH_BANDS_BPS
+        .iter()
+        .map(|&bps| {
+            let distance = mid * Decimal::from(bps) / Decimal::from(10_000);
+            DepthBand {
+                bps,
+                bid_size: bids
+                    .iter()
+                    .take_while(|(price, _)| *price >= mid - distance)
+                    .map(|(_, size)| *size)
+                    .sum(),
+                ask_size: asks
+                    .iter()
+                    .take_while(|(price, _)| *price <= mid + distance)
+                    .map(|(_, size)| *size)
+                    .sum(),
+            }
+        })
+        .collect();
+
+    // Skew over the widest band, zero when either side is empty there.
+    let skew = depth
+        .last()
+        .and_then(|band| {
+            let bid = band.bid_size.to_f64()?;
+            let ask = band.ask_size.to_f64()?;
+            (bid > 0.0 && ask > 0.0).then(|| (bid / ask).ln())
+        })
+        .unwrap_or(0.0);
+
+    Ok(BookStats {
+        best_bid,
+        best_ask,
+        spread,
+        spread_bps,
+        mid,
+        microprice,
+        imbalance_levels,
+        imbalance,
+        depth,
+        skew,
+    })
+}
+
+// Divides two decimals as f64, returning zero when the denominator is zero.
+fn ratio(numerator: Decimal, denominator: Decimal) -> f64 {
+    if denominator.is_zero() {
+        return 0.0;
+    }
+    (numerator / denominator).to_f64().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_book::Side;
+
+    #[test]
+    fn order_book_analytics_use_every_level() {
+        let mut book = OrderBook::new("BTC-USD");
+        for offset in 1..=40 {
+            book.set_level(Side::Bid, Decimal::from(10_000 - offset), Decimal::ONE);
+        }
+        book.set_level(Side::Ask, Decimal::from(10_001), Decimal::ONE);
+
+        let stats = book.analytics(DEFAULT_IMBALANCE_LEVELS).unwrap();
+        let widest = stats.depth.last().unwrap();
+
+        assert_eq!(widest.bps, 100);
+        assert_eq!(widest.bid_size, Decimal::from(40));
+        assert_eq!(
+            book.to_product_book(30)
+                .analytics(DEFAULT_IMBALANCE_LEVELS)
+                .unwrap()
+                .depth
+                .last()
+                .unwrap()
+                .bid_size,
+            Decimal::from(30)
+        );
+    }
+}
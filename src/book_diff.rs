@@ -0,0 +1,262 @@
+use crate::models::{ProductBook, ProductBookEntry}; // Importing the REST book models.
+use crate::order_book::{parse_decimal, BookError, OrderBook, Side}; // Importing the book engine, decimal parsing and sides.
+use rust_decimal::Decimal; // Importing Decimal for exact prices and sizes.
+use std::collections::BTreeMap; // Importing BTreeMap to match levels by price.
+use std::fs::{File, OpenOptions}; // Importing file types for the change log.
+use std::io::{self, Write}; // Importing Write for the change log.
+use std::path::{Path, PathBuf}; // Importing path types for the change log location.
+use std::sync::OnceLock; // Importing OnceLock for the configured change log path.
+
+// Where the live book view writes its change log, when requested.
+static CHANGE_LOG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+// How a price level changed between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,     // The level appeared.
+    Removed,   // The level disappeared.
+    Increased, // The size at the level went up.
+    Decreased, // The size at the level went down.
+}
+
+impl ChangeKind {
+    // Returns the name used in the change log.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "add",
+            ChangeKind::Removed => "remove",
+            ChangeKind::Increased => "increase",
+            ChangeKind::Decreased => "decrease",
+        }
+    }
+
+    // Returns true when liquidity was added at the level.
+    pub fn is_size_up(&self) -> bool {
+        matches!(self, ChangeKind::Added | ChangeKind::Increased)
+    }
+}
+
+// A change at one price level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelChange {
+    pub side: Side,                // Side of the level.
+    pub price: Decimal,            // Price of the level.
+    pub old_size: Option<Decimal>, // Size in the previous snapshot, None when added.
+    pub new_size: Option<Decimal>, // Size in the current snapshot, None when removed.
+}
+
+impl LevelChange {
+    // Classifies the change.
+    pub fn kind(&self) -> ChangeKind {
+        match (self.old_size, self.new_size) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            (Some(old), Some(new)) if new > old => ChangeKind::Increased,
+            _ => ChangeKind::Decreased,
+        }
+    }
+
+    // Returns the signed size change.
+    pub fn size_delta(&self) -> Decimal {
+        self.new_size.unwrap_or_default() - self.old_size.unwrap_or_default()
+    }
+}
+
+// Level changes between two snapshots of the same product book.
+#[derive(Debug, Clone, Default)]
+pub struct BookDiff {
+    pub product_id: String,     // ID of the product.
+    pub time: String,           // Time of the current snapshot.
+    pub bids: Vec<LevelChange>, // Bid changes, best price first.
+    pub asks: Vec<LevelChange>, // Ask changes, best price first.
+}
+
+impl BookDiff {
+    // Returns true when nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    // Returns the number of changed levels on both sides.
+    pub fn len(&self) -> usize {
+        self.bids.len() + self.asks.len()
+    }
+
+    // Iterates over the changes on both sides, bids first.
+    pub fn changes(&self) -> impl Iterator<Item = &LevelChange> {
+        self.bids.iter().chain(self.asks.iter())
+    }
+
+    // Returns the change at a price level, if any.
+    pub fn change_at(&self, side: Side, price: Decimal) -> Option<&LevelChange> {
+        let changes = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        changes.iter().find(|change| change.price == price)
+    }
+}
+
+// Compares two snapshots of a product book, level by level on each side.
+pub fn diff_books(previous: &ProductBook, current: &ProductBook) -> Result<BookDiff, BookError> {
+    let mut bids = diff_side(
+        Side::Bid,
+        &levels(&previous.pricebook.bids)?,
+        &levels(&current.pricebook.bids)?,
+    );
+    bids.reverse(); // Bids are best when highest.
+    let asks = diff_side(
+        Side::Ask,
+        &levels(&previous.pricebook.asks)?,
+        &levels(&current.pricebook.asks)?,
+    );
+
+    Ok(BookDiff {
+        product_id: current.pricebook.product_id.clone(),
+        time: current.pricebook.time.clone(),
+        bids,
+        asks,
+    })
+}
+
+// Compares two states of a local order book, level by level on each side, timed now.
+pub fn diff_order_books(previous: &OrderBook, current: &OrderBook) -> BookDiff {
+    let mut bids = diff_side(
+        Side::Bid,
+        previous.levels(Side::Bid),
+        current.levels(Side::Bid),
+    );
+    bids.reverse(); // Bids are best when highest.
+    let asks = diff_side(
+        Side::Ask,
+        previous.levels(Side::Ask),
+        current.levels(Side::Ask),
+    );
+
+    BookDiff {
+        product_id: current.product_id().to_string(),
+        time: chrono::Utc::now().to_rfc3339(),
+        bids,
+        asks,
+    }
+}
+
+// Compares the levels of one side, returning the changes in ascending price order.
+fn diff_side(
+    side: Side,
+    previous: &BTreeMap<Decimal, Decimal>,
+    current: &BTreeMap<Decimal, Decimal>,
+) -> Vec<LevelChange> {
+    let mut prices: Vec<&Decimal> = previous.keys().chain(current.keys()).collect();
+    prices.sort();
+    prices.dedup();
+
+    prices
+        .into_iter()
+        .filter_map(|price| {
+            let old_size = previous.get(price).copied();
+            let new_size = current.get(price).copied();
+            (old_size != new_size).then_some(LevelChange {
+                side,
+                price: *price,
+                old_size,
+                new_size,
+            })
+        })
+        .collect()
+}
+
+// Parses the levels of one side into sizes by price, skipping empty levels.
+fn levels(entries: &[ProductBookEntry]) -> Result<BTreeMap<Decimal, Decimal>, BookError> {
+    let mut levels = BTreeMap::new();
+    for entry in entries {
+        let size = parse_decimal(&entry.size)?;
+        if !size.is_zero() {
+            levels.insert(parse_decimal(&entry.price)?, size);
+        }
+    }
+    Ok(levels)
+}
+
+// Appends book changes as one line per level, to a file or to stdout.
+pub struct BookChangeLog {
+    output: Box<dyn Write + Send>, // Destination of the log lines.
+}
+
+impl BookChangeLog {
+    // Opens the log at the given path for appending, or stdout when the path is "-".
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let output: Box<dyn Write + Send> = if path == Path::new("-") {
+            Box::new(io::stdout())
+        } else {
+            let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+            Box::new(file)
+        };
+        Ok(BookChangeLog { output })
+    }
+
+    // Writes every change of a diff as `time product side kind price old_size new_size`.
+    pub fn write(&mut self, diff: &BookDiff) -> io::Result<()> {
+        let format_size = |size: Option<Decimal>| size.map_or("-".to_string(), |s| s.to_string());
+        for change in diff.changes() {
+            writeln!(
+                self.output,
+                "{} {} {} {} {} {} {}",
+                diff.time,
+                diff.product_id,
+                match change.side {
+                    Side::Bid => "bid",
+                    Side::Ask => "ask",
+                },
+                change.kind().name(),
+                change.price,
+                format_size(change.old_size),
+                format_size(change.new_size)
+            )?;
+        }
+        self.output.flush()
+    }
+}
+
+// Sets the path the live book view writes its change log to. Only the first call takes effect.
+pub fn set_change_log_path(path: PathBuf) {
+    let _ = CHANGE_LOG_PATH.set(path);
+}
+
+// Returns the path of the change log, if one was requested.
+pub fn change_log_path() -> Option<&'static Path> {
+    CHANGE_LOG_PATH.get().map(PathBuf::as_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_local_books_level_by_level() {
+        let mut previous = OrderBook::new("BTC-USD");
+        previous.set_level(Side::Bid, Decimal::from(99), Decimal::ONE);
+        previous.set_level(Side::Bid, Decimal::from(100), Decimal::ONE);
+        previous.set_level(Side::Ask, Decimal::from(101), Decimal::ONE);
+        let mut current = previous.clone();
+        current.set_level(Side::Bid, Decimal::from(99), Decimal::ZERO);
+        current.set_level(Side::Bid, Decimal::from(100), Decimal::TWO);
+        current.set_level(Side::Ask, Decimal::from(102), Decimal::ONE);
+
+        let diff = diff_order_books(&previous, &current);
+
+        let kinds: Vec<_> = diff
+            .changes()
+            .map(|change| (change.side, change.price, change.kind()))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (Side::Bid, Decimal::from(100), ChangeKind::Increased),
+                (Side::Bid, Decimal::from(99), ChangeKind::Removed),
+                (Side::Ask, Decimal::from(102), ChangeKind::Added),
+            ]
+        );
+        assert!(diff_order_books(&current, &current).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::bar_builder::TradeBar; // Importing bars built from trades.
+use crate::indicators::{IndicatorColumn, Ohlcv}; // Importing the candle and indicator series.
+use chrono::{TimeZone, Utc}; // Importing chrono to format candle start times.
+use std::path::Path; // Importing Path to pick a format from a file name.
+
+// File formats candles can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,  // One header line, then one line per candle.
+    Json, // An array with one object per candle.
+}
+
+impl ExportFormat {
+    // Parses a format name.
+    pub fn parse(name: &str) -> Option<ExportFormat> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    // Picks the format from a file extension, defaulting to CSV.
+    pub fn from_path(path: &Path) -> ExportFormat {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(ExportFormat::parse)
+            .unwrap_or(ExportFormat::Csv)
+    }
+}
+
+// Formats candles sorted oldest first, with one extra field per indicator column.
+//
+// Indicator values are empty in CSV and null in JSON during their warm-up.
+pub fn export_candles(bars: &[Ohlcv], columns: &[IndicatorColumn], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Csv => candles_csv(bars, columns),
+        ExportFormat::Json => candles_json(bars, columns),
+    }
+}
+
+// Formats bars built from trades like candles, with their end time, trade count and VWAP.
+pub fn export_bars(bars: &[TradeBar], format: ExportFormat) -> String {
+    let candles: Vec<Ohlcv> = bars
+        .iter()
+        .map(|bar| Ohlcv {
+            start: bar.start.timestamp(),
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+        })
+        .collect();
+    let column = |key: &str, values: Vec<Option<f64>>| IndicatorColumn {
+        key: key.to_string(),
+        label: key.to_string(),
+        values,
+    };
+    let columns = vec![
+        column(
+            "end",
+            bars.iter()
+                .map(|bar| Some(bar.end.timestamp() as f64))
+                .collect(),
+        ),
+        column(
+            "trade_count",
+            bars.iter()
+                .map(|bar| Some(bar.trade_count as f64))
+                .collect(),
+        ),
+        column("vwap", bars.iter().map(TradeBar::vwap).collect()),
+    ];
+    export_candles(&candles, &columns, format)
+}
+
+// Formats the candles as CSV.
+fn candles_csv(bars: &[Ohlcv], columns: &[IndicatorColumn]) -> String {
+    let mut header = vec!["start", "time", "open", "high", "low", "close", "volume"];
+    header.extend(columns.iter().map(|column| column.key.as_str()));
+    let mut lines = vec![header.join(",")];
+
+    for (i, bar) in bars.iter().enumerate() {
+        let mut fields = vec![
+            bar.start.to_string(),
+            format_time(bar.start),
+            bar.open.to_string(),
+            bar.high.to_string(),
+            bar.low.to_string(),
+            bar.close.to_string(),
+            bar.volume.to_string(),
+        ];
+        fields.extend(columns.iter().map(|column| {
+            column
+                .values
+                .get(i)
+                .copied()
+                .flatten()
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        }));
+        lines.push(fields.join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+// Formats the candles as a pretty-printed JSON array.
+fn candles_json(bars: &[Ohlcv], columns: &[IndicatorColumn]) -> String {
+    let rows: Vec<serde_json::Value> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, bar)| {
+            let mut row = serde_json::json!({
+                "start": bar.start,
+                "time": format_time(bar.start),
+                "open": bar.open,
+                "high": bar.high,
+                "low": bar.low,
+                "close": bar.close,
+                "volume": bar.volume,
+            });
+            for column in columns {
+                row[column.key.as_str()] = column
+                    .values
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .map_or(serde_json::Value::Null, json_number);
+            }
+            row
+        })
+        .collect();
+    serde_json::to_string_pretty(&rows).unwrap_or_default() + "\n"
+}
+
+// Converts a value to a JSON number, without a fraction when it is a whole number such as a count.
+fn json_number(value: f64) -> serde_json::Value {
+    if value.fract() == 0.0 && value.abs() < 9e15 {
+        (value as i64).into()
+    } else {
+        value.into()
+    }
+}
+
+// Formats a Unix timestamp as an RFC 3339 time.
+fn format_time(start: i64) -> String {
+    Utc.timestamp_opt(start, 0)
+        .single()
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
@@ -0,0 +1,107 @@
+use crate::models::Candle; // Importing the Candle model.
+use std::collections::VecDeque; // Importing VecDeque for the rolling series.
+
+// What merging a poll changed in the series.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SeriesUpdate {
+    pub updated: Vec<String>,  // Starts of candles whose values changed.
+    pub appended: Vec<String>, // Starts of candles added after the previous last candle.
+}
+
+impl SeriesUpdate {
+    // Returns true when nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.appended.is_empty()
+    }
+}
+
+// The trailing candles of a product, kept up to date from repeated polls.
+//
+// Candles are held oldest first and keyed by their start time. Polls may return candles the
+// series already has, whose values replace the held ones, and newer candles, which are appended
+// while the oldest ones are dropped to keep at most `capacity` candles.
+#[derive(Debug)]
+pub struct CandleSeries {
+    candles: VecDeque<Candle>, // Candles, oldest first.
+    capacity: usize,           // Largest number of candles kept.
+}
+
+impl CandleSeries {
+    // Creates an empty series keeping at most `capacity` candles.
+    pub fn new(capacity: usize) -> Self {
+        CandleSeries {
+            candles: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    // Merges polled candles, in any order, returning what changed.
+    pub fn merge(&mut self, polled: Vec<Candle>) -> SeriesUpdate {
+        let mut polled: Vec<(i64, Candle)> = polled
+            .into_iter()
+            .filter_map(|candle| Some((candle.start.parse().ok()?, candle)))
+            .collect();
+        polled.sort_by_key(|(start, _)| *start);
+
+        let mut update = SeriesUpdate::default();
+        for (start, candle) in polled {
+            match self.position(start) {
+                Ok(i) => {
+                    if !same_values(&self.candles[i], &candle) {
+                        update.updated.push(candle.start.clone());
+                        self.candles[i] = candle;
+                    }
+                }
+                Err(i) if i == self.candles.len() => {
+                    update.appended.push(candle.start.clone());
+                    self.candles.push_back(candle);
+                }
+                // Older than the last candle but missing, e.g. a period without trades that
+                // traded late.
+                Err(i) => {
+                    update.updated.push(candle.start.clone());
+                    self.candles.insert(i, candle);
+                }
+            }
+        }
+        while self.candles.len() > self.capacity {
+            self.candles.pop_front();
+        }
+        update
+    }
+
+    // Returns the candles, oldest first.
+    pub fn candles(&self) -> impl DoubleEndedIterator<Item = &Candle> {
+        self.candles.iter()
+    }
+
+    // Returns the newest candle, which is still forming while its period is open.
+    pub fn last(&self) -> Option<&Candle> {
+        self.candles.back()
+    }
+
+    // Returns the number of candles held.
+    pub fn len(&self) -> usize {
+        self.candles.len()
+    }
+
+    // Returns true when no candle is held.
+    pub fn is_empty(&self) -> bool {
+        self.candles.is_empty()
+    }
+
+    // Finds a candle by start time, or where it would be inserted.
+    fn position(&self, start: i64) -> Result<usize, usize> {
+        self.candles
+            .binary_search_by_key(&start, |candle| candle.start.parse().unwrap_or(i64::MIN))
+    }
+}
+
+// Returns true when two candles hold the same prices and volume.
+fn same_values(a: &Candle, b: &Candle) -> bool {
+    a.open == b.open
+        && a.high == b.high
+        && a.low == b.low
+        && a.close == b.close
+        && a.volume == b.volume
+}
@@ -0,0 +1,286 @@
+use crate::models::Candle; // Importing the Candle model.
+use crate::terminal::{paint, GREEN, RED}; // Importing colour helpers for bullish/bearish candles.
+use chrono::{TimeZone, Utc}; // Importing chrono utilities to format candle timestamps.
+
+// Width reserved on the left of the chart for the price and volume axis labels.
+const AXIS_WIDTH: usize = 14;
+// Characters used to draw fractional volume bars, from empty to full.
+const VOLUME_BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// Number of columns between two time axis labels.
+const TIME_LABEL_SPACING: usize = 16;
+// Number of rows between two price axis labels.
+const PRICE_LABEL_SPACING: usize = 4;
+// Smallest number of rows the price area can be drawn with.
+const MIN_PRICE_ROWS: usize = 4;
+
+// Numeric representation of a candle used for plotting.
+#[derive(Debug, Clone, Copy)]
+struct ChartCandle {
+    start: i64,  // Start time as a Unix timestamp.
+    open: f64,   // Opening price.
+    high: f64,   // Highest price.
+    low: f64,    // Lowest price.
+    close: f64,  // Closing price.
+    volume: f64, // Traded volume.
+}
+
+impl ChartCandle {
+    // Parses a raw API candle, returning None if any field is not numeric.
+    fn parse(candle: &Candle) -> Option<Self> {
+        Some(ChartCandle {
+            start: candle.start.parse().ok()?,
+            open: candle.open.parse().ok()?,
+            high: candle.high.parse().ok()?,
+            low: candle.low.parse().ok()?,
+            close: candle.close.parse().ok()?,
+            volume: candle.volume.parse().ok()?,
+        })
+    }
+
+    // Folds the following candle into this one so several candles share a single column.
+    fn merge(&mut self, next: &ChartCandle) {
+        self.high = self.high.max(next.high);
+        self.low = self.low.min(next.low);
+        self.close = next.close;
+        self.volume += next.volume;
+    }
+
+    // Returns true when the candle closed at or above its open.
+    fn is_bullish(&self) -> bool {
+        self.close >= self.open
+    }
+
+    // Returns the colour used to draw this candle.
+    fn colour(&self) -> &'static str {
+        if self.is_bullish() {
+            GREEN
+        } else {
+            RED
+        }
+    }
+}
+
+// Renders candles as a Unicode candlestick chart with a price axis, a volume histogram and
+// time axis labels, scaled to fit the given width and height in terminal cells.
+pub fn render_candlestick_chart(candles: &[Candle], width: usize, height: usize) -> Vec<String> {
+    // Parse and order candles oldest first, since the API returns them newest first.
+    let mut parsed: Vec<ChartCandle> = candles.iter().filter_map(ChartCandle::parse).collect();
+    parsed.sort_by_key(|c| c.start);
+    if parsed.is_empty() {
+        return vec!["No candle data to chart.".to_string()];
+    }
+
+    // Split the available height between the header, price area, volume area and time axis.
+    let volume_rows = (height / 6).max(3);
+    let price_rows = height.saturating_sub(1 + volume_rows + 2);
+    let plot_width = width.saturating_sub(AXIS_WIDTH + 1);
+    if price_rows < MIN_PRICE_ROWS || plot_width == 0 {
+        return vec!["Terminal is too small to draw the chart.".to_string()];
+    }
+
+    // Merge neighbouring candles when there are more candles than columns.
+    let per_column = parsed.len().div_ceil(plot_width);
+    let columns: Vec<ChartCandle> = parsed
+        .chunks(per_column)
+        .map(|chunk| {
+            let mut merged = chunk[0];
+            for next in &chunk[1..] {
+                merged.merge(next);
+            }
+            merged
+        })
+        .collect();
+
+    let max_price = columns.iter().map(|c| c.high).fold(f64::MIN, f64::max);
+    let min_price = columns.iter().map(|c| c.low).fold(f64::MAX, f64::min);
+    let precision = price_precision(max_price);
+
+    let mut lines = Vec::with_capacity(height);
+    lines.push(render_header(
+        &parsed, per_column, max_price, min_price, precision,
+    ));
+    lines.extend(render_price_area(
+        &columns, price_rows, max_price, min_price, precision,
+    ));
+    lines.extend(render_volume_area(&columns, volume_rows));
+    lines.extend(render_time_axis(&columns));
+    lines
+}
+
+// Builds the summary line shown above the chart.
+fn render_header(
+    candles: &[ChartCandle],
+    per_column: usize,
+    max_price: f64,
+    min_price: f64,
+    precision: usize,
+) -> String {
+    let last_close = candles.last().map(|c| c.close).unwrap_or_default();
+    let mut header = format!(
+        "Candles: {}  High: {:.*}  Low: {:.*}  Last close: {:.*}",
+        candles.len(),
+        precision,
+        max_price,
+        precision,
+        min_price,
+        precision,
+        last_close
+    );
+    if per_column > 1 {
+        header.push_str(&format!("  ({} candles per column)", per_column));
+    }
+    header
+}
+
+// Draws the candle bodies and wicks together with the price axis.
+fn render_price_area(
+    columns: &[ChartCandle],
+    rows: usize,
+    max_price: f64,
+    min_price: f64,
+    precision: usize,
+) -> Vec<String> {
+    // Avoid a zero-height range when every price is identical.
+    let range = if max_price > min_price {
+        max_price - min_price
+    } else {
+        max_price.abs().max(1.0) * 0.001
+    };
+    let step = range / rows as f64;
+
+    (0..rows)
+        .map(|row| {
+            let top = max_price - row as f64 * step;
+            let bottom = top - step;
+
+            // Label every few rows, plus the last row when it is not next to another label.
+            let last_row = row == rows - 1 && row % PRICE_LABEL_SPACING > 1;
+            let mut line = if row % PRICE_LABEL_SPACING == 0 || last_row {
+                format!(
+                    "{:>12} ┤",
+                    format!("{:.*}", precision, (top + bottom) / 2.0)
+                )
+            } else {
+                format!("{:>12} │", "")
+            };
+
+            for candle in columns {
+                let body_high = candle.open.max(candle.close);
+                let body_low = candle.open.min(candle.close);
+                let symbol = if body_high >= bottom && body_low <= top {
+                    "┃"
+                } else if candle.high >= bottom && candle.low <= top {
+                    "│"
+                } else {
+                    " "
+                };
+                if symbol == " " {
+                    line.push(' ');
+                } else {
+                    line.push_str(&paint(symbol, candle.colour()));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+// Draws the volume histogram below the price area.
+fn render_volume_area(columns: &[ChartCandle], rows: usize) -> Vec<String> {
+    let max_volume = columns.iter().map(|c| c.volume).fold(0.0, f64::max);
+
+    (0..rows)
+        .map(|row| {
+            let mut line = if row == 0 {
+                format!("{:>12} ┤", format_volume(max_volume))
+            } else if row == rows - 1 {
+                format!("{:>12} │", "Volume")
+            } else {
+                format!("{:>12} │", "")
+            };
+
+            // Each row holds eight vertical steps of the bar.
+            let floor = (rows - 1 - row) as f64 * 8.0;
+            for candle in columns {
+                let units = if max_volume > 0.0 {
+                    candle.volume / max_volume * rows as f64 * 8.0
+                } else {
+                    0.0
+                };
+                let level = (units - floor).round().clamp(0.0, 8.0) as usize;
+                let block = VOLUME_BLOCKS[level];
+                if level == 0 {
+                    line.push(block);
+                } else {
+                    line.push_str(&paint(&block.to_string(), candle.colour()));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+// Draws the time axis line with tick marks and the labels underneath.
+fn render_time_axis(columns: &[ChartCandle]) -> Vec<String> {
+    let first = columns.first().map(|c| c.start).unwrap_or_default();
+    let last = columns.last().map(|c| c.start).unwrap_or_default();
+    let column_seconds = if columns.len() > 1 {
+        (last - first) / (columns.len() as i64 - 1)
+    } else {
+        0
+    };
+
+    // Pick a label format that matches the time span being displayed.
+    let label_format = if column_seconds >= 86_400 {
+        "%Y-%m-%d"
+    } else if last - first >= 86_400 {
+        "%m-%d %H:%M"
+    } else {
+        "%H:%M"
+    };
+
+    let mut axis = format!("{:>12} └", "");
+    let mut labels = " ".repeat(AXIS_WIDTH);
+    for (i, candle) in columns.iter().enumerate() {
+        if i % TIME_LABEL_SPACING == 0 {
+            axis.push('┴');
+            let label = Utc
+                .timestamp_opt(candle.start, 0)
+                .single()
+                .map(|t| t.format(label_format).to_string())
+                .unwrap_or_default();
+            // Only place the label if it fits before the end of the chart.
+            if i + label.len() <= columns.len() {
+                let padding = (AXIS_WIDTH + i).saturating_sub(labels.len());
+                labels.push_str(&" ".repeat(padding));
+                labels.push_str(&label);
+            }
+        } else {
+            axis.push('─');
+        }
+    }
+
+    vec![axis, labels]
+}
+
+// Chooses how many decimals to show for prices of the given magnitude.
+pub fn price_precision(max_price: f64) -> usize {
+    if max_price >= 1000.0 {
+        2
+    } else if max_price >= 1.0 {
+        4
+    } else {
+        8
+    }
+}
+
+// Formats a volume in a compact form for the axis label.
+fn format_volume(volume: f64) -> String {
+    if volume >= 1_000_000.0 {
+        format!("{:.2}M", volume / 1_000_000.0)
+    } else if volume >= 1_000.0 {
+        format!("{:.2}K", volume / 1_000.0)
+    } else {
+        format!("{:.4}", volume)
+    }
+}
@@ -1,7 +1,11 @@
+use crate::terminal::is_plain; // Importing the output mode check.
 use std::io::{self, Write};
 
-// Function to clear the screen
+// Function to clear the screen, skipped in plain mode so redirected output stays append-only
 pub fn clear_screen() {
+    if is_plain() {
+        return;
+    }
     print!("\x1B[2J\x1B[1;1H");
     io::stdout().flush().unwrap();
 }
@@ -0,0 +1,494 @@
+use crate::candle_export::ExportFormat; // Importing the candle export formats.
+use crate::config::Config; // Importing the config holding the arbitrage settings.
+use crate::correlation::Method; // Importing the correlation methods.
+use crate::handlers::arbitrage_handler::{arbitrage_handler, parse_bps, parse_currencies}; // Importing the arbitrage monitor.
+use crate::handlers::candles_handler::{candles_command, CandlesOptions}; // Importing the candles command.
+use crate::handlers::conversion_handler::{
+    apply_requested_currency, convert_command, parse_convert_amount,
+}; // Importing the convert command and the reporting currency.
+use crate::handlers::correlation_handler::correlation_command; // Importing the correlation command.
+use crate::handlers::market_overview_handler::{overview_command, DEFAULT_TOP}; // Importing the overview command.
+use crate::handlers::order_validation_handler::validate_order_command; // Importing the validate-order command.
+use crate::handlers::screener_handler::{delete_screen, list_screens, screen_command}; // Importing the screen command.
+use crate::handlers::slippage_handler::estimate_slippage; // Importing the slippage command.
+use crate::handlers::trade_bars_handler::{bars_command, parse_bar_rule}; // Importing the bars command.
+use crate::handlers::volatility_handler::volatility_command; // Importing the vol command.
+use crate::indicators::Indicator; // Importing the technical indicators.
+use crate::market_data::Transport; // Importing the transport selection.
+use crate::order_validation::{OrderRequest, OrderType}; // Importing the order validator types.
+use crate::resample::{parse_zone, MissingCandles, Resampling, Timeframe, Zone}; // Importing candle resampling.
+use crate::slippage::{OrderAmount, OrderSide}; // Importing the order types.
+use crate::task_runner::run_until_ctrl_c; // Importing the Ctrl+C runner for the monitor.
+use crate::trade_flow::parse_windows; // Importing the trade-flow window parser.
+use crate::volatility::Estimator; // Importing the volatility estimators.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use rust_decimal::Decimal; // Importing Decimal for order sizes.
+use std::collections::HashMap; // Importing HashMap for command flags.
+use std::path::{Path, PathBuf}; // Importing Path and PathBuf for file arguments.
+use std::str::FromStr; // Importing FromStr to parse decimals.
+
+// Usage of the commands that run without the menu.
+const USAGE: &str = "commands:
+  arbitrage [--currencies LIST] [--fee-bps F] [--threshold-bps T] [--rate N]
+      monitor implied versus direct cross rates and triangular round-trip edges until Ctrl+C
+  bars <trades_file> <rule> [--fill] [--format csv|json] [--output F]
+      build time, tick, volume, dollar or imbalance bars from recorded trades
+  candles <product_id> <start> <end> <granularity> [--indicators LIST] [--resample TF [--tz ZONE] [--fill]] [--format csv|json] [--output F]
+      print candles with indicator columns as a table, CSV or JSON, optionally resampled to a timeframe such as 4h, 1w or 1M
+  convert <amount> <from> <to>
+      convert an amount between two currencies through the products linking them, showing the path
+  correlation <start> <end> <granularity> <product_id>... [--benchmark P] [--method pearson|spearman] [--csv]
+      correlate the products' log returns and measure their beta against a benchmark, the first product by default
+  overview [--top N]
+      summarise the market: top movers, volume leaders, new and restricted products, volume breakdowns
+  screen [<filter>] [--sort KEYS] [--limit N] [--name NAME] [--save NAME] | --list | --delete NAME
+      filter and sort products by an expression such as 'quote=USD and volume_24h > 1e6', or run a saved screen
+  slippage <product_id> <buy|sell> <size> [--quote]
+      estimate a market order against the current book
+  validate-order <product_id> <buy|sell> <market|limit> <size> [--price P] [--quote] [--post-only] [--product-file F] [--json]
+      round an order to the product's increments and report the rules it breaks
+  vol <product_id>... [--granularity G] [--estimator LIST] [--window N] [--csv]
+      print annualised 1d, 7d, 30d and 90d realized volatility by each estimator, or over a rolling window of N candles";
+// Usage of the arbitrage command.
+const ARBITRAGE_USAGE: &str =
+    "arbitrage [--currencies LIST] [--fee-bps F] [--threshold-bps T] [--rate N]";
+// Usage of the bars command.
+const BARS_USAGE: &str = "bars <trades_file> <rule> [--fill] [--format csv|json] [--output F]";
+// Usage of the candles command.
+const CANDLES_USAGE: &str = "candles <product_id> <start> <end> <granularity> [--indicators LIST] [--resample TF [--tz ZONE] [--fill]] [--format csv|json] [--output F]";
+// Usage of the convert command.
+const CONVERT_USAGE: &str = "convert <amount> <from> <to>";
+// Usage of the correlation command.
+const CORRELATION_USAGE: &str = "correlation <start> <end> <granularity> <product_id>... [--benchmark P] [--method pearson|spearman] [--csv]";
+// Usage of the screen command.
+const SCREEN_USAGE: &str = "screen [<filter>] [--sort KEYS] [--limit N] [--name NAME] [--save NAME] | --list | --delete NAME";
+// Usage of the validate-order command.
+const VALIDATE_ORDER_USAGE: &str = "validate-order <product_id> <buy|sell> <market|limit> <size> [--price P] [--quote] [--post-only] [--product-file F] [--json]";
+
+// Usage of the vol command.
+const VOL_USAGE: &str =
+    "vol <product_id>... [--granularity G] [--estimator LIST] [--window N] [--csv]";
+
+// Options and command parsed from the command line.
+#[derive(Debug, Default)]
+pub struct Options {
+    pub plain: bool,                    // Plain output requested with --plain.
+    pub transport: Option<Transport>,   // Live data transport chosen with --transport.
+    pub book_changes: Option<PathBuf>,  // Book change log path chosen with --book-changes.
+    pub currency: Option<String>,       // Reporting currency chosen with --currency.
+    pub flow_windows: Option<Vec<u64>>, // Trade-flow window lengths chosen with --flow-windows.
+    pub command: Vec<String>,           // Command and its arguments, empty to show the menu.
+}
+
+// Parses the command line arguments, without the program name.
+pub fn parse_args(args: &[String]) -> Result<Options> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--plain" => options.plain = true,
+            "--transport" => {
+                let name = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--transport expects rest or websocket"))?;
+                options.transport = Some(Transport::parse(name).ok_or_else(|| {
+                    anyhow!("unknown transport {}, expected rest or websocket", name)
+                })?);
+            }
+            "--book-changes" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--book-changes expects a file path or -"))?;
+                options.book_changes = Some(PathBuf::from(path));
+            }
+            "--currency" => {
+                let currency = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--currency expects a currency, e.g. EUR"))?;
+                options.currency = Some(currency.to_uppercase());
+            }
+            "--flow-windows" => {
+                let windows = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--flow-windows expects a list, e.g. 30s,5m,1h"))?;
+                options.flow_windows = Some(parse_windows(windows).ok_or_else(|| {
+                    anyhow!(
+                        "invalid trade-flow windows {}, expected e.g. 30s,5m,1h",
+                        windows
+                    )
+                })?);
+            }
+            _ => options.command.push(arg.clone()),
+        }
+    }
+    Ok(options)
+}
+
+// Runs a command given on the command line.
+pub async fn run_command(command: &[String]) -> Result<()> {
+    match command.first().map(String::as_str) {
+        Some("arbitrage") => {
+            let args = CommandArgs::parse(
+                &command[1..],
+                &["--currencies", "--fee-bps", "--threshold-bps", "--rate"],
+            )?;
+            if !args.positional.is_empty() {
+                return Err(anyhow!("usage: {}", ARBITRAGE_USAGE));
+            }
+            // Flags override the settings saved in the config file.
+            let mut settings = Config::load()?.arbitrage;
+            if let Some(list) = args.value("--currencies") {
+                settings.currencies = parse_currencies(list);
+            }
+            if let Some(fee) = args.value("--fee-bps") {
+                settings.fee_bps = parse_bps(fee)?;
+            }
+            if let Some(threshold) = args.value("--threshold-bps") {
+                settings.threshold_bps = threshold
+                    .parse()
+                    .map_err(|_| anyhow!("invalid threshold {}", threshold))?;
+            }
+            if let Some(rate) = args.value("--rate") {
+                settings.requests_per_second = rate
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|rate| *rate > 0.0 && rate.is_finite())
+                    .ok_or_else(|| anyhow!("invalid request rate {}", rate))?;
+            }
+            run_until_ctrl_c(|rx| arbitrage_handler(rx, settings)).await
+        }
+        Some("bars") => {
+            let args = CommandArgs::parse(&command[1..], &["--format", "--output"])?;
+            let [trades_file, rule] = args.positional[..] else {
+                return Err(anyhow!("usage: {}", BARS_USAGE));
+            };
+            let format = match args.value("--format") {
+                Some(name) => ExportFormat::parse(name)
+                    .ok_or_else(|| anyhow!("unknown format {}, expected csv or json", name))?,
+                None => ExportFormat::Csv,
+            };
+            let empty = if args.has("--fill") {
+                MissingCandles::FillFlat
+            } else {
+                MissingCandles::Skip
+            };
+            let output = args.value("--output").map(PathBuf::from);
+            bars_command(
+                Path::new(trades_file),
+                parse_bar_rule(rule)?,
+                empty,
+                format,
+                output.as_deref(),
+            )
+        }
+        Some("candles") => {
+            let args = CommandArgs::parse(
+                &command[1..],
+                &["--indicators", "--resample", "--tz", "--format", "--output"],
+            )?;
+            let [product_id, start, end, granularity] = args.positional[..] else {
+                return Err(anyhow!("usage: {}", CANDLES_USAGE));
+            };
+            if args.value("--resample").is_none() && (args.has("--tz") || args.has("--fill")) {
+                return Err(anyhow!("--tz and --fill need --resample"));
+            }
+            let options = CandlesOptions {
+                indicators: parse_indicators(args.value("--indicators").unwrap_or(""))?,
+                resampling: args
+                    .value("--resample")
+                    .map(|timeframe| {
+                        parse_resampling(timeframe, args.value("--tz"), args.has("--fill"))
+                    })
+                    .transpose()?,
+                format: args
+                    .value("--format")
+                    .map(|name| {
+                        ExportFormat::parse(name)
+                            .ok_or_else(|| anyhow!("unknown format {}, expected csv or json", name))
+                    })
+                    .transpose()?,
+                output: args.value("--output").map(PathBuf::from),
+            };
+            // Exports stay in the quote currency, only the table shows reporting prices.
+            if options.format.is_none() && options.output.is_none() {
+                apply_requested_currency().await?;
+            }
+            candles_command(product_id, start, end, granularity, &options).await
+        }
+        Some("convert") => {
+            let args = CommandArgs::parse(&command[1..], &[])?;
+            let [amount, from, to] = args.positional[..] else {
+                return Err(anyhow!("usage: {}", CONVERT_USAGE));
+            };
+            convert_command(parse_convert_amount(amount)?, from, to).await
+        }
+        Some("correlation") => {
+            let args = CommandArgs::parse(&command[1..], &["--benchmark", "--method"])?;
+            let [start, end, granularity, ref products @ ..] = args.positional[..] else {
+                return Err(anyhow!("usage: {}", CORRELATION_USAGE));
+            };
+            if products.len() < 2 {
+                return Err(anyhow!(
+                    "at least two products are needed\nusage: {}",
+                    CORRELATION_USAGE
+                ));
+            }
+            let mut product_ids: Vec<String> = products.iter().map(|id| id.to_string()).collect();
+            let benchmark = args.value("--benchmark").unwrap_or(products[0]);
+            // A benchmark outside the list is fetched too, so it shows in the matrix.
+            if !product_ids.iter().any(|id| id == benchmark) {
+                product_ids.push(benchmark.to_string());
+            }
+            let method = match args.value("--method") {
+                Some(name) => Method::parse(name).ok_or_else(|| {
+                    anyhow!("unknown method {}, expected pearson or spearman", name)
+                })?,
+                None => Method::Pearson,
+            };
+            correlation_command(
+                &product_ids,
+                start,
+                end,
+                granularity,
+                benchmark,
+                method,
+                args.has("--csv"),
+            )
+            .await
+        }
+        Some("overview") => {
+            let args = CommandArgs::parse(&command[1..], &["--top"])?;
+            if !args.positional.is_empty() {
+                return Err(anyhow!("usage: overview [--top N]"));
+            }
+            let top = match args.value("--top") {
+                Some(top) => top
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|top| *top > 0)
+                    .ok_or_else(|| anyhow!("invalid count {}", top))?,
+                None => DEFAULT_TOP,
+            };
+            apply_requested_currency().await?;
+            overview_command(top).await
+        }
+        Some("screen") => {
+            let args = CommandArgs::parse(
+                &command[1..],
+                &["--sort", "--limit", "--name", "--save", "--delete"],
+            )?;
+            if args.positional.len() > 1 {
+                return Err(anyhow!(
+                    "quote the filter as one argument\nusage: {}",
+                    SCREEN_USAGE
+                ));
+            }
+            if args.has("--list") {
+                return list_screens();
+            }
+            if let Some(name) = args.value("--delete") {
+                return delete_screen(name);
+            }
+            apply_requested_currency().await?;
+            screen_command(
+                args.positional.first().copied(),
+                args.value("--sort"),
+                args.value("--limit"),
+                args.value("--name"),
+                args.value("--save"),
+            )
+            .await
+        }
+        Some("slippage") => {
+            let args = CommandArgs::parse(&command[1..], &[])?;
+            let [product_id, side, size] = args.positional[..] else {
+                return Err(anyhow!(
+                    "usage: slippage <product_id> <buy|sell> <size> [--quote]"
+                ));
+            };
+            let side = parse_side(side)?;
+            let size = parse_amount(size)?;
+            let amount = if args.has("--quote") {
+                OrderAmount::Quote(size)
+            } else {
+                OrderAmount::Base(size)
+            };
+            apply_requested_currency().await?;
+            estimate_slippage(product_id, side, amount).await
+        }
+        Some("validate-order") => {
+            let args = CommandArgs::parse(&command[1..], &["--price", "--product-file"])?;
+            let [product_id, side, order_type, size] = args.positional[..] else {
+                return Err(anyhow!("usage: {}", VALIDATE_ORDER_USAGE));
+            };
+            let size = parse_amount(size)?;
+            let request = OrderRequest {
+                side: parse_side(side)?,
+                order_type: OrderType::parse(order_type).ok_or_else(|| {
+                    anyhow!(
+                        "unknown order type {}, expected market or limit",
+                        order_type
+                    )
+                })?,
+                amount: if args.has("--quote") {
+                    OrderAmount::Quote(size)
+                } else {
+                    OrderAmount::Base(size)
+                },
+                limit_price: args.value("--price").map(parse_amount).transpose()?,
+                post_only: args.has("--post-only"),
+            };
+            let product_file = args.value("--product-file").map(PathBuf::from);
+            validate_order_command(
+                product_id,
+                &request,
+                product_file.as_deref(),
+                args.has("--json"),
+            )
+            .await
+        }
+        Some("vol") => {
+            let args =
+                CommandArgs::parse(&command[1..], &["--granularity", "--estimator", "--window"])?;
+            if args.positional.is_empty() {
+                return Err(anyhow!("usage: {}", VOL_USAGE));
+            }
+            let window = args
+                .value("--window")
+                .map(|window| {
+                    window
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|window| *window >= 2)
+                        .ok_or_else(|| {
+                            anyhow!("invalid window {}, expected at least 2 candles", window)
+                        })
+                })
+                .transpose()?;
+            let product_ids: Vec<String> =
+                args.positional.iter().map(|id| id.to_string()).collect();
+            let estimators = match args.value("--estimator") {
+                Some(list) => parse_estimators(list)?,
+                None => Estimator::ALL.to_vec(),
+            };
+            volatility_command(
+                &product_ids,
+                args.value("--granularity").unwrap_or("ONE_HOUR"),
+                &estimators,
+                window,
+                args.has("--csv"),
+            )
+            .await
+        }
+        Some(other) => Err(anyhow!("unknown command {}\n{}", other, USAGE)),
+        None => Err(anyhow!("{}", USAGE)),
+    }
+}
+
+// Positional arguments and flags of a command.
+struct CommandArgs<'a> {
+    positional: Vec<&'a str>,                 // Arguments that are not flags.
+    flags: HashMap<&'a str, Option<&'a str>>, // Flags, with their value when they take one.
+}
+
+impl<'a> CommandArgs<'a> {
+    // Splits arguments into positional arguments and flags, reading a value after each of `value_flags`.
+    fn parse(args: &'a [String], value_flags: &[&str]) -> Result<Self> {
+        let mut positional = Vec::new();
+        let mut flags = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                positional.push(arg.as_str());
+            } else if value_flags.contains(&arg.as_str()) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow!("{} expects a value", arg))?;
+                flags.insert(arg.as_str(), Some(value.as_str()));
+            } else {
+                flags.insert(arg.as_str(), None);
+            }
+        }
+        Ok(CommandArgs { positional, flags })
+    }
+
+    // Returns true when the flag was given.
+    fn has(&self, flag: &str) -> bool {
+        self.flags.contains_key(flag)
+    }
+
+    // Returns the value given after a flag.
+    fn value(&self, flag: &str) -> Option<&'a str> {
+        self.flags.get(flag).copied().flatten()
+    }
+}
+
+// Parses an order side argument.
+pub fn parse_side(side: &str) -> Result<OrderSide> {
+    OrderSide::parse(side).ok_or_else(|| anyhow!("unknown side {}, expected buy or sell", side))
+}
+
+// Parses a positive size or notional argument.
+pub fn parse_amount(amount: &str) -> Result<Decimal> {
+    let value = Decimal::from_str(amount).map_err(|_| anyhow!("invalid amount {}", amount))?;
+    if value <= Decimal::ZERO {
+        return Err(anyhow!("amount must be positive, got {}", amount));
+    }
+    Ok(value)
+}
+
+// Parses a comma-separated list of indicator specs, e.g. sma:20,rsi:14,obv.
+pub fn parse_indicators(list: &str) -> Result<Vec<Indicator>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+        .map(|spec| Indicator::parse(spec).ok_or_else(|| anyhow!("invalid indicator {}", spec)))
+        .collect()
+}
+
+// Parses a comma-separated list of volatility estimators, e.g. close,parkinson,yz.
+pub fn parse_estimators(list: &str) -> Result<Vec<Estimator>> {
+    let estimators: Vec<Estimator> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            Estimator::parse(name).ok_or_else(|| {
+                anyhow!(
+                    "unknown estimator {}, expected close, parkinson, gk, rs or yz",
+                    name
+                )
+            })
+        })
+        .collect::<Result<_>>()?;
+    if estimators.is_empty() {
+        return Err(anyhow!("--estimator expects at least one estimator"));
+    }
+    Ok(estimators)
+}
+
+// Parses the resampling arguments: a timeframe, an optional time zone and whether to fill gaps.
+pub fn parse_resampling(timeframe: &str, zone: Option<&str>, fill: bool) -> Result<Resampling> {
+    Ok(Resampling {
+        timeframe: Timeframe::parse(timeframe).ok_or_else(|| {
+            anyhow!(
+                "invalid timeframe {}, expected e.g. 4h, 1d, 1w or 1M",
+                timeframe
+            )
+        })?,
+        zone: match zone {
+            Some(zone) => parse_zone(zone).ok_or_else(|| {
+                anyhow!(
+                    "invalid time zone {}, expected e.g. +09:00 or Europe/Paris",
+                    zone
+                )
+            })?,
+            None => Zone::utc(),
+        },
+        missing: if fill {
+            MissingCandles::FillFlat
+        } else {
+            MissingCandles::Skip
+        },
+    })
+}
@@ -0,0 +1,64 @@
+use crate::arbitrage::ArbitrageSettings; // Importing the triangular arbitrage settings.
+use crate::screener::Screen; // Importing the saved product screens.
+use crate::trade_flow::TradeFlowSettings; // Importing the trade-flow settings.
+use anyhow::{anyhow, Context, Result}; // Importing anyhow utilities for error handling.
+use serde::{Deserialize, Serialize}; // Importing serde traits to (de)serialize the config file.
+use std::collections::BTreeMap; // Importing BTreeMap to keep named entries sorted.
+use std::fs; // Importing filesystem utilities.
+use std::path::PathBuf; // Importing PathBuf for the config file location.
+
+// Environment variable that overrides the config file location.
+const CONFIG_PATH_ENV: &str = "COINBASE_API_CLIENT_CONFIG";
+
+// Struct to represent the user settings persisted between sessions.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub watchlists: BTreeMap<String, Vec<String>>, // Named watchlists of product IDs.
+    #[serde(default)]
+    pub product_columns: Vec<String>, // Columns shown in the products browser.
+    #[serde(default)]
+    pub screens: BTreeMap<String, Screen>, // Named product screens.
+    #[serde(default)]
+    pub trade_flow: TradeFlowSettings, // Windows and thresholds of the trade-flow panel.
+    #[serde(default)]
+    pub arbitrage: ArbitrageSettings, // Fee, threshold and request rate of the arbitrage monitor.
+    #[serde(default)]
+    pub reporting_currency: Option<String>, // Currency the price views report in, if any.
+}
+
+impl Config {
+    // Loads the config from disk, returning the defaults when no file exists yet.
+    pub fn load() -> Result<Config> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("reading config file {}", path.display()))?;
+        let config = serde_json::from_str(&contents)
+            .with_context(|| format!("parsing config file {}", path.display()))?;
+        Ok(config)
+    }
+
+    // Writes the config to disk, creating the parent directory if needed.
+    pub fn save(&self) -> Result<()> {
+        let path = config_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(&path, contents)
+            .with_context(|| format!("writing config file {}", path.display()))?;
+        Ok(())
+    }
+}
+
+// Returns the location of the config file.
+fn config_path() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os(CONFIG_PATH_ENV) {
+        return Ok(PathBuf::from(path));
+    }
+    let dir = dirs::config_dir().ok_or_else(|| anyhow!("could not find a config directory"))?;
+    Ok(dir.join("coinbase_api_client").join("config.json"))
+}
@@ -0,0 +1,431 @@
+use crate::models::{Candle, Product}; // Importing the Candle and Product models.
+use std::collections::{BTreeMap, HashMap}; // Importing maps for the currency graph and rates.
+use std::sync::RwLock; // Importing RwLock for the reporting currency shared by every view.
+
+// Currency the price views report in, with the rates to convert each product's prices.
+static REPORTING: RwLock<Option<Reporting>> = RwLock::new(None);
+
+// One way to exchange a currency for another through a product.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionStep {
+    pub product_id: String, // Product traded, e.g. BTC-USD.
+    pub from: String,       // Currency given.
+    pub to: String,         // Currency received.
+    pub rate: f64,          // Units of `to` per unit of `from`.
+    volume: f64,            // Approximate 24h quote volume of the product, as reported.
+}
+
+// Chain of products leading from one currency to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionPath {
+    pub from: String,               // Starting currency.
+    pub to: String,                 // Final currency.
+    pub steps: Vec<ConversionStep>, // Exchanges in order, empty when both currencies are the same.
+}
+
+impl ConversionPath {
+    // Returns the units of `to` per unit of `from` along the path.
+    pub fn rate(&self) -> f64 {
+        self.steps.iter().map(|step| step.rate).product()
+    }
+
+    // Returns the currencies visited, from the first to the last.
+    pub fn currencies(&self) -> Vec<&str> {
+        let mut currencies = vec![self.from.as_str()];
+        currencies.extend(self.steps.iter().map(|step| step.to.as_str()));
+        currencies
+    }
+}
+
+// Graph of currencies linked by the spot products trading them, weighted by their prices.
+//
+// Each product gives an edge from its base to its quote currency at its mid-market price, or its
+// last price when no mid is reported, and the reverse edge at the inverse rate.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionGraph {
+    edges: BTreeMap<String, Vec<ConversionStep>>, // Exchanges leaving each currency.
+    quotes: HashMap<String, String>,              // Quote currency of every listed product.
+}
+
+impl ConversionGraph {
+    // Builds the graph from the online spot products with a positive price.
+    pub fn from_products(products: &[Product]) -> Self {
+        let mut graph = ConversionGraph::default();
+        for product in products {
+            if !product.quote_currency_id.is_empty() {
+                graph.quotes.insert(
+                    product.product_id.clone(),
+                    product.quote_currency_id.to_uppercase(),
+                );
+            }
+            if !is_convertible(product) {
+                continue;
+            }
+            let Some(price) = weight(product) else {
+                continue;
+            };
+            let volume = product
+                .approximate_quote_24h_volume
+                .parse()
+                .ok()
+                .filter(|volume: &f64| volume.is_finite())
+                .unwrap_or(0.0);
+            let base = product.base_currency_id.to_uppercase();
+            let quote = product.quote_currency_id.to_uppercase();
+            graph.add_edge(&product.product_id, &base, &quote, price, volume);
+            graph.add_edge(&product.product_id, &quote, &base, 1.0 / price, volume);
+        }
+        graph
+    }
+
+    // Adds a one-way exchange between two currencies.
+    fn add_edge(&mut self, product_id: &str, from: &str, to: &str, rate: f64, volume: f64) {
+        self.edges.entry(to.to_string()).or_default();
+        self.edges
+            .entry(from.to_string())
+            .or_default()
+            .push(ConversionStep {
+                product_id: product_id.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+                rate,
+                volume,
+            });
+    }
+
+    // Returns the currencies in the graph, sorted.
+    pub fn currencies(&self) -> impl Iterator<Item = &str> {
+        self.edges.keys().map(String::as_str)
+    }
+
+    // Returns true when a product trades the currency.
+    pub fn has_currency(&self, currency: &str) -> bool {
+        self.edges.contains_key(&currency.to_uppercase())
+    }
+
+    // Returns the quote currency of a listed product.
+    pub fn quote_currency(&self, product_id: &str) -> Option<&str> {
+        self.quotes.get(product_id).map(String::as_str)
+    }
+
+    // Finds the best path between two currencies: the fewest exchanges, and among paths as
+    // short, the one whose least traded product has the highest 24h quote volume.
+    //
+    // Volumes are compared as reported, in each product's own quote currency, so the tie-break
+    // is only a rough preference for liquid products.
+    pub fn path(&self, from: &str, to: &str) -> Option<ConversionPath> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        if from == to {
+            return Some(ConversionPath {
+                from,
+                to,
+                steps: Vec::new(),
+            });
+        }
+        if !self.edges.contains_key(&from) || !self.edges.contains_key(&to) {
+            return None;
+        }
+
+        // Breadth-first by number of exchanges. Every currency one exchange further out is
+        // reached from all currencies of the current layer before the next layer starts, so it
+        // keeps the widest of its shortest paths.
+        let mut depth: HashMap<&str, usize> = HashMap::from([(from.as_str(), 0)]);
+        let mut best: HashMap<&str, (f64, &ConversionStep)> = HashMap::new();
+        let mut layer = vec![from.as_str()];
+        let mut distance = 0;
+        while !layer.is_empty() && !depth.contains_key(to.as_str()) {
+            let mut next = Vec::new();
+            for currency in &layer {
+                let width = best
+                    .get(currency)
+                    .map_or(f64::INFINITY, |(width, _)| *width);
+                for step in &self.edges[*currency] {
+                    let reached = width.min(step.volume);
+                    match depth.get(step.to.as_str()) {
+                        None => {
+                            depth.insert(&step.to, distance + 1);
+                            best.insert(&step.to, (reached, step));
+                            next.push(step.to.as_str());
+                        }
+                        Some(d) if *d == distance + 1 && reached > best[step.to.as_str()].0 => {
+                            best.insert(&step.to, (reached, step));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            layer = next;
+            distance += 1;
+        }
+
+        let mut steps = Vec::new();
+        let mut currency = to.as_str();
+        while currency != from {
+            let (_, step) = best.get(currency)?;
+            steps.push((*step).clone());
+            currency = &step.from;
+        }
+        steps.reverse();
+        Some(ConversionPath { from, to, steps })
+    }
+
+    // Returns the units of `to` one unit of `from` is worth along the best path.
+    pub fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        self.path(from, to).map(|path| path.rate())
+    }
+
+    // Converts an amount of `from` into `to` along the best path.
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        self.rate(from, to).map(|rate| amount * rate)
+    }
+}
+
+// Returns true for online spot products whose price is a usable exchange rate.
+fn is_convertible(product: &Product) -> bool {
+    product.status.eq_ignore_ascii_case("online")
+        && product.product_type.eq_ignore_ascii_case("spot")
+        && !product.is_disabled
+        && !product.base_currency_id.is_empty()
+        && !product.quote_currency_id.is_empty()
+        && !product
+            .base_currency_id
+            .eq_ignore_ascii_case(&product.quote_currency_id)
+}
+
+// Returns the mid-market price of a product, or its last price when no mid is reported.
+fn weight(product: &Product) -> Option<f64> {
+    let positive = |price: &str| {
+        price
+            .parse::<f64>()
+            .ok()
+            .filter(|price| *price > 0.0 && price.is_finite())
+    };
+    positive(&product.mid_market_price).or_else(|| positive(&product.price))
+}
+
+// Reporting currency with the rate from every reachable currency into it.
+struct Reporting {
+    currency: String,                // Currency the views report in.
+    quotes: HashMap<String, String>, // Quote currency of every listed product.
+    rates: HashMap<String, f64>,     // Units of the reporting currency per unit of each currency.
+}
+
+// Makes every price view report in `currency`, converting with rates from the graph.
+//
+// Returns false, leaving the current setting as it is, when no product trades the currency.
+pub fn set_reporting_currency(graph: &ConversionGraph, currency: &str) -> bool {
+    let currency = currency.to_uppercase();
+    if !graph.has_currency(&currency) {
+        return false;
+    }
+    let rates = graph
+        .currencies()
+        .filter_map(|from| Some((from.to_string(), graph.rate(from, &currency)?)))
+        .collect();
+    *REPORTING.write().unwrap() = Some(Reporting {
+        currency,
+        quotes: graph.quotes.clone(),
+        rates,
+    });
+    true
+}
+
+// Makes the price views show each product's prices in its own quote currency again.
+pub fn clear_reporting_currency() {
+    *REPORTING.write().unwrap() = None;
+}
+
+// Returns the currency the price views report in, if one is set.
+pub fn reporting_currency() -> Option<String> {
+    REPORTING
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|reporting| reporting.currency.clone())
+}
+
+// Converts a price of a product into the reporting currency.
+//
+// Returns None when no reporting currency is set, or when the product or its quote currency
+// cannot be converted.
+pub fn to_reporting(price: f64, product_id: &str) -> Option<f64> {
+    let reporting = REPORTING.read().unwrap();
+    let reporting = reporting.as_ref()?;
+    let quote = reporting.quotes.get(product_id)?;
+    Some(price * reporting.rates.get(quote)?)
+}
+
+// Formats a price of a product for display in the reporting currency.
+//
+// The price is returned as given when no reporting currency is set or it is not a number, and
+// followed by its quote currency when it cannot be converted, so it is never mistaken for a
+// converted value.
+pub fn display_price(price: &str, product_id: &str) -> String {
+    let Ok(value) = price.parse::<f64>() else {
+        return price.to_string();
+    };
+    let reporting = REPORTING.read().unwrap();
+    let Some(reporting) = reporting.as_ref() else {
+        return price.to_string();
+    };
+    let Some(quote) = reporting.quotes.get(product_id) else {
+        return price.to_string();
+    };
+    match reporting.rates.get(quote) {
+        Some(rate) => format_amount(value * rate),
+        None => format!("{} {}", price, quote),
+    }
+}
+
+// Returns a product's candles with their prices in the reporting currency, volumes staying in
+// the base currency.
+//
+// Returns None when no reporting currency is set or the product cannot be converted.
+pub fn candles_to_reporting(candles: &[Candle], product_id: &str) -> Option<Vec<Candle>> {
+    let rate = to_reporting(1.0, product_id)?;
+    let convert = |price: &str| match price.parse::<f64>() {
+        Ok(price) => format_amount(price * rate),
+        Err(_) => price.to_string(),
+    };
+    Some(
+        candles
+            .iter()
+            .map(|candle| Candle {
+                start: candle.start.clone(),
+                low: convert(&candle.low),
+                high: convert(&candle.high),
+                open: convert(&candle.open),
+                close: convert(&candle.close),
+                volume: candle.volume.clone(),
+            })
+            .collect(),
+    )
+}
+
+// Formats a converted amount with about six significant digits and at least two decimals.
+pub fn format_amount(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format!("{:.2}", value);
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    let decimals = (5 - magnitude).clamp(2, 10) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+// Returns a column or field label, naming the reporting currency when one is set.
+pub fn price_header(label: &str) -> String {
+    match reporting_currency() {
+        Some(currency) => format!("{} ({})", label, currency),
+        None => label.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds a listed product with its 24h quote volume.
+    fn product(product_id: &str, price: &str, volume: &str) -> Product {
+        let mut product = Product::for_test(product_id, price);
+        product.approximate_quote_24h_volume = volume.to_string();
+        product
+    }
+
+    // ETH reaches EUR in two exchanges through BTC or USD, and JPY only trades against DOGE.
+    fn graph() -> ConversionGraph {
+        let mut offline = product("SOL-USD", "150", "5000");
+        offline.status = "offline".to_string();
+        ConversionGraph::from_products(&[
+            product("ETH-USD", "2000", "1000"),
+            product("ETH-BTC", "0.05", "50"),
+            product("BTC-USD", "40000", "1000000"),
+            product("BTC-EUR", "36000", "2000"),
+            product("EUR-USD", "1.1", "10"),
+            product("DOGE-JPY", "20", "100"),
+            offline,
+        ])
+    }
+
+    fn product_ids(path: &ConversionPath) -> Vec<&str> {
+        path.steps
+            .iter()
+            .map(|step| step.product_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn the_fewest_exchanges_win_over_volume() {
+        // Through USD both products trade more than ETH-BTC, but that takes two exchanges.
+        let path = graph().path("ETH", "BTC").unwrap();
+        assert_eq!(product_ids(&path), ["ETH-BTC"]);
+        assert_eq!(path.rate(), 0.05);
+    }
+
+    #[test]
+    fn equally_short_paths_keep_the_widest_least_traded_product() {
+        // Through BTC the least traded product has 50 of volume, through USD only 10, even
+        // though ETH-USD alone trades far more than ETH-BTC.
+        let path = graph().path("eth", "eur").unwrap();
+        assert_eq!(path.currencies(), ["ETH", "BTC", "EUR"]);
+        assert_eq!(product_ids(&path), ["ETH-BTC", "BTC-EUR"]);
+        assert!((path.rate() - 1800.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn quote_to_base_exchanges_use_the_inverse_price() {
+        let path = graph().path("EUR", "ETH").unwrap();
+        assert_eq!(path.currencies(), ["EUR", "BTC", "ETH"]);
+        assert_eq!(product_ids(&path), ["BTC-EUR", "ETH-BTC"]);
+        assert!((path.rate() - 1.0 / 1800.0).abs() < 1e-15);
+        assert!((graph().convert(3600.0, "EUR", "ETH").unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_same_currency_needs_no_exchange() {
+        let path = graph().path("usd", "USD").unwrap();
+        assert!(path.steps.is_empty());
+        assert_eq!(path.currencies(), ["USD"]);
+        assert_eq!(path.rate(), 1.0);
+    }
+
+    #[test]
+    fn unreachable_and_unknown_currencies_have_no_path() {
+        let graph = graph();
+        assert_eq!(graph.path("ETH", "JPY"), None);
+        assert_eq!(graph.path("ETH", "XYZ"), None);
+        // Offline products give no exchange, though their quote currency is still known.
+        assert!(!graph.has_currency("SOL"));
+        assert_eq!(graph.quote_currency("SOL-USD"), Some("USD"));
+    }
+
+    // Every check on the reporting currency lives in one test, as it is shared process-wide.
+    #[test]
+    fn prices_display_in_the_reporting_currency() {
+        let graph = graph();
+        assert!(!set_reporting_currency(&graph, "XYZ"));
+        assert_eq!(display_price("2000", "ETH-USD"), "2000");
+
+        assert!(set_reporting_currency(&graph, "eur"));
+        assert_eq!(reporting_currency().as_deref(), Some("EUR"));
+        assert_eq!(display_price("2000", "ETH-USD"), "1818.18");
+        // JPY cannot reach EUR, so the price keeps its quote currency.
+        assert_eq!(display_price("0.5", "DOGE-JPY"), "0.5 JPY");
+        assert_eq!(display_price("2000", "UNKNOWN-USD"), "2000");
+        assert_eq!(display_price("n/a", "ETH-USD"), "n/a");
+        assert_eq!(price_header("Price"), "Price (EUR)");
+
+        clear_reporting_currency();
+        assert_eq!(display_price("2000", "ETH-USD"), "2000");
+        assert_eq!(price_header("Price"), "Price");
+    }
+
+    #[test]
+    fn amounts_keep_about_six_significant_digits() {
+        assert_eq!(format_amount(0.0), "0.00");
+        assert_eq!(format_amount(43210.987), "43210.99");
+        assert_eq!(format_amount(1.5), "1.50000");
+        assert_eq!(format_amount(0.000123456789), "0.000123457");
+        assert_eq!(format_amount(-12.345678), "-12.3457");
+        assert_eq!(format_amount(f64::NAN), "NaN");
+    }
+}
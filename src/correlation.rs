@@ -0,0 +1,332 @@
+use crate::indicators::parse_candles; // Importing candle parsing.
+use crate::models::Candle; // Importing the Candle model.
+use std::collections::BTreeMap; // Importing BTreeMap to align candles on start time.
+use std::fmt; // Importing fmt to describe errors.
+
+// Smallest number of aligned returns a report is computed from.
+pub const MIN_OBSERVATIONS: usize = 3;
+
+// Correlation measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Pearson,  // Linear correlation of the returns.
+    Spearman, // Correlation of the return ranks.
+}
+
+impl Method {
+    // Parses a method name.
+    pub fn parse(name: &str) -> Option<Method> {
+        match name.to_lowercase().as_str() {
+            "pearson" => Some(Method::Pearson),
+            "spearman" => Some(Method::Spearman),
+            _ => None,
+        }
+    }
+
+    // Returns the display name of the method.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::Pearson => "Pearson",
+            Method::Spearman => "Spearman",
+        }
+    }
+}
+
+// Why a correlation report cannot be computed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorrelationError {
+    // The benchmark is not one of the products.
+    UnknownBenchmark(String),
+    // Too few candle times are shared by every product.
+    NotEnoughData { observations: usize },
+}
+
+impl fmt::Display for CorrelationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorrelationError::UnknownBenchmark(product_id) => {
+                write!(f, "benchmark {} is not one of the products", product_id)
+            }
+            CorrelationError::NotEnoughData { observations } => write!(
+                f,
+                "only {} aligned return(s), at least {} are needed",
+                observations, MIN_OBSERVATIONS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CorrelationError {}
+
+// Correlation and beta figures for a set of products.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationReport {
+    pub product_ids: Vec<String>,        // Products, in the order given.
+    pub benchmark: String,               // Product the betas are measured against.
+    pub observations: usize,             // Number of aligned log returns.
+    pub pearson: Vec<Vec<Option<f64>>>,  // Pearson correlation of each pair.
+    pub spearman: Vec<Vec<Option<f64>>>, // Spearman correlation of each pair.
+    pub betas: Vec<Option<f64>>,         // Beta of each product against the benchmark.
+}
+
+impl CorrelationReport {
+    // Returns the matrix for a method.
+    pub fn matrix(&self, method: Method) -> &Vec<Vec<Option<f64>>> {
+        match method {
+            Method::Pearson => &self.pearson,
+            Method::Spearman => &self.spearman,
+        }
+    }
+}
+
+// Aligns the products' candles on the start times they all share, computes close-to-close log
+// returns and correlates them pairwise.
+//
+// Only consecutive shared candles give a return, so a candle missing for any product removes
+// the return into and out of it for every product. Returns are None when a product's returns
+// do not vary.
+pub fn correlation_report(
+    candles: &[(String, Vec<Candle>)],
+    benchmark: &str,
+) -> Result<CorrelationReport, CorrelationError> {
+    let benchmark_index = candles
+        .iter()
+        .position(|(product_id, _)| product_id == benchmark)
+        .ok_or_else(|| CorrelationError::UnknownBenchmark(benchmark.to_string()))?;
+
+    let returns = aligned_log_returns(candles);
+    let observations = returns.first().map_or(0, Vec::len);
+    if observations < MIN_OBSERVATIONS {
+        return Err(CorrelationError::NotEnoughData { observations });
+    }
+
+    let ranks: Vec<Vec<f64>> = returns.iter().map(|series| rank(series)).collect();
+    let pairwise = |series: &[Vec<f64>]| -> Vec<Vec<Option<f64>>> {
+        series
+            .iter()
+            .map(|a| series.iter().map(|b| pearson(a, b)).collect())
+            .collect()
+    };
+    Ok(CorrelationReport {
+        product_ids: candles.iter().map(|(id, _)| id.clone()).collect(),
+        benchmark: benchmark.to_string(),
+        observations,
+        pearson: pairwise(&returns),
+        spearman: pairwise(&ranks),
+        betas: returns
+            .iter()
+            .map(|series| beta(series, &returns[benchmark_index]))
+            .collect(),
+    })
+}
+
+// Returns each product's log returns between consecutive candle times shared by every product.
+pub fn aligned_log_returns(candles: &[(String, Vec<Candle>)]) -> Vec<Vec<f64>> {
+    // Closes by start time, kept only for times every product has.
+    let mut closes: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+    for (_, series) in candles {
+        for bar in parse_candles(series) {
+            closes.entry(bar.start).or_default().push(bar.close);
+        }
+    }
+    let shared: Vec<(i64, Vec<f64>)> = closes
+        .into_iter()
+        .filter(|(_, values)| values.len() == candles.len())
+        .collect();
+
+    // Candle length, taken as the smallest gap between shared times.
+    let step = shared
+        .windows(2)
+        .map(|pair| pair[1].0 - pair[0].0)
+        .min()
+        .unwrap_or(0);
+
+    let mut returns = vec![Vec::new(); candles.len()];
+    for pair in shared.windows(2) {
+        let ((previous_start, previous), (start, current)) = (&pair[0], &pair[1]);
+        if start - previous_start != step {
+            continue; // A candle is missing in between.
+        }
+        if previous.iter().chain(current).any(|close| *close <= 0.0) {
+            continue;
+        }
+        for (i, series) in returns.iter_mut().enumerate() {
+            series.push((current[i] / previous[i]).ln());
+        }
+    }
+    returns
+}
+
+// Pearson correlation of two equally long series, None when either does not vary.
+pub fn pearson(a: &[f64], b: &[f64]) -> Option<f64> {
+    let covariance = covariance(a, b)?;
+    let deviation = (variance(a)? * variance(b)?).sqrt();
+    (deviation > 0.0).then(|| (covariance / deviation).clamp(-1.0, 1.0))
+}
+
+// Spearman rank correlation of two equally long series, with ties given their average rank.
+pub fn spearman(a: &[f64], b: &[f64]) -> Option<f64> {
+    pearson(&rank(a), &rank(b))
+}
+
+// Beta of a series against a benchmark: their covariance over the benchmark's variance.
+pub fn beta(series: &[f64], benchmark: &[f64]) -> Option<f64> {
+    let variance = variance(benchmark)?;
+    (variance > 0.0).then(|| covariance(series, benchmark).unwrap_or(0.0) / variance)
+}
+
+// Sample covariance of two equally long series.
+fn covariance(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+    let n = a.len() as f64;
+    let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+    let sum: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| (x - mean_a) * (y - mean_b))
+        .sum();
+    Some(sum / (n - 1.0))
+}
+
+// Sample variance of a series.
+fn variance(a: &[f64]) -> Option<f64> {
+    covariance(a, a)
+}
+
+// Ranks values from 1, giving tied values the average of their ranks.
+fn rank(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let average = (i + j) as f64 / 2.0 + 1.0;
+        for &k in &order[i..=j] {
+            ranks[k] = average;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds a product's candles from (start, close) pairs.
+    fn series(product_id: &str, closes: &[(i64, f64)]) -> (String, Vec<Candle>) {
+        let candles = closes
+            .iter()
+            .map(|(start, close)| Candle {
+                start: start.to_string(),
+                low: close.to_string(),
+                high: close.to_string(),
+                open: close.to_string(),
+                close: close.to_string(),
+                volume: "1".to_string(),
+            })
+            .collect();
+        (product_id.to_string(), candles)
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-12, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn returns_skip_gaps_in_the_shared_candles() {
+        // BTC has no candle at 180, so neither product has a return from 120 to 240; the
+        // candle at 360 only BTC has is dropped too.
+        let candles = [
+            series(
+                "BTC-USD",
+                &[
+                    (60, 110.0),
+                    (0, 100.0),
+                    (120, 121.0),
+                    (180, 50.0),
+                    (240, 100.0),
+                    (300, 200.0),
+                    (360, 400.0),
+                ],
+            ),
+            series(
+                "ETH-USD",
+                &[(0, 10.0), (60, 20.0), (120, 40.0), (240, 10.0), (300, 5.0)],
+            ),
+        ];
+        let returns = aligned_log_returns(&candles);
+        let (up, double, half) = (1.1f64.ln(), 2f64.ln(), 0.5f64.ln());
+        assert_close(&returns[0], &[up, up, double]);
+        assert_close(&returns[1], &[double, double, half]);
+    }
+
+    #[test]
+    fn returns_skip_non_positive_closes() {
+        let candles = [
+            series(
+                "BTC-USD",
+                &[(0, 100.0), (60, 0.0), (120, 100.0), (180, 200.0)],
+            ),
+            series(
+                "ETH-USD",
+                &[(0, 10.0), (60, 10.0), (120, 10.0), (180, 20.0)],
+            ),
+        ];
+        let returns = aligned_log_returns(&candles);
+        assert_close(&returns[0], &[2f64.ln()]);
+        assert_close(&returns[1], &[2f64.ln()]);
+    }
+
+    #[test]
+    fn ties_share_their_average_rank() {
+        assert_eq!(rank(&[3.0, 1.0, 3.0, 2.0]), [3.5, 1.0, 3.5, 2.0]);
+        assert_eq!(rank(&[5.0, 5.0, 5.0]), [2.0, 2.0, 2.0]);
+        // Ranks 1, 2.5, 2.5, 4 against 1, 3, 2, 4 correlate at 4.5 / sqrt(4.5 * 5).
+        let rho = spearman(&[1.0, 2.0, 2.0, 3.0], &[1.0, 3.0, 2.0, 4.0]).unwrap();
+        assert!((rho - 0.9f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn spearman_sees_monotonic_relations_pearson_does_not() {
+        let (a, b) = ([1.0, 2.0, 3.0, 4.0], [1.0, 8.0, 27.0, 64.0]);
+        assert!((spearman(&a, &b).unwrap() - 1.0).abs() < 1e-12);
+        assert!(pearson(&a, &b).unwrap() < 0.99);
+        assert_eq!(pearson(&a, &[2.0; 4]), None);
+    }
+
+    #[test]
+    fn beta_is_covariance_over_benchmark_variance() {
+        // Deviations 5, -25, 25, -5 against 5, -15, 15, -5 (in thousandths): 800 / 500.
+        let beta_value = beta(&[0.01, -0.02, 0.03, 0.0], &[0.01, -0.01, 0.02, 0.0]).unwrap();
+        assert!((beta_value - 1.6).abs() < 1e-12);
+        let doubled = beta(&[3.0, 5.0, 7.0, 9.0], &[1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert!((doubled - 2.0).abs() < 1e-12);
+        assert_eq!(beta(&[1.0, 2.0, 3.0], &[1.0, 1.0, 1.0]), None);
+    }
+
+    #[test]
+    fn reports_need_the_benchmark_and_enough_returns() {
+        let candles = [
+            series("BTC-USD", &[(0, 100.0), (60, 110.0), (120, 99.0)]),
+            series("ETH-USD", &[(0, 10.0), (60, 12.0), (120, 11.0)]),
+        ];
+        assert_eq!(
+            correlation_report(&candles, "SOL-USD"),
+            Err(CorrelationError::UnknownBenchmark("SOL-USD".to_string()))
+        );
+        assert_eq!(
+            correlation_report(&candles, "BTC-USD"),
+            Err(CorrelationError::NotEnoughData { observations: 2 })
+        );
+    }
+}
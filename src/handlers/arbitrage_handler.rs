@@ -0,0 +1,298 @@
+use crate::api::product_book::fetch_product_book_depth; // Importing the product book endpoint.
+use crate::api::products::fetch_products; // Importing the fetch_products function.
+use crate::arbitrage::{
+    discover_triangles, tradable_markets, ArbitrageSettings, TopOfBook, Triangle, TriangleReport,
+}; // Importing the triangular arbitrage analytics.
+use crate::models::ProductBookEntry; // Importing the book level model.
+use crate::terminal::{highlight, is_plain, print_timestamped, prompt, terminal_size}; // Importing output helpers.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use chrono::Utc; // Importing Utc to time the rounds.
+use std::collections::{BTreeSet, HashMap}; // Importing collections for the polled books.
+use std::time::{Duration, Instant}; // Importing time types for the redraw interval.
+use tokio::sync::watch; // Importing watch from tokio for synchronization.
+use tokio::time::{self, MissedTickBehavior}; // Importing tokio time utilities for the request pacing.
+
+// Shortest time between two redraws of the table.
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+// Prompts for the currencies, fee and threshold, starting from the saved settings.
+pub fn prompt_for_arbitrage(saved: ArbitrageSettings) -> Result<ArbitrageSettings> {
+    let mut settings = saved;
+    let currencies = prompt(&format!(
+        "Enter the currencies to use, e.g. USD,BTC,ETH (leave empty for {}): ",
+        if settings.currencies.is_empty() {
+            "all".to_string()
+        } else {
+            settings.currencies.join(",")
+        }
+    ));
+    if !currencies.is_empty() {
+        settings.currencies = parse_currencies(&currencies);
+    }
+    let fee = prompt(&format!(
+        "Enter the fee per leg in bps (leave empty for {}): ",
+        settings.fee_bps
+    ));
+    if !fee.is_empty() {
+        settings.fee_bps = parse_bps(&fee)?;
+    }
+    let threshold = prompt(&format!(
+        "Enter the edge to highlight in bps (leave empty for {}): ",
+        settings.threshold_bps
+    ));
+    if !threshold.is_empty() {
+        settings.threshold_bps = threshold
+            .parse()
+            .map_err(|_| anyhow!("invalid threshold {}", threshold))?;
+    }
+    Ok(settings)
+}
+
+// A market's best bid and ask with the time it was fetched.
+type Quote = (TopOfBook, Instant);
+
+// Builds the currency graph from the products, then polls the books of every triangle's markets
+// at a limited request rate and shows the implied and direct cross prices and round-trip edges.
+//
+// A triangle's legs are polled back to back, reusing legs fetched for an earlier triangle within
+// half the largest quote age, and only quotes younger than that age price a triangle, so its
+// legs are close together in time. A failed request drops the market's quote.
+pub async fn arbitrage_handler(
+    mut stop: watch::Receiver<()>,
+    settings: ArbitrageSettings,
+) -> Result<()> {
+    let products = fetch_products().await?;
+    let markets = tradable_markets(&products, &settings.currencies);
+    let triangles = discover_triangles(&markets);
+    if triangles.is_empty() {
+        println!("No triangles found between the tradable markets.");
+        return Ok(());
+    }
+    let books = triangles
+        .iter()
+        .flat_map(|triangle| triangle.product_ids())
+        .collect::<BTreeSet<&str>>()
+        .len();
+
+    let rate = settings.requests_per_second.max(0.1);
+    let mut pacing = time::interval(Duration::from_secs_f64(1.0 / rate));
+    pacing.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let max_age = Duration::from_secs_f64(settings.max_quote_age_secs.max(0.1));
+    let mut quotes: HashMap<String, Quote> = HashMap::new();
+    let mut failures = 0usize;
+    let mut round = 0usize;
+    let mut last_draw = Instant::now() - REDRAW_INTERVAL;
+
+    loop {
+        round += 1;
+        let mut fetched = false;
+        for triangle in &triangles {
+            for product_id in triangle.product_ids() {
+                if quotes
+                    .get(product_id)
+                    .is_some_and(|(_, fetched_at)| fetched_at.elapsed() < max_age / 2)
+                {
+                    continue;
+                }
+                if paced(&mut stop, &mut pacing).await {
+                    return Ok(());
+                }
+                fetched = true;
+                match fetch_top_of_book(product_id).await {
+                    Ok(Some(book)) => {
+                        quotes.insert(product_id.to_string(), (book, Instant::now()));
+                    }
+                    Ok(None) => {
+                        quotes.remove(product_id);
+                    }
+                    Err(_) => {
+                        quotes.remove(product_id);
+                        failures += 1;
+                    }
+                }
+            }
+            if !is_plain() && last_draw.elapsed() >= REDRAW_INTERVAL {
+                let fresh = fresh_quotes(&quotes, max_age);
+                let status = Status {
+                    round,
+                    books,
+                    quoted: fresh.len(),
+                    failures,
+                };
+                display_arbitrage(&triangles, &fresh, &settings, &status);
+                last_draw = Instant::now();
+            }
+        }
+        if is_plain() {
+            display_arbitrage_plain(
+                &triangles,
+                &fresh_quotes(&quotes, max_age),
+                &settings,
+                round,
+            );
+        }
+        // Every quote was recent enough to reuse, so wait a request interval before the next round.
+        if !fetched && paced(&mut stop, &mut pacing).await {
+            return Ok(());
+        }
+    }
+}
+
+// Waits for the next request slot, returning true when the handler is stopped first.
+async fn paced(stop: &mut watch::Receiver<()>, pacing: &mut time::Interval) -> bool {
+    tokio::select! {
+        _ = stop.changed() => {
+            println!("Stopping arbitrage_handler");
+            true
+        }
+        _ = pacing.tick() => false,
+    }
+}
+
+// Returns the quotes fetched within `max_age`, leaving out those too old to price a triangle.
+fn fresh_quotes(quotes: &HashMap<String, Quote>, max_age: Duration) -> HashMap<String, TopOfBook> {
+    quotes
+        .iter()
+        .filter(|(_, (_, fetched_at))| fetched_at.elapsed() <= max_age)
+        .map(|(product_id, (book, _))| (product_id.clone(), *book))
+        .collect()
+}
+
+// Progress shown in the status line.
+struct Status {
+    round: usize,    // Current polling round.
+    books: usize,    // Books polled each round.
+    quoted: usize,   // Books with a recent bid and ask.
+    failures: usize, // Failed book requests so far.
+}
+
+// Fetches the best bid and ask of a product, None when either side is empty.
+async fn fetch_top_of_book(product_id: &str) -> Result<Option<TopOfBook>> {
+    let book = fetch_product_book_depth(product_id, 1).await?;
+    let price = |levels: &[ProductBookEntry]| -> Option<f64> { levels.first()?.price.parse().ok() };
+    Ok(
+        match (price(&book.pricebook.bids), price(&book.pricebook.asks)) {
+            (Some(bid), Some(ask)) => Some(TopOfBook { bid, ask }),
+            _ => None,
+        },
+    )
+}
+
+// Evaluates every triangle with quotes, best round trip first.
+fn ranked_reports<'a>(
+    triangles: &'a [Triangle],
+    quotes: &HashMap<String, TopOfBook>,
+    fee_bps: f64,
+) -> Vec<(&'a Triangle, TriangleReport)> {
+    let mut reports: Vec<(&Triangle, TriangleReport)> = triangles
+        .iter()
+        .filter_map(|triangle| Some((triangle, triangle.evaluate(quotes, fee_bps)?)))
+        .collect();
+    reports.sort_by(|(_, a), (_, b)| b.best_edge_bps().0.total_cmp(&a.best_edge_bps().0));
+    reports
+}
+
+// Function to display the triangles as a table, best round trip first, highlighting those
+// above the threshold.
+fn display_arbitrage(
+    triangles: &[Triangle],
+    quotes: &HashMap<String, TopOfBook>,
+    settings: &ArbitrageSettings,
+    status: &Status,
+) {
+    let (_, height) = terminal_size();
+    let reports = ranked_reports(triangles, quotes, settings.fee_bps);
+
+    print!("\x1B[1;1H");
+    println!(
+        "Triangular arbitrage  |  {} triangles  |  fee {} bps per leg  |  threshold {} bps\x1B[K",
+        triangles.len(),
+        settings.fee_bps,
+        settings.threshold_bps
+    );
+    println!(
+        "{:<12} {:<24} {:>14} {:>14} {:>9}  {:<26} {:>9}\x1B[K",
+        "Cross", "Via", "Direct", "Implied", "Dev bps", "Best round trip", "Edge bps"
+    );
+    // Leave room for the title, header and status lines.
+    for (triangle, report) in reports.iter().take(height.saturating_sub(4)) {
+        let (edge, reverse) = report.best_edge_bps();
+        let line = format!(
+            "{:<12} {:<24} {:>14.8} {:>14.8} {:>+9.1}  {:<26} {:>+9.1}",
+            triangle.cross.product_id,
+            format!(
+                "{} / {}",
+                triangle.base_leg.product_id, triangle.quote_leg.product_id
+            ),
+            report.direct,
+            report.implied,
+            report.deviation_bps,
+            triangle.cycle(reverse).join(" > "),
+            edge
+        );
+        if edge > settings.threshold_bps {
+            println!("{}\x1B[K", highlight(&line));
+        } else {
+            println!("{}\x1B[K", line);
+        }
+    }
+    print!(
+        "round {}  |  {} of {} books freshly quoted  |  {} failed request(s)  |  {} req/s  |  {}\x1B[K",
+        status.round,
+        status.quoted,
+        status.books,
+        status.failures,
+        settings.requests_per_second,
+        Utc::now().format("%H:%M:%S")
+    );
+    println!("\x1B[J");
+}
+
+// Function to print the triangles above the threshold as timestamped lines after each round.
+fn display_arbitrage_plain(
+    triangles: &[Triangle],
+    quotes: &HashMap<String, TopOfBook>,
+    settings: &ArbitrageSettings,
+    round: usize,
+) {
+    let reports = ranked_reports(triangles, quotes, settings.fee_bps);
+    let above: Vec<&(&Triangle, TriangleReport)> = reports
+        .iter()
+        .filter(|(_, report)| report.best_edge_bps().0 > settings.threshold_bps)
+        .collect();
+    print_timestamped(&format!(
+        "ROUND {} triangles={} evaluated={} above_threshold={}",
+        round,
+        triangles.len(),
+        reports.len(),
+        above.len()
+    ));
+    for (triangle, report) in above {
+        let (edge, reverse) = report.best_edge_bps();
+        print_timestamped(&format!(
+            "ARB {} direct={} implied={} deviation_bps={:.1} cycle={} edge_bps={:.1}",
+            triangle.cross.product_id,
+            report.direct,
+            report.implied,
+            report.deviation_bps,
+            triangle.cycle(reverse).join(">"),
+            edge
+        ));
+    }
+}
+
+// Splits a comma-separated list of currencies.
+pub fn parse_currencies(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|currency| currency.trim().to_uppercase())
+        .filter(|currency| !currency.is_empty())
+        .collect()
+}
+
+// Parses a non-negative number of basis points.
+pub fn parse_bps(bps: &str) -> Result<f64> {
+    bps.parse::<f64>()
+        .ok()
+        .filter(|bps| *bps >= 0.0 && bps.is_finite())
+        .ok_or_else(|| anyhow!("invalid number of basis points {}", bps))
+}
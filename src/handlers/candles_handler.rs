@@ -1,8 +1,21 @@
-use crate::api::candles::fetch_candles; // Importing the fetch_candles function.
+use crate::api::candles::{
+    fetch_candle_history, fetch_candles, granularity_seconds, MAX_CANDLES_PER_REQUEST,
+}; // Importing the candle requests.
+use crate::candle_export::{export_candles, ExportFormat}; // Importing the CSV and JSON export.
+use crate::chart::{price_precision, render_candlestick_chart}; // Importing the candlestick chart renderer.
+use crate::clear_screen::clear_screen; // Importing the clear_screen function.
+use crate::cli::{parse_indicators, parse_resampling}; // Importing the argument parsers.
+use crate::conversion::{candles_to_reporting, price_header, reporting_currency}; // Importing the reporting currency display.
+use crate::indicators::{compute_indicators, parse_candles, Indicator, IndicatorColumn, Ohlcv}; // Importing the technical indicators.
 use crate::models::Candle; // Importing the Candle model.
-use anyhow::Result; // Importing the Result type from anyhow for error handling.
+use crate::resample::{coverage_columns, resample, Resampling}; // Importing candle resampling.
+use crate::terminal::{prompt, terminal_size}; // Importing prompts and terminal size detection for chart scaling.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use chrono::Utc; // Importing Utc to parse the requested range.
 use prettytable::{format, Cell, Row, Table}; // Importing prettytable components for table formatting and display.
+use std::collections::HashMap; // Importing HashMap to line indicator values up with candles.
 use std::io::{self, Write}; // Importing I/O utilities for user input.
+use std::path::{Path, PathBuf}; // Importing Path for export files.
 
 // Asynchronously fetches and displays candle data for a specific product.
 pub async fn fetch_candles_handler() -> Result<()> {
@@ -10,17 +23,10 @@ pub async fn fetch_candles_handler() -> Result<()> {
     let start = prompt_for_start_time(); // Prompting user for start time.
     let end = prompt_for_end_time(); // Prompting user for end time.
     let granularity = prompt_for_granularity(); // Prompting user for granularity.
-    let mut header_printed = false; // Flag to check if header has been printed.
-
-    // Converting start and end time to DateTime<Utc> and calculating the duration.
-    let start_dt = chrono::DateTime::parse_from_rfc3339(&start)?.with_timezone(&chrono::Utc);
-    let end_dt = chrono::DateTime::parse_from_rfc3339(&end)?.with_timezone(&chrono::Utc);
-    let duration = end_dt.signed_duration_since(start_dt);
-    let num_candles = duration.num_seconds() / granularity_to_seconds(&granularity); // Calculating number of candles.
 
     // Checking if the number of candles exceeds the limit.
-    if num_candles > 300 {
-        println!("The number of candles requested should be less than 300. Please adjust the time range or granularity.");
+    if let Err(e) = check_candle_count(&start, &end, &granularity) {
+        println!("{}", e);
         return Ok(());
     }
 
@@ -31,7 +37,7 @@ pub async fn fetch_candles_handler() -> Result<()> {
                 println!("No candle data available for the given time range and granularity.");
             // No data available.
             } else {
-                display_candles(&candles, &mut header_printed); // Displaying candle data.
+                browse_candles(&product_id, &candles, &granularity); // Displaying candle data as a chart or table.
             }
         }
         Err(e) => println!("Error fetching candles for {}: {:?}", product_id, e), // Handling fetch error.
@@ -40,39 +46,318 @@ pub async fn fetch_candles_handler() -> Result<()> {
     Ok(()) // Returning Ok result.
 }
 
-// Function to display the candles in a table format.
-fn display_candles(candles: &Vec<Candle>, header_printed: &mut bool) {
+// Ways the fetched candles can be displayed.
+#[derive(Clone, Copy, PartialEq)]
+enum CandlesView {
+    Chart, // Candlestick chart with volume histogram.
+    Table, // Raw OHLCV table.
+}
+
+// Displays the candles, letting the user toggle between the chart and table views, pick
+// indicator columns for the table, resample to a higher timeframe and export the candles.
+//
+// Candles are shown in the reporting currency at the current rate when one is set, and exported
+// as fetched.
+fn browse_candles(product_id: &str, candles: &[Candle], granularity: &str) {
+    let mut view = CandlesView::Chart; // Starting with the chart view.
+    let mut indicators: Vec<Indicator> = Vec::new(); // Indicator columns shown in the table.
+    let mut shown = CandleSet::from_candles(candles.to_vec()); // Candles as fetched or resampled.
+
+    loop {
+        clear_screen();
+        let converted = shown.in_reporting_currency(product_id);
+        let reported = converted.as_ref().unwrap_or(&shown);
+        match view {
+            CandlesView::Chart => {
+                // Leave a line for the conversion note.
+                display_candle_chart(&reported.candles, usize::from(converted.is_some()))
+            }
+            CandlesView::Table => {
+                let (_, columns) = reported.columns(&indicators);
+                let mut header_printed = false; // Flag to check if header has been printed.
+                display_candles(&reported.candles, &columns, &mut header_printed);
+            }
+        }
+        if converted.is_some() {
+            print_conversion_note();
+        }
+
+        print!("[t] Table  [c] Chart  [i] Indicators  [r] Resample  [x] Export  [Enter] Back to menu: ");
+        io::stdout().flush().unwrap(); // Flushing stdout to display prompt.
+        let mut choice = String::new();
+        io::stdin().read_line(&mut choice).unwrap(); // Reading user input.
+
+        match choice.trim() {
+            "t" | "T" => view = CandlesView::Table,
+            "c" | "C" => view = CandlesView::Chart,
+            "i" | "I" => {
+                let list = prompt(
+                    "Enter indicators, e.g. sma:20,ema:50,rsi:14,macd:12:26:9,bb:20:2,atr:14,stoch:14:3,obv,vwap (empty for none): ",
+                );
+                match parse_indicators(&list) {
+                    Ok(selected) => {
+                        indicators = selected;
+                        view = CandlesView::Table;
+                    }
+                    Err(e) => wait_for_enter(&e.to_string()),
+                }
+            }
+            "r" | "R" => {
+                let timeframe = prompt(
+                    "Enter the timeframe, e.g. 4h, 12h, 1w or 1M (empty for the fetched candles): ",
+                );
+                if timeframe.is_empty() {
+                    shown = CandleSet::from_candles(candles.to_vec());
+                    continue;
+                }
+                let zone = prompt(
+                    "Enter the time zone to align buckets to (e.g. +09:00 or Europe/Paris, empty for UTC): ",
+                );
+                let fill = prompt("Fill buckets without candles at the previous close? (y/n): ");
+                let resampled = parse_resampling(
+                    &timeframe,
+                    (!zone.is_empty()).then_some(zone.as_str()),
+                    fill.eq_ignore_ascii_case("y"),
+                )
+                .and_then(|resampling| resample_candles(candles, granularity, &resampling));
+                match resampled {
+                    Ok(set) => shown = set,
+                    Err(e) => wait_for_enter(&e.to_string()),
+                }
+            }
+            "x" | "X" => {
+                let path = prompt("Enter the export file (.csv or .json): ");
+                if path.is_empty() {
+                    continue;
+                }
+                let message = match shown.write_export(&indicators, None, Path::new(&path)) {
+                    Ok(()) => format!("Exported {} candle(s) to {}", shown.candles.len(), path),
+                    Err(e) => format!("Error exporting candles: {:?}", e),
+                };
+                wait_for_enter(&message);
+            }
+            "" => break, // Returning to the menu.
+            _ => continue,
+        }
+    }
+}
+
+// Output options of the candles command.
+#[derive(Debug, Default)]
+pub struct CandlesOptions {
+    pub indicators: Vec<Indicator>,     // Indicator columns to add.
+    pub resampling: Option<Resampling>, // Timeframe to aggregate the candles into.
+    pub format: Option<ExportFormat>,   // CSV or JSON instead of a table.
+    pub output: Option<PathBuf>,        // File to write instead of stdout.
+}
+
+// Fetches candles and prints them as a table, or as CSV or JSON to stdout or a file.
+//
+// When resampling, the range is fetched in pages, so it may hold more than one request's candles.
+pub async fn candles_command(
+    product_id: &str,
+    start: &str,
+    end: &str,
+    granularity: &str,
+    options: &CandlesOptions,
+) -> Result<()> {
+    let set = match &options.resampling {
+        Some(resampling) => {
+            let start_dt = chrono::DateTime::parse_from_rfc3339(start)?.with_timezone(&Utc);
+            let end_dt = chrono::DateTime::parse_from_rfc3339(end)?.with_timezone(&Utc);
+            let candles = fetch_candle_history(product_id, start_dt, end_dt, granularity).await?;
+            resample_candles(&candles, granularity, resampling)?
+        }
+        None => {
+            check_candle_count(start, end, granularity)?;
+            CandleSet::from_candles(fetch_candles(product_id, start, end, granularity).await?)
+        }
+    };
+
+    if let Some(path) = &options.output {
+        return set.write_export(&options.indicators, options.format, path);
+    }
+    match options.format {
+        Some(format) => {
+            let (bars, columns) = set.columns(&options.indicators);
+            print!("{}", export_candles(&bars, &columns, format));
+        }
+        None => {
+            let converted = set.in_reporting_currency(product_id);
+            let reported = converted.as_ref().unwrap_or(&set);
+            let (_, columns) = reported.columns(&options.indicators);
+            display_candles(&reported.candles, &columns, &mut false);
+            if converted.is_some() {
+                print_conversion_note();
+            }
+        }
+    }
+    Ok(())
+}
+
+// Candles to display or export, with any columns that come with them.
+struct CandleSet {
+    candles: Vec<Candle>,        // Candles, newest first like the API returns them.
+    extra: Vec<IndicatorColumn>, // Columns aligned with the candles oldest first.
+}
+
+impl CandleSet {
+    // Wraps candles that have no extra columns.
+    fn from_candles(candles: Vec<Candle>) -> Self {
+        CandleSet {
+            candles,
+            extra: Vec::new(),
+        }
+    }
+
+    // Returns a copy with the prices in the reporting currency, or None when none is set or the
+    // product cannot be converted.
+    //
+    // Every candle is converted at the current rate, since the rates of past periods are not
+    // known, so the note from `print_conversion_note` goes with it.
+    fn in_reporting_currency(&self, product_id: &str) -> Option<CandleSet> {
+        Some(CandleSet {
+            candles: candles_to_reporting(&self.candles, product_id)?,
+            extra: self.extra.clone(),
+        })
+    }
+
+    // Returns the candles oldest first, with the extra columns followed by the indicators.
+    fn columns(&self, indicators: &[Indicator]) -> (Vec<Ohlcv>, Vec<IndicatorColumn>) {
+        let bars = parse_candles(&self.candles);
+        let mut columns = self.extra.clone();
+        columns.extend(compute_indicators(&bars, indicators));
+        (bars, columns)
+    }
+
+    // Writes the candles and columns to a file, in the given format or the one implied by the
+    // file extension.
+    fn write_export(
+        &self,
+        indicators: &[Indicator],
+        format: Option<ExportFormat>,
+        path: &Path,
+    ) -> Result<()> {
+        let (bars, columns) = self.columns(indicators);
+        let format = format.unwrap_or_else(|| ExportFormat::from_path(path));
+        std::fs::write(path, export_candles(&bars, &columns, format))?;
+        Ok(())
+    }
+}
+
+// Resamples candles of an API granularity, adding the source and expected candle counts as columns.
+fn resample_candles(
+    candles: &[Candle],
+    granularity: &str,
+    resampling: &Resampling,
+) -> Result<CandleSet> {
+    let source = granularity_seconds(granularity)
+        .ok_or_else(|| anyhow!("unknown granularity {}", granularity))?;
+    let resampled = resample(candles, source, resampling)?;
+    Ok(CandleSet {
+        candles: resampled.iter().rev().map(|r| r.candle.clone()).collect(),
+        extra: coverage_columns(&resampled),
+    })
+}
+
+// Returns an error when the time range is invalid or needs more candles than one request returns.
+fn check_candle_count(start: &str, end: &str, granularity: &str) -> Result<()> {
+    // Converting start and end time to DateTime<Utc> and calculating the duration.
+    let start_dt = chrono::DateTime::parse_from_rfc3339(start)?.with_timezone(&chrono::Utc);
+    let end_dt = chrono::DateTime::parse_from_rfc3339(end)?.with_timezone(&chrono::Utc);
+    let duration = end_dt.signed_duration_since(start_dt);
+    let seconds = granularity_seconds(granularity)
+        .ok_or_else(|| anyhow!("Unknown granularity {}.", granularity))?;
+    let num_candles = duration.num_seconds() / seconds; // Calculating number of candles.
+    if num_candles > MAX_CANDLES_PER_REQUEST {
+        return Err(anyhow!("The number of candles requested should be less than 300. Please adjust the time range or granularity."));
+    }
+    Ok(())
+}
+
+// Function to display the candles as a candlestick chart sized to the terminal, leaving
+// `reserved` lines below it besides the prompt.
+fn display_candle_chart(candles: &[Candle], reserved: usize) {
+    let (width, height) = terminal_size();
+    // Leave room for the prompt line below the chart.
+    for line in render_candlestick_chart(candles, width, height.saturating_sub(2 + reserved)) {
+        println!("{}", line);
+    }
+}
+
+// Function to print that converted candles use the current rate, not the rate of their period.
+fn print_conversion_note() {
+    if let Some(currency) = reporting_currency() {
+        println!(
+            "Prices converted to {} at the current rate, not the rate at each candle's time.",
+            currency
+        );
+    }
+}
+
+// Function to display the candles in a table format, with one extra column per indicator series.
+fn display_candles(candles: &[Candle], columns: &[IndicatorColumn], header_printed: &mut bool) {
     let mut table = Table::new(); // Creating a new table.
     table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR); // Setting table format.
 
     // Printing the header if it hasn't been printed yet.
     if !*header_printed {
-        table.set_titles(Row::new(vec![
+        let mut titles = vec![
             Cell::new("Start Time"),
-            Cell::new("Low"),
-            Cell::new("High"),
-            Cell::new("Open"),
-            Cell::new("Close"),
+            Cell::new(&price_header("Low")),
+            Cell::new(&price_header("High")),
+            Cell::new(&price_header("Open")),
+            Cell::new(&price_header("Close")),
             Cell::new("Volume"),
-        ]));
+        ];
+        titles.extend(columns.iter().map(|column| Cell::new(&column.label)));
+        table.set_titles(Row::new(titles));
         *header_printed = true; // Setting the header printed flag.
     }
 
+    // Indicator values are computed oldest first, so look them up by candle start.
+    let bars = parse_candles(candles);
+    let positions: HashMap<i64, usize> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, bar)| (bar.start, i))
+        .collect();
+    let max_price = bars.iter().map(|bar| bar.high).fold(0.0, f64::max);
+    let precision = price_precision(max_price);
+
     // Adding each candle to the table.
     for candle in candles {
-        table.add_row(Row::new(vec![
+        let mut cells = vec![
             Cell::new(&format!("{:<20}", candle.start)),
             Cell::new(&format!("{:<10}", candle.low)),
             Cell::new(&format!("{:<10}", candle.high)),
             Cell::new(&format!("{:<10}", candle.open)),
             Cell::new(&format!("{:<10}", candle.close)),
             Cell::new(&format!("{:<10}", candle.volume)),
-        ]));
+        ];
+        let position = candle
+            .start
+            .parse::<i64>()
+            .ok()
+            .and_then(|start| positions.get(&start));
+        cells.extend(columns.iter().map(|column| {
+            let value = position.and_then(|&i| column.values[i]);
+            Cell::new(&match value {
+                Some(value) => format!("{:.*}", precision, value),
+                None => "-".to_string(),
+            })
+        }));
+        table.add_row(Row::new(cells));
     }
 
     table.printstd(); // Printing the table.
 }
 
+// Shows a message until the user presses Enter.
+fn wait_for_enter(message: &str) {
+    prompt(&format!("{}  [Enter] Continue", message));
+}
+
 // Function to prompt the user for product ID.
 fn prompt_for_product_id() -> String {
     print!("Enter the product ID: "); // Prompt message.
@@ -101,25 +386,10 @@ fn prompt_for_end_time() -> String {
 }
 
 // Function to prompt the user for granularity.
-fn prompt_for_granularity() -> String {
+pub fn prompt_for_granularity() -> String {
     println!("Enter the granularity (e.g., ONE_MINUTE, FIVE_MINUTE, FIFTEEN_MINUTE, THIRTY_MINUTE, ONE_HOUR, TWO_HOUR, SIX_HOUR, ONE_DAY): "); // Prompt message.
     io::stdout().flush().unwrap(); // Flushing stdout to display prompt.
     let mut granularity = String::new(); // Creating a mutable string for input.
     io::stdin().read_line(&mut granularity).unwrap(); // Reading user input.
     granularity.trim().to_string() // Returning the trimmed input.
 }
-
-// Function to convert granularity to seconds.
-fn granularity_to_seconds(granularity: &str) -> i64 {
-    match granularity {
-        "ONE_MINUTE" => 60,
-        "FIVE_MINUTE" => 300,
-        "FIFTEEN_MINUTE" => 900,
-        "THIRTY_MINUTE" => 1800,
-        "ONE_HOUR" => 3600,
-        "TWO_HOUR" => 7200,
-        "SIX_HOUR" => 21600,
-        "ONE_DAY" => 86400,
-        _ => 0, // Default case for invalid granularity.
-    }
-}
@@ -0,0 +1,203 @@
+use crate::api::products::fetch_products; // Importing the fetch_products function.
+use crate::config::Config; // Importing the config holding the saved reporting currency.
+use crate::conversion::{
+    clear_reporting_currency, format_amount, reporting_currency, set_reporting_currency,
+    ConversionGraph,
+}; // Importing the currency conversion graph and reporting currency.
+use crate::terminal::{is_plain, prompt}; // Importing the output mode check and prompts.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use std::sync::atomic::{AtomicBool, Ordering}; // Importing atomics to start the refresh task once.
+use std::sync::Mutex; // Importing Mutex for the requested reporting currency.
+use std::time::Duration; // Importing Duration for the refresh interval.
+use tokio::time; // Importing tokio time utilities.
+
+// Time between two refreshes of the reporting rates.
+const RATES_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+// Whether the task refreshing the reporting rates has been started.
+static REFRESHING: AtomicBool = AtomicBool::new(false);
+
+// Reporting currency chosen at start-up and not applied yet, with whether it was given on the
+// command line rather than saved.
+static REQUESTED: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+// Records the reporting currency to apply once a command or view shows prices, so commands that
+// show none never fetch the products or start the refresh task for it.
+pub fn request_reporting_currency(currency: &str, explicit: bool) {
+    *REQUESTED.lock().unwrap() = Some((currency.to_uppercase(), explicit));
+}
+
+// Returns the reporting currency in use, or the requested one while it is not applied yet.
+pub fn current_reporting_currency() -> Option<String> {
+    reporting_currency().or_else(|| {
+        REQUESTED
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(currency, _)| currency.clone())
+    })
+}
+
+// Applies the requested reporting currency, if any, the first time a price is about to be shown.
+//
+// A currency given on the command line that cannot be applied is an error, while a saved one
+// only warns and prices stay in their quote currencies.
+pub async fn apply_requested_currency() -> Result<()> {
+    let requested = REQUESTED.lock().unwrap().take();
+    let Some((currency, explicit)) = requested else {
+        return Ok(());
+    };
+    match use_reporting_currency(&currency).await {
+        Err(e) if !explicit => {
+            eprintln!(
+                "Showing prices in quote currencies, {} is unavailable: {}",
+                currency, e
+            );
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+// Fetches the products and makes every price view report in `currency`, refreshing the rates
+// in the background.
+pub async fn use_reporting_currency(currency: &str) -> Result<()> {
+    let products = fetch_products().await?;
+    let graph = ConversionGraph::from_products(&products);
+    if !set_reporting_currency(&graph, currency) {
+        return Err(anyhow!("no online spot product trades {}", currency));
+    }
+    if !REFRESHING.swap(true, Ordering::Relaxed) {
+        tokio::spawn(refresh_rates());
+    }
+    Ok(())
+}
+
+// Rebuilds the reporting rates from a fresh products listing on every interval while a
+// reporting currency is set, keeping the previous rates when the fetch fails.
+async fn refresh_rates() {
+    let mut interval = time::interval(RATES_REFRESH_INTERVAL);
+    interval.tick().await; // The first tick completes immediately.
+    loop {
+        interval.tick().await;
+        let Some(currency) = reporting_currency() else {
+            continue;
+        };
+        let Ok(products) = fetch_products().await else {
+            continue;
+        };
+        // The currency may have been changed or cleared while fetching.
+        if reporting_currency().as_deref() == Some(currency.as_str()) {
+            set_reporting_currency(&ConversionGraph::from_products(&products), &currency);
+        }
+    }
+}
+
+// Prompts for the reporting currency, applies it and saves it for next time.
+pub async fn reporting_currency_handler() -> Result<()> {
+    // Saving after a failed load would overwrite every other saved setting.
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            println!(
+                "Error loading config, the reporting currency is unchanged: {}",
+                e
+            );
+            return Ok(());
+        }
+    };
+    let input = prompt(
+        "Enter the currency to show prices in, e.g. EUR (leave empty for each product's quote currency): ",
+    );
+    // The choice replaces a currency requested at start-up that no view has applied yet.
+    REQUESTED.lock().unwrap().take();
+    if input.is_empty() {
+        clear_reporting_currency();
+        config.reporting_currency = None;
+        println!("Prices are shown in each product's quote currency.");
+    } else {
+        let currency = input.to_uppercase();
+        if let Err(e) = use_reporting_currency(&currency).await {
+            println!("Error setting the reporting currency: {}", e);
+            return Ok(());
+        }
+        config.reporting_currency = Some(currency.clone());
+        println!("Prices are now shown in {}.", currency);
+    }
+    if let Err(e) = config.save() {
+        println!("Error saving config: {}", e);
+    }
+    Ok(())
+}
+
+// Prompts for an amount and two currencies and shows the conversion.
+pub async fn convert_handler() -> Result<()> {
+    let amount = prompt("Enter the amount: ");
+    let amount = match parse_convert_amount(&amount) {
+        Ok(amount) => amount,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    let from = prompt("Enter the currency to convert from: ");
+    let to = prompt("Enter the currency to convert to: ");
+    if let Err(e) = convert_command(amount, &from, &to).await {
+        println!("Error converting {} to {}: {}", from, to, e);
+    }
+    Ok(())
+}
+
+// Fetches the products and converts an amount between two currencies along the best path,
+// showing each exchange on the way.
+pub async fn convert_command(amount: f64, from: &str, to: &str) -> Result<()> {
+    let products = fetch_products().await?;
+    let graph = ConversionGraph::from_products(&products);
+    for currency in [from, to] {
+        if !graph.has_currency(currency) {
+            return Err(anyhow!("no online spot product trades {}", currency));
+        }
+    }
+    let path = graph
+        .path(from, to)
+        .ok_or_else(|| anyhow!("no products link {} and {}", from, to))?;
+    let converted = amount * path.rate();
+
+    if is_plain() {
+        println!(
+            "CONVERT {} {} = {} {} path={}",
+            amount,
+            path.from,
+            converted,
+            path.to,
+            path.currencies().join(">")
+        );
+        return Ok(());
+    }
+    println!(
+        "{} {} = {} {}",
+        amount,
+        path.from,
+        format_amount(converted),
+        path.to
+    );
+    for step in &path.steps {
+        println!(
+            "  {} > {} via {} at {}",
+            step.from,
+            step.to,
+            step.product_id,
+            format_amount(step.rate)
+        );
+    }
+    Ok(())
+}
+
+// Parses a finite, non-negative amount to convert.
+pub fn parse_convert_amount(amount: &str) -> Result<f64> {
+    amount
+        .parse::<f64>()
+        .ok()
+        .filter(|amount| *amount >= 0.0 && amount.is_finite())
+        .ok_or_else(|| anyhow!("invalid amount {}", amount))
+}
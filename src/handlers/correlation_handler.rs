@@ -0,0 +1,113 @@
+use crate::api::candles::fetch_candle_history; // Importing the paged candle request.
+use crate::correlation::{correlation_report, CorrelationReport, Method}; // Importing the correlation analytics.
+use crate::models::Candle; // Importing the Candle model.
+use crate::terminal::heat; // Importing the heatmap colouring.
+use anyhow::Result; // Importing anyhow utilities for error handling.
+use chrono::{DateTime, Utc}; // Importing chrono to parse the range.
+
+// Fetches candles for every product over the range, then prints the correlation matrix of
+// their log returns with each product's beta against the benchmark, as a heatmap or CSV.
+pub async fn correlation_command(
+    product_ids: &[String],
+    start: &str,
+    end: &str,
+    granularity: &str,
+    benchmark: &str,
+    method: Method,
+    csv: bool,
+) -> Result<()> {
+    let start = DateTime::parse_from_rfc3339(start)?.with_timezone(&Utc);
+    let end = DateTime::parse_from_rfc3339(end)?.with_timezone(&Utc);
+
+    let mut candles: Vec<(String, Vec<Candle>)> = Vec::new();
+    for product_id in product_ids {
+        let history = fetch_candle_history(product_id, start, end, granularity).await?;
+        candles.push((product_id.clone(), history));
+    }
+    let report = correlation_report(&candles, benchmark)?;
+
+    if csv {
+        print!("{}", correlation_csv(&report, method));
+    } else {
+        display_correlation(&report, method, granularity);
+    }
+    Ok(())
+}
+
+// Function to print the matrix with each cell shaded by its value, and the betas below it.
+fn display_correlation(report: &CorrelationReport, method: Method, granularity: &str) {
+    let width = report
+        .product_ids
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    println!(
+        "{} correlation of {} log returns ({} aligned returns)",
+        method.name(),
+        granularity,
+        report.observations
+    );
+    println!();
+
+    let mut header = format!("{:<width$}", "", width = width);
+    for product_id in &report.product_ids {
+        header.push_str(&format!(" {:>width$}", product_id, width = width));
+    }
+    println!("{}", header);
+    for (product_id, row) in report.product_ids.iter().zip(report.matrix(method)) {
+        let mut line = format!("{:<width$}", product_id, width = width);
+        for value in row {
+            line.push(' ');
+            line.push_str(&cell(*value, width));
+        }
+        println!("{}", line);
+    }
+
+    println!();
+    let mut betas = format!("{:<width$}", "Beta", width = width);
+    for beta in &report.betas {
+        betas.push_str(&format!(" {:>width$}", format_value(*beta), width = width));
+    }
+    println!("{}", betas);
+    println!("Betas are measured against {}.", report.benchmark);
+}
+
+// Formats a matrix cell right aligned and shaded by its value.
+fn cell(value: Option<f64>, width: usize) -> String {
+    let text = format!("{:>width$}", format_value(value), width = width);
+    match value {
+        Some(value) => heat(&text, value),
+        None => text,
+    }
+}
+
+// Formats a correlation or beta to two decimals, or n/a when it is undefined.
+fn format_value(value: Option<f64>) -> String {
+    value.map_or("n/a".to_string(), |value| format!("{:.2}", value))
+}
+
+// Formats the matrix as CSV, one line per product with its beta in the last column.
+fn correlation_csv(report: &CorrelationReport, method: Method) -> String {
+    let mut header = vec!["product".to_string()];
+    header.extend(report.product_ids.iter().cloned());
+    header.push(format!("beta_{}", report.benchmark));
+    let mut lines = vec![header.join(",")];
+
+    for ((product_id, row), beta) in report
+        .product_ids
+        .iter()
+        .zip(report.matrix(method))
+        .zip(&report.betas)
+    {
+        let mut fields = vec![product_id.clone()];
+        fields.extend(
+            row.iter()
+                .chain([beta])
+                .map(|value| value.map(|value| value.to_string()).unwrap_or_default()),
+        );
+        lines.push(fields.join(","));
+    }
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,333 @@
+use crate::api::candles::{fetch_candle_history, fetch_candles, granularity_seconds}; // Importing the candle requests.
+use crate::bar_builder::{BarRule, TimeBarBuilder, TradeBar}; // Importing the time bar builder.
+use crate::candle_series::{CandleSeries, SeriesUpdate}; // Importing the rolling candle series.
+use crate::chart::render_candlestick_chart; // Importing the candlestick chart renderer.
+use crate::conversion::{candles_to_reporting, display_price, price_header, reporting_currency}; // Importing the reporting currency display.
+use crate::handlers::candles_handler::prompt_for_granularity; // Importing the granularity prompt.
+use crate::market_data::{self, recv_batch, DataKind, MarketEvent, Subscription}; // Importing the market data interface.
+use crate::models::Candle; // Importing the Candle model.
+use crate::resample::MissingCandles; // Importing the empty interval policy.
+use crate::terminal::{highlight, is_plain, print_timestamped, prompt, terminal_size}; // Importing output helpers.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use chrono::{DateTime, Duration, TimeZone, Utc}; // Importing chrono types for the polled range.
+use tokio::sync::watch; // Importing watch from tokio for synchronization.
+use tokio::time; // Importing tokio time utilities.
+
+// Shortest and longest time between two polls of the latest candles.
+const MIN_POLL_SECS: u64 = 2;
+const MAX_POLL_SECS: u64 = 60;
+// Number of polls per candle period, within the bounds above.
+const POLLS_PER_PERIOD: u64 = 30;
+// Number of candles loaded when none is entered.
+const DEFAULT_CANDLE_COUNT: usize = 100;
+// Largest number of candles the view keeps.
+const MAX_CANDLE_COUNT: usize = 1000;
+// Time between two redraws of candles built from trades.
+const TRADE_REDRAW_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+// How long a candle built from trades stays open after its period ends, for trades that arrive late.
+const CLOSE_GRACE_SECS: i64 = 2;
+
+// Ways the live candles can be displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveView {
+    Chart, // Candlestick chart with volume histogram.
+    Table, // OHLCV table, newest first.
+}
+
+// What the live candles view shows.
+#[derive(Debug, Clone)]
+pub struct LiveCandles {
+    pub granularity: String, // API granularity, e.g. ONE_MINUTE, or a trade bar interval, e.g. 5s.
+    pub count: usize,        // Number of trailing candles shown.
+    pub view: LiveView,      // Chart or table.
+}
+
+// Prompts for the granularity, number of candles and view of the live candles view.
+pub fn prompt_for_live_candles() -> Result<LiveCandles> {
+    println!("Sub-minute candles such as 5s, 15s or 30s are built from the trade stream.");
+    let granularity = prompt_for_granularity();
+    if granularity_seconds(&granularity).is_none() && trade_bar_interval(&granularity).is_none() {
+        return Err(anyhow!("Unknown granularity {}.", granularity));
+    }
+    let count = match prompt(&format!(
+        "Enter the number of candles to show (default {}, at most {}): ",
+        DEFAULT_CANDLE_COUNT, MAX_CANDLE_COUNT
+    ))
+    .as_str()
+    {
+        "" => DEFAULT_CANDLE_COUNT,
+        count => count
+            .parse::<usize>()
+            .ok()
+            .filter(|count| (1..=MAX_CANDLE_COUNT).contains(count))
+            .ok_or_else(|| anyhow!("Invalid number of candles {}.", count))?,
+    };
+    let view = if prompt("Show a chart or a table? (c/t): ").eq_ignore_ascii_case("t") {
+        LiveView::Table
+    } else {
+        LiveView::Chart
+    };
+    Ok(LiveCandles {
+        granularity,
+        count,
+        view,
+    })
+}
+
+// Loads the trailing candles of a product, then polls the latest ones so the last candle
+// updates in place and new candles are appended as periods close.
+//
+// Granularities the candles endpoint does not offer, such as 5s, are built from the trade
+// stream instead.
+pub async fn live_candles_handler(
+    mut stop: watch::Receiver<()>,
+    product_id: String,
+    settings: LiveCandles,
+) -> Result<()> {
+    let Some(seconds) = granularity_seconds(&settings.granularity) else {
+        let interval = trade_bar_interval(&settings.granularity)
+            .ok_or_else(|| anyhow!("unknown granularity {}", settings.granularity))?;
+        return live_trade_candles(stop, product_id, settings, interval).await;
+    };
+    let mut series = CandleSeries::new(settings.count);
+
+    // Load the trailing candles.
+    let now = Utc::now();
+    let start = now - Duration::seconds(seconds * settings.count as i64);
+    let loaded = fetch_candle_history(&product_id, start, now, &settings.granularity).await?;
+    let update = series.merge(loaded);
+    display(&product_id, &settings, &series, &update, None);
+
+    let mut poll = time::interval(poll_interval(seconds));
+    poll.tick().await; // The first tick completes immediately.
+    loop {
+        tokio::select! {
+            _ = stop.changed() => {
+                println!("Stopping live_candles_handler");
+                break;
+            }
+            _ = poll.tick() => {
+                // Two periods, so the previous candle's final values arrive after it closes.
+                let now = Utc::now();
+                let from = now - Duration::seconds(2 * seconds);
+                let polled = fetch_candles(
+                    &product_id,
+                    &from.to_rfc3339(),
+                    &now.to_rfc3339(),
+                    &settings.granularity,
+                )
+                .await;
+                match polled {
+                    Ok(candles) => {
+                        let update = series.merge(candles);
+                        display(&product_id, &settings, &series, &update, None);
+                    }
+                    Err(e) => {
+                        let error = format!("poll failed: {}", e);
+                        display(&product_id, &settings, &series, &SeriesUpdate::default(), Some(&error));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Builds candles of `interval` seconds from the product's trades, with the forming candle
+// updated as trades arrive, and shows them like polled candles.
+async fn live_trade_candles(
+    mut stop: watch::Receiver<()>,
+    product_id: String,
+    settings: LiveCandles,
+    interval: i64,
+) -> Result<()> {
+    let subscription = Subscription::new(std::slice::from_ref(&product_id), &[DataKind::Trades]);
+    let mut events = market_data::source().subscribe(&subscription); // Trades from the selected transport.
+    let mut builder = TimeBarBuilder::new(interval, MissingCandles::FillFlat);
+    let mut series = CandleSeries::new(settings.count);
+    let mut completed: Vec<TradeBar> = Vec::new(); // Bars completed since the last redraw.
+    let mut redraw = time::interval(TRADE_REDRAW_INTERVAL); // Closes candles when trading is quiet.
+
+    loop {
+        tokio::select! {
+            _ = stop.changed() => {
+                println!("Stopping live_candles_handler");
+                break;
+            }
+            batch = recv_batch(&mut events) => {
+                let Some(batch) = batch else { break }; // The source stopped.
+                for event in batch {
+                    match event {
+                        MarketEvent::Trade(trade) => completed.extend(builder.push(&trade)),
+                        MarketEvent::Notice(notice) if is_plain() => {
+                            print_timestamped(&format!("FEED {}", notice))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ = redraw.tick() => {
+                let now = Utc::now() - Duration::seconds(CLOSE_GRACE_SECS);
+                completed.extend(builder.advance(now));
+                let candles = completed
+                    .drain(..)
+                    .chain(builder.current().cloned())
+                    .map(|bar| bar.to_candle())
+                    .collect();
+                let update = series.merge(candles);
+                display(&product_id, &settings, &series, &update, None);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Returns the time between two polls for candles `seconds` long, so one-minute candles are
+// polled every 2 seconds and daily candles once a minute.
+fn poll_interval(seconds: i64) -> std::time::Duration {
+    let seconds = u64::try_from(seconds).unwrap_or_default() / POLLS_PER_PERIOD;
+    std::time::Duration::from_secs(seconds.clamp(MIN_POLL_SECS, MAX_POLL_SECS))
+}
+
+// Returns the length in seconds of candles built from trades, for a time bar rule such as 5s.
+fn trade_bar_interval(granularity: &str) -> Option<i64> {
+    match BarRule::parse(granularity)? {
+        BarRule::Time(seconds) => Some(seconds),
+        _ => None,
+    }
+}
+
+// Shows the series in the chosen view, or prints changed candles in plain mode.
+fn display(
+    product_id: &str,
+    settings: &LiveCandles,
+    series: &CandleSeries,
+    update: &SeriesUpdate,
+    error: Option<&str>,
+) {
+    if is_plain() {
+        display_candles_plain(product_id, settings, series, update, error);
+        return;
+    }
+
+    let (width, height) = terminal_size();
+    print!("\x1B[1;1H");
+    // Leave room for the status line below the view.
+    let rows = height.saturating_sub(2);
+    let candles: Vec<Candle> = series.candles().cloned().collect();
+    let candles = candles_to_reporting(&candles, product_id).unwrap_or(candles);
+    match settings.view {
+        LiveView::Chart if !candles.is_empty() => {
+            for line in render_candlestick_chart(&candles, width, rows) {
+                println!("{}\x1B[K", line);
+            }
+        }
+        LiveView::Chart => println!("No candles yet.\x1B[K"),
+        LiveView::Table => display_candles_table(product_id, series, update, rows),
+    }
+
+    let status = match error {
+        Some(error) => error.to_string(),
+        None => format!(
+            "{} updated, {} new",
+            update.updated.len(),
+            update.appended.len()
+        ),
+    };
+    // Loaded candles are converted at the current rate like the forming one.
+    let currency_note = reporting_currency()
+        .map(|currency| format!("  |  prices in {} at the current rate", currency))
+        .unwrap_or_default();
+    print!(
+        "{} {}{}  |  {} candle(s)  |  last poll {}  |  {}\x1B[K",
+        product_id,
+        settings.granularity,
+        currency_note,
+        series.len(),
+        Utc::now().format("%H:%M:%S"),
+        status
+    );
+    println!("\x1B[J");
+}
+
+// Function to display the candles newest first, marking the forming candle and those that changed.
+fn display_candles_table(
+    product_id: &str,
+    series: &CandleSeries,
+    update: &SeriesUpdate,
+    rows: usize,
+) {
+    println!(
+        "{:<22} {:<14} {:<14} {:<14} {:<14} {:<16}\x1B[K",
+        "Start Time",
+        price_header("Open"),
+        price_header("High"),
+        price_header("Low"),
+        price_header("Close"),
+        "Volume"
+    );
+    let last_start = series.last().map(|candle| candle.start.clone());
+    for candle in series.candles().rev().take(rows.saturating_sub(1)) {
+        let mut line = format!(
+            "{:<22} {:<14} {:<14} {:<14} {:<14} {:<16}",
+            format_start(&candle.start),
+            display_price(&candle.open, product_id),
+            display_price(&candle.high, product_id),
+            display_price(&candle.low, product_id),
+            display_price(&candle.close, product_id),
+            candle.volume
+        );
+        if Some(&candle.start) == last_start.as_ref() {
+            line.push_str(" forming");
+        }
+        if update.updated.contains(&candle.start) || update.appended.contains(&candle.start) {
+            line = highlight(&line);
+        }
+        println!("{}\x1B[K", line);
+    }
+}
+
+// Function to print the loaded and changed candles as timestamped lines, for plain output.
+fn display_candles_plain(
+    product_id: &str,
+    settings: &LiveCandles,
+    series: &CandleSeries,
+    update: &SeriesUpdate,
+    error: Option<&str>,
+) {
+    if let Some(error) = error {
+        print_timestamped(&format!("ERROR {} {}", product_id, error));
+        return;
+    }
+    for candle in series.candles() {
+        let kind = if update.appended.contains(&candle.start) {
+            "NEW"
+        } else if update.updated.contains(&candle.start) {
+            "UPDATE"
+        } else {
+            continue;
+        };
+        print_timestamped(&format!(
+            "CANDLE {} {} {} start={} open={} high={} low={} close={} volume={}",
+            kind,
+            product_id,
+            settings.granularity,
+            format_start(&candle.start),
+            display_price(&candle.open, product_id),
+            display_price(&candle.high, product_id),
+            display_price(&candle.low, product_id),
+            display_price(&candle.close, product_id),
+            candle.volume
+        ));
+    }
+}
+
+// Formats a candle start as an RFC 3339 time, leaving it as is when it is not a timestamp.
+fn format_start(start: &str) -> String {
+    start
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+        .map(|time: DateTime<Utc>| time.to_rfc3339())
+        .unwrap_or_else(|| start.to_string())
+}
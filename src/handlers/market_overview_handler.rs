@@ -0,0 +1,193 @@
+use crate::api::products::fetch_products; // Importing the fetch_products function.
+use crate::conversion::{display_price, reporting_currency}; // Importing the reporting currency display.
+use crate::market_overview::{MarketOverview, Mover, VolumeLeader, VolumeShare}; // Importing the market overview.
+use crate::terminal::{paint, terminal_size, GREEN, RED}; // Importing output helpers.
+use anyhow::Result; // Importing the Result type from anyhow for error handling.
+use chrono::Utc; // Importing Utc to time the overview.
+
+// Products shown in each ranking when no count is given.
+pub const DEFAULT_TOP: usize = 10;
+// Currency volume by product type is totalled in when no reporting currency is set.
+const DEFAULT_VOLUME_CURRENCY: &str = "USD";
+// Width of a gainers or losers column.
+const MOVER_WIDTH: usize = 30;
+// Width of the volume columns.
+const VOLUME_WIDTH: usize = 26;
+// Space between columns.
+const GAP: &str = "  ";
+
+// Fetches the products once and prints the market overview.
+pub async fn fetch_market_overview_handler() -> Result<()> {
+    overview_command(DEFAULT_TOP).await
+}
+
+// Fetches the products once and prints the market overview with `top` products per ranking.
+pub async fn overview_command(top: usize) -> Result<()> {
+    let products = fetch_products().await?;
+    let currency = reporting_currency().unwrap_or_else(|| DEFAULT_VOLUME_CURRENCY.to_string());
+    display_overview(&MarketOverview::from_products(&products, top, &currency));
+    Ok(())
+}
+
+// Function to print the overview on one screen: rankings side by side, the product modes,
+// then the volume breakdowns side by side.
+fn display_overview(overview: &MarketOverview) {
+    let (width, _) = terminal_size();
+    let currency_note = reporting_currency()
+        .map(|currency| format!(", prices in {}", currency))
+        .unwrap_or_default();
+    println!(
+        "Market overview at {}: {} products, {} tradable{}",
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        overview.product_count,
+        overview.tradable_count,
+        currency_note
+    );
+    println!();
+
+    println!(
+        "{:<m$}{}{:<m$}{}Top quote volume (24h)",
+        "Top gainers (24h)",
+        GAP,
+        "Top losers (24h)",
+        GAP,
+        m = MOVER_WIDTH
+    );
+    let rows = overview
+        .gainers
+        .len()
+        .max(overview.losers.len())
+        .max(overview.volume_leaders.len());
+    for i in 0..rows {
+        println!(
+            "{}{}{}{}{}",
+            mover_cell(overview.gainers.get(i)),
+            GAP,
+            mover_cell(overview.losers.get(i)),
+            GAP,
+            leader_cell(overview.volume_leaders.get(i), &overview.volume_currency)
+        );
+    }
+    println!();
+
+    for (label, ids) in [
+        ("New listings", &overview.new_listings),
+        ("Auction mode", &overview.auction_mode),
+        ("Limit only", &overview.limit_only),
+        ("Cancel only", &overview.cancel_only),
+    ] {
+        println!("{}", product_list(label, ids, width));
+    }
+    println!();
+
+    println!(
+        "{:<v$}{}Volume by product type ({})",
+        "Volume by quote currency",
+        GAP,
+        overview.volume_currency,
+        v = VOLUME_WIDTH
+    );
+    let rows = overview
+        .volume_by_quote
+        .len()
+        .max(overview.volume_by_type.len());
+    for i in 0..rows {
+        println!(
+            "{}{}{}",
+            share_cell(overview.volume_by_quote.get(i)),
+            GAP,
+            share_cell(overview.volume_by_type.get(i)).trim_end()
+        );
+    }
+    if overview.unconverted > 0 {
+        println!(
+            "{} product(s) left out of volume by product type: no rate into {}",
+            overview.unconverted, overview.volume_currency
+        );
+    }
+}
+
+// Formats a gainer or loser: product, price and coloured change.
+fn mover_cell(mover: Option<&Mover>) -> String {
+    let Some(mover) = mover else {
+        return " ".repeat(MOVER_WIDTH);
+    };
+    let change = format!("{:>+7.2}%", mover.change);
+    let colour = if mover.change >= 0.0 { GREEN } else { RED };
+    format!(
+        "{:<11} {:>10}{}",
+        truncate(&mover.product_id, 11),
+        truncate(&display_price(&mover.price, &mover.product_id), 10),
+        paint(&change, colour)
+    )
+}
+
+// Formats a volume leader: product and volume with its currency, in the overview currency it is
+// ranked in when it has a rate.
+fn leader_cell(leader: Option<&VolumeLeader>, currency: &str) -> String {
+    leader.map_or(String::new(), |leader| {
+        let (volume, currency) = match leader.volume {
+            Some(volume) => (volume, currency),
+            None => (leader.quote_volume, leader.quote_currency.as_str()),
+        };
+        format!(
+            "{:<11} {:>9} {}",
+            truncate(&leader.product_id, 11),
+            compact_number(volume),
+            currency
+        )
+    })
+}
+
+// Formats a volume breakdown row: group, volume and product count.
+fn share_cell(share: Option<&VolumeShare>) -> String {
+    let text = share.map_or(String::new(), |share| {
+        format!(
+            "{:<8} {:>9} ({})",
+            truncate(&share.name, 8),
+            compact_number(share.volume),
+            share.products
+        )
+    });
+    format!("{:<v$}", text, v = VOLUME_WIDTH)
+}
+
+// Formats a labelled list of products on one line, counting those that do not fit.
+fn product_list(label: &str, ids: &[String], width: usize) -> String {
+    let mut line = format!("{} ({}): ", label, ids.len());
+    if ids.is_empty() {
+        line.push_str("none");
+        return line;
+    }
+    for (i, id) in ids.iter().enumerate() {
+        let rest = ids.len() - i - 1;
+        let more = if rest > 0 {
+            format!(", +{} more", rest)
+        } else {
+            String::new()
+        };
+        let separator = if i == 0 { "" } else { ", " };
+        if line.len() + separator.len() + id.len() + more.len() > width && i > 0 {
+            line.push_str(&format!(", +{} more", ids.len() - i));
+            break;
+        }
+        line.push_str(separator);
+        line.push_str(id);
+    }
+    line
+}
+
+// Formats a volume with a K, M or B suffix.
+fn compact_number(value: f64) -> String {
+    match value.abs() {
+        v if v >= 1e9 => format!("{:.2}B", value / 1e9),
+        v if v >= 1e6 => format!("{:.2}M", value / 1e6),
+        v if v >= 1e3 => format!("{:.2}K", value / 1e3),
+        _ => format!("{:.2}", value),
+    }
+}
+
+// Shortens text to at most `width` characters.
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
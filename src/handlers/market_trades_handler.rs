@@ -1,15 +1,39 @@
-use crate::api::market_trades::fetch_market_trades; // Importing the fetch_market_trades function.
-use crate::models::MarketTrade; // Importing the MarketTrade model.
+use crate::config::Config; // Importing the saved settings.
+use crate::conversion::{display_price, price_header}; // Importing the reporting currency display.
+use crate::market_data::{self, recv_batch, DataKind, MarketEvent, Subscription}; // Importing the market data interface.
+use crate::terminal::{is_plain, paint, print_timestamped, GREEN, RED}; // Importing output helpers.
+use crate::trade_flow::{window_label, FlowTrade, TradeFlow}; // Importing the rolling trade-flow analytics.
+use crate::trade_tape::{TapeUpdate, TradeTape}; // Importing the de-duplicating trade tape.
 use anyhow::Result; // Importing the Result type from anyhow for error handling.
-use std::time::Duration; // Importing Duration for time handling.
+use chrono::Utc; // Importing Utc to age the rolling windows.
+use std::time::Duration; // Importing Duration for the panel refresh.
 use tokio::sync::watch; // Importing watch from tokio for synchronization.
-use tokio::time; // Importing time module from tokio for sleep functionality.
+use tokio::time; // Importing tokio time utilities.
 
-// Asynchronously fetches and displays market trades for a specific product.
+// Number of trades shown on screen.
+const TRADES_TO_DISPLAY: usize = 30;
+// Number of trades retained by the tape.
+const TAPE_CAPACITY: usize = 1000;
+// Screen column of the trade-flow panel, right of the tape.
+const FLOW_PANEL_COLUMN: usize = 84;
+// Number of large trades listed in the panel.
+const LARGE_TRADES_TO_DISPLAY: usize = 5;
+
+// Asynchronously receives market trades for a specific product and displays them as a time-and-sales tape.
 pub async fn fetch_market_trades_handler(
     mut stop: watch::Receiver<()>,
     product_id: String,
 ) -> Result<()> {
+    let subscription = Subscription::new(std::slice::from_ref(&product_id), &[DataKind::Trades]);
+    let mut events = market_data::source().subscribe(&subscription); // Trades from the selected transport.
+    let mut tape = TradeTape::new(TAPE_CAPACITY); // Tape tracking trades across updates.
+    let settings = Config::load()
+        .map(|config| config.trade_flow)
+        .unwrap_or_default()
+        .with_window_override();
+    let mut flow = TradeFlow::new(settings); // Rolling analytics over the new trades.
+    let mut refresh = time::interval(Duration::from_secs(1)); // Ages the windows when trading is quiet.
+
     loop {
         // Using tokio::select! to handle concurrent tasks.
         tokio::select! {
@@ -18,53 +42,277 @@ pub async fn fetch_market_trades_handler(
                 println!("Stopping fetch_market_trades_handler"); // Print message when stopping.
                 break; // Exit the loop.
             }
-            // Adding a delay of 50 milliseconds before the next fetch.
-            _ = time::sleep(Duration::from_millis(750)) => {
-                // Fetching market trades and handling the result.
-                match fetch_market_trades(&product_id).await {
-                    Ok(mut market_trades) => {
-                        // Sort trades by time using a stable sort to maintain order for equal elements
-                        market_trades.sort_unstable_by(|a, b| a.time.cmp(&b.time));
-                        // Limit the number of trades to display
-                        let trades_to_display = market_trades.iter().take(30).collect::<Vec<_>>();
-                        // Move cursor to the specific location for this handler
-                        print!("\x1B[1;1H");
-                        // Display the trades
-                        display_market_trades(&trades_to_display); // Displaying market trades.
-                    },
-                    Err(e) => println!("Error fetching market trades for {}: {:?}", product_id, e), // Handling fetch error.
+            batch = recv_batch(&mut events) => {
+                let Some(batch) = batch else { break }; // The source stopped.
+
+                let mut trades = Vec::new();
+                let mut gap = false;
+                for event in batch {
+                    match event {
+                        MarketEvent::Trade(trade) => trades.push(trade),
+                        // The source reports gaps, a reset means trades were missed while reconnecting.
+                        MarketEvent::Gap { .. } | MarketEvent::Reset(_) => gap = true,
+                        MarketEvent::Notice(notice) => display_trades_status(&notice),
+                        _ => {}
+                    }
+                }
+                if trades.is_empty() && !gap {
+                    continue;
                 }
+
+                // Keep only the trades not seen before.
+                let mut update = tape.ingest(trades);
+                update.possible_gap = gap;
+
+                // Feed the new trades to the analytics oldest first.
+                let large_trades: Vec<FlowTrade> = update
+                    .new_trades
+                    .iter()
+                    .rev()
+                    .filter_map(|trade| flow.ingest(trade))
+                    .collect();
+                flow.evict(Utc::now());
+
+                if is_plain() {
+                    display_market_trades_plain(&product_id, &update); // Appending new trades.
+                    display_trade_flow_plain(&product_id, &flow, &large_trades);
+                } else {
+                    display_market_trades(&tape, &update); // Repainting the tape.
+                    display_trade_flow(&product_id, &flow);
+                }
+            }
+            _ = refresh.tick(), if !is_plain() => {
+                flow.evict(Utc::now());
+                display_trade_flow(&product_id, &flow);
             }
         }
     }
     Ok(()) // Returning Ok result.
 }
 
-// Function to display the market trades in a table format.
-fn display_market_trades(trades: &[&MarketTrade]) {
+// Function to show the transport state below the tape, or as a timestamped line in plain mode.
+fn display_trades_status(status: &str) {
+    if is_plain() {
+        print_timestamped(&format!("FEED {}", status));
+    } else {
+        println!("\x1B[{};1H{}\x1B[K", TRADES_TO_DISPLAY + 3, status);
+    }
+}
+
+// Function to display the most recent trades, newest first, coloured by side.
+fn display_market_trades(tape: &TradeTape, update: &TapeUpdate) {
     // Print header at the top of the designated area
     println!(
-        "{}{:<15} {:<10} {:<10} {:<10} {:<25} {:<5}",
-        format!("\x1B[1;1H"),
+        "\x1B[1;1H{:<15} {:<10} {:<10} {:<10} {:<25} {:<5}",
         "Trade ID",
         "Product ID",
-        "Price",
+        price_header("Price"),
         "Size",
         "Time",
         "Side"
     );
 
-    // Print each trade in the designated area
-    for (i, trade) in trades.iter().enumerate() {
-        println!(
-            "{}{:<15} {:<10} {:<10} {:<10} {:<25} {:<5}",
-            format!("\x1B[{};1H", i + 2),
+    // Print each trade in the designated area, clearing leftovers from longer lines.
+    for (i, trade) in tape.trades().take(TRADES_TO_DISPLAY).enumerate() {
+        let line = format!(
+            "{:<15} {:<10} {:<10} {:<10} {:<25} {:<5}",
             trade.trade_id,
             trade.product_id,
-            trade.price,
+            display_price(&trade.price, &trade.product_id),
+            trade.size,
+            trade.time,
+            trade.side
+        );
+        println!(
+            "\x1B[{};1H{}\x1B[K",
+            i + 2,
+            paint(&line, side_colour(&trade.side))
+        );
+    }
+
+    // Print the status line below the trades.
+    let gap_warning = if update.possible_gap {
+        "  |  Gap since last update, trades may be missing"
+    } else {
+        ""
+    };
+    println!(
+        "\x1B[{};1H{} new trade(s){}\x1B[K",
+        TRADES_TO_DISPLAY + 2,
+        update.new_trades.len(),
+        gap_warning
+    );
+}
+
+// Function to print newly seen trades as timestamped lines in chronological order, for plain output.
+fn display_market_trades_plain(product_id: &str, update: &TapeUpdate) {
+    if update.possible_gap {
+        print_timestamped(&format!(
+            "GAP {} trades may have been missed since the last update",
+            product_id
+        ));
+    }
+    for trade in update.new_trades.iter().rev() {
+        print_timestamped(&format!(
+            "TRADE {} {} price={} size={} time={} side={}",
+            trade.product_id,
+            trade.trade_id,
+            display_price(&trade.price, &trade.product_id),
             trade.size,
             trade.time,
             trade.side
+        ));
+    }
+}
+
+// Function to display the rolling trade-flow figures as a panel right of the tape.
+fn display_trade_flow(product_id: &str, flow: &TradeFlow) {
+    let stats = flow.window_stats();
+    let column = |values: Vec<String>| {
+        values
+            .iter()
+            .map(|value| format!("{:>14}", value))
+            .collect::<String>()
+    };
+    let optional = |value: Option<f64>, decimals: usize| match value {
+        Some(value) => format!("{:.*}", decimals, value),
+        None => "-".to_string(),
+    };
+
+    let rows: Vec<(&str, Vec<String>)> = vec![
+        (
+            "Flow",
+            stats.iter().map(|s| window_label(s.length)).collect(),
+        ),
+        (
+            "VWAP",
+            stats
+                .iter()
+                .map(|s| display_price(&optional(s.vwap, 4), product_id))
+                .collect(),
+        ),
+        (
+            "Buy vol",
+            stats
+                .iter()
+                .map(|s| format!("{:.4}", s.buy_volume))
+                .collect(),
+        ),
+        (
+            "Sell vol",
+            stats
+                .iter()
+                .map(|s| format!("{:.4}", s.sell_volume))
+                .collect(),
+        ),
+        (
+            "Delta",
+            stats.iter().map(|s| format!("{:+.4}", s.delta)).collect(),
+        ),
+        (
+            "Trades",
+            stats.iter().map(|s| s.trade_count.to_string()).collect(),
+        ),
+        (
+            "Avg size",
+            stats.iter().map(|s| optional(s.average_size, 6)).collect(),
+        ),
+        (
+            "Max size",
+            stats.iter().map(|s| optional(s.max_size, 6)).collect(),
+        ),
+    ];
+    for (i, (label, values)) in rows.into_iter().enumerate() {
+        println!(
+            "\x1B[{};{}H{:<10}{}\x1B[K",
+            i + 1,
+            FLOW_PANEL_COLUMN,
+            label,
+            column(values)
         );
     }
+    println!(
+        "\x1B[10;{}H{:<10}{:+.4}\x1B[K",
+        FLOW_PANEL_COLUMN,
+        "CVD",
+        flow.cumulative_delta()
+    );
+
+    // Large trades, newest first.
+    let settings = flow.settings();
+    let mut thresholds = Vec::new();
+    if let Some(size) = settings.large_trade_size {
+        thresholds.push(format!("size >= {}", size));
+    }
+    if let Some(notional) = settings.large_trade_notional {
+        thresholds.push(format!("notional >= {}", notional));
+    }
+    println!(
+        "\x1B[12;{}HLarge trades ({})\x1B[K",
+        FLOW_PANEL_COLUMN,
+        thresholds.join(" or ")
+    );
+    let large: Vec<&FlowTrade> = flow.large_trades().take(LARGE_TRADES_TO_DISPLAY).collect();
+    for row in 0..LARGE_TRADES_TO_DISPLAY {
+        let line = match large.get(row) {
+            Some(trade) => {
+                let side = if trade.is_buy { "BUY" } else { "SELL" };
+                paint(
+                    &format!(
+                        "{} {:<4} {} @ {}",
+                        trade.time.format("%H:%M:%S"),
+                        side,
+                        trade.size,
+                        display_price(&trade.price.to_string(), product_id)
+                    ),
+                    side_colour(side),
+                )
+            }
+            None => String::new(),
+        };
+        println!("\x1B[{};{}H{}\x1B[K", 13 + row, FLOW_PANEL_COLUMN, line);
+    }
+}
+
+// Function to print the rolling trade-flow figures and new large trades as timestamped lines, for plain output.
+fn display_trade_flow_plain(product_id: &str, flow: &TradeFlow, large_trades: &[FlowTrade]) {
+    for stats in flow.window_stats() {
+        print_timestamped(&format!(
+            "FLOW {} window={} vwap={} buy_volume={:.8} sell_volume={:.8} delta={:.8} trades={} avg_size={} max_size={}",
+            product_id,
+            window_label(stats.length),
+            stats.vwap.map_or("-".to_string(), |v| display_price(&format!("{:.8}", v), product_id)),
+            stats.buy_volume,
+            stats.sell_volume,
+            stats.delta,
+            stats.trade_count,
+            stats.average_size.map_or("-".to_string(), |v| format!("{:.8}", v)),
+            stats.max_size.map_or("-".to_string(), |v| v.to_string())
+        ));
+    }
+    print_timestamped(&format!(
+        "CVD {} {:.8}",
+        product_id,
+        flow.cumulative_delta()
+    ));
+    for trade in large_trades {
+        print_timestamped(&format!(
+            "LARGE {} {} size={} price={} notional={:.2} time={}",
+            product_id,
+            if trade.is_buy { "BUY" } else { "SELL" },
+            trade.size,
+            display_price(&trade.price.to_string(), product_id),
+            trade.notional(),
+            trade.time.to_rfc3339()
+        ));
+    }
+}
+
+// Returns the colour used for a trade side.
+fn side_colour(side: &str) -> &'static str {
+    if side.eq_ignore_ascii_case("buy") {
+        GREEN
+    } else {
+        RED
+    }
 }
@@ -1,6 +1,18 @@
+pub mod arbitrage_handler;
 pub mod candles_handler;
+pub mod conversion_handler;
+pub mod correlation_handler;
+pub mod live_candles_handler;
+pub mod market_overview_handler;
 pub mod market_trades_handler;
+pub mod order_validation_handler;
 pub mod product_book_handler;
+pub mod products_browser;
 pub mod products_handler;
+pub mod screener_handler;
 pub mod server_time_handler;
+pub mod slippage_handler;
 pub mod specific_product_handler;
+pub mod trade_bars_handler;
+pub mod volatility_handler;
+pub mod watchlist_handler;
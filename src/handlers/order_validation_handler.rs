@@ -0,0 +1,134 @@
+use crate::api::specific_product::fetch_specific_product; // Importing the product request for trading rules.
+use crate::models::Product; // Importing the Product model.
+use crate::order_validation::{validate_order, OrderRequest, ValidatedOrder}; // Importing the order validator.
+use crate::slippage::OrderAmount; // Importing the order amount.
+use crate::terminal::{paint, GREEN, RED}; // Importing output helpers.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use rust_decimal::Decimal; // Importing Decimal for order amounts.
+use std::path::Path; // Importing Path for the product file.
+
+// Validates an order against a product's rules and prints the result.
+//
+// The product is read from a JSON file when one is given, so orders can be checked offline,
+// and fetched from the API otherwise. Returns an error when the order breaks any rule.
+pub async fn validate_order_command(
+    product_id: &str,
+    request: &OrderRequest,
+    product_file: Option<&Path>,
+    json: bool,
+) -> Result<()> {
+    let product: Product = match product_file {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        None => fetch_specific_product(product_id).await?,
+    };
+    if product.product_id != product_id {
+        return Err(anyhow!(
+            "product file describes {}, not {}",
+            product.product_id,
+            product_id
+        ));
+    }
+
+    let validated = validate_order(&product, request)?;
+    if json {
+        println!("{}", validation_json(product_id, &validated));
+    } else {
+        display_validation(product_id, request, &validated);
+    }
+
+    if validated.is_valid() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "order breaks {} rule(s)",
+            validated.violations.len()
+        ))
+    }
+}
+
+// Prints the rounded order and each violated rule.
+fn display_validation(product_id: &str, request: &OrderRequest, validated: &ValidatedOrder) {
+    let order = &validated.order;
+    let post_only = if order.post_only { " POST-ONLY" } else { "" };
+    println!(
+        "{} {} {}{}",
+        order.order_type, order.side, product_id, post_only
+    );
+    println!(
+        "{:<12} {}{}",
+        "Amount",
+        format_amount(&order.amount),
+        rounded_from(
+            amount_value(&order.amount),
+            Some(amount_value(&request.amount))
+        )
+    );
+    if let Some(price) = order.limit_price {
+        println!(
+            "{:<12} {}{}",
+            "Limit price",
+            price,
+            rounded_from(price, request.limit_price)
+        );
+    }
+    if let Some(notional) = validated.notional {
+        println!("{:<12} {}", "Notional", notional);
+    }
+
+    if validated.is_valid() {
+        println!("{}", paint("Order is valid", GREEN));
+    } else {
+        for violation in &validated.violations {
+            println!("{}", paint(&format!("- {}", violation), RED));
+        }
+    }
+}
+
+// Builds the machine-readable result.
+fn validation_json(product_id: &str, validated: &ValidatedOrder) -> serde_json::Value {
+    let order = &validated.order;
+    let (base_size, quote_size) = match order.amount {
+        OrderAmount::Base(size) => (Some(size.to_string()), None),
+        OrderAmount::Quote(funds) => (None, Some(funds.to_string())),
+    };
+    serde_json::json!({
+        "product_id": product_id,
+        "valid": validated.is_valid(),
+        "side": order.side.to_string(),
+        "order_type": order.order_type.to_string(),
+        "base_size": base_size,
+        "quote_size": quote_size,
+        "limit_price": order.limit_price.map(|p| p.to_string()),
+        "post_only": order.post_only,
+        "notional": validated.notional.map(|n| n.to_string()),
+        "violations": validated
+            .violations
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>(),
+    })
+}
+
+// Formats an order amount with its currency kind.
+fn format_amount(amount: &OrderAmount) -> String {
+    match amount {
+        OrderAmount::Base(size) => format!("{} base", size),
+        OrderAmount::Quote(funds) => format!("{} quote", funds),
+    }
+}
+
+// Returns the size or funds of an order amount.
+fn amount_value(amount: &OrderAmount) -> Decimal {
+    match amount {
+        OrderAmount::Base(size) => *size,
+        OrderAmount::Quote(funds) => *funds,
+    }
+}
+
+// Describes the original value when rounding changed it.
+fn rounded_from(rounded: Decimal, requested: Option<Decimal>) -> String {
+    match requested {
+        Some(requested) if requested != rounded => format!(" (rounded from {})", requested),
+        _ => String::new(),
+    }
+}
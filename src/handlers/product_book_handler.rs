@@ -1,15 +1,28 @@
-use crate::api::product_book::fetch_product_book; // Importing the fetch_product_book function.
+use crate::book_analytics::{BookStats, DEFAULT_IMBALANCE_LEVELS}; // Importing the book analytics.
+use crate::book_diff::{change_log_path, diff_order_books, BookChangeLog, BookDiff}; // Importing book diffing.
+use crate::conversion::{display_price, price_header}; // Importing the reporting currency display.
+use crate::market_data::{self, recv_batch, DataKind, MarketEvent, Subscription}; // Importing the market data interface.
 use crate::models::ProductBook; // Importing the ProductBook model.
+use crate::order_book::{parse_decimal, OrderBook, Side}; // Importing the local order book engine.
+use crate::terminal::{is_plain, paint, print_timestamped, GREEN, RED}; // Importing the output mode helpers.
 use anyhow::Result; // Importing the Result type from anyhow for error handling.
-use std::time::Duration; // Importing Duration for time handling.
 use tokio::sync::watch; // Importing watch from tokio for synchronization.
-use tokio::time; // Importing time module from tokio for sleep functionality;
 
-// Asynchronously fetches and displays the product book for a specific product.
+// Asynchronously maintains the order book for a specific product and redraws it on every update.
+// The book is dropped and rebuilt from a new snapshot whenever the source reports a reset.
 pub async fn fetch_product_book_handler(
     mut stop: watch::Receiver<()>,
     product_id: String,
 ) -> Result<()> {
+    let subscription = Subscription::new(std::slice::from_ref(&product_id), &[DataKind::Book]);
+    let mut events = market_data::source().subscribe(&subscription); // Book data from the selected transport.
+    let mut book = OrderBook::new(&product_id); // Local book, filled by the first snapshot.
+    let mut previous: Option<OrderBook> = None; // Book as of the last redraw, for diffing.
+    let mut change_log = match change_log_path() {
+        Some(path) => Some(BookChangeLog::open(path)?), // Optional log of every level change.
+        None => None,
+    };
+
     loop {
         // Using tokio::select! to handle concurrent tasks.
         tokio::select! {
@@ -18,36 +31,139 @@ pub async fn fetch_product_book_handler(
                 println!("Stopping fetch_product_book_handler"); // Print message when stopping.
                 break; // Exit the loop.
             }
-            // Adding a delay of 50 milliseconds before the next fetch.
-            _ = time::sleep(Duration::from_millis(750)) => {
-                // Fetching the product book and handling the result.
-                match fetch_product_book(&product_id).await {
-                    Ok(product_book) => {
-                        // Move cursor to the specific location for this handler
-                        print!("\x1B[1;150H");
-                        display_product_book(&product_book, 2); // Starting at line 2
+            batch = recv_batch(&mut events) => {
+                let Some(batch) = batch else { break }; // The source stopped.
+
+                let mut snapshot = false;
+                let mut changed = false;
+                for event in batch {
+                    match event {
+                        MarketEvent::BookSnapshot(new_book) if new_book.product_id() == product_id => {
+                            book = new_book;
+                            snapshot = true;
+                            changed = true;
+                        }
+                        // Deltas received before a snapshot are expected while rebuilding, and dropped.
+                        MarketEvent::BookDelta { product_id: id, side, price, size }
+                            if id == product_id && book.is_synced() =>
+                        {
+                            book.set_level(side, price, size);
+                            changed = true;
+                        }
+                        MarketEvent::Reset(reason) => {
+                            // A fresh snapshot follows, drop the book until it arrives.
+                            book.invalidate();
+                            display_feed_status(&format!("Rebuilding order book for {} ({})", product_id, reason));
+                        }
+                        MarketEvent::Notice(notice) => display_feed_status(&notice),
+                        _ => {}
+                    }
+                }
+
+                if changed && book.is_synced() {
+                    // Compare the whole book with the last redraw to find the changed levels.
+                    let diff = previous
+                        .as_ref()
+                        .map(|previous| diff_order_books(previous, &book));
+                    if let (Some(log), Some(diff)) = (change_log.as_mut(), diff.as_ref()) {
+                        if let Err(e) = log.write(diff) {
+                            display_feed_status(&format!("Error writing book change log: {}", e));
+                        }
+                    }
+                    previous = Some(book.clone());
+
+                    let stats = book.analytics(DEFAULT_IMBALANCE_LEVELS);
+                    let top = book.to_product_book(30); // Only the rendering is cut to 30 levels.
+                    if is_plain() {
+                        if let Ok(stats) = &stats {
+                            display_book_stats_plain(&product_id, stats); // Appending the analytics.
+                        }
+                        if snapshot {
+                            display_product_book_plain(&top); // Appending every level.
+                        } else {
+                            display_top_of_book_plain(&book); // Appending a timestamped line.
+                        }
+                    } else {
+                        match &stats {
+                            Ok(stats) => display_book_stats(&product_id, stats), // Stats strip on lines 1 and 2.
+                            Err(e) => println!("\x1B[1;150H{}\x1B[K\x1B[2;150H\x1B[K", e),
+                        }
+                        display_product_book(&top, 3, diff.as_ref()); // Starting at line 3
                     }
-                    Err(e) => println!("Error fetching product book for {}: {:?}", product_id, e),
                 }
             }
         }
     }
+
     Ok(())
 }
 
-// Function to display the product book with levels of depth.
-fn display_product_book(product_book: &ProductBook, start_y: usize) {
+// Function to show the transport state below the book, or as a timestamped line in plain mode.
+fn display_feed_status(status: &str) {
+    if is_plain() {
+        if !status.is_empty() {
+            print_timestamped(&format!("FEED {}", status));
+        }
+    } else {
+        println!("\x1B[34;150H{}\x1B[K", status);
+    }
+}
+
+// Function to display the book analytics as a two-line strip above the book.
+fn display_book_stats(product_id: &str, stats: &BookStats) {
+    println!(
+        "\x1B[1;150H{} {} ({:.2} bps)  Mid {}  Micro {}  Imb({}) {:+.3}  Skew {:+.3}\x1B[K",
+        price_header("Spread"),
+        display_price(&stats.spread.to_string(), product_id),
+        stats.spread_bps,
+        display_price(&stats.mid.to_string(), product_id),
+        display_price(&format!("{:.8}", stats.microprice.normalize()), product_id),
+        stats.imbalance_levels,
+        stats.imbalance,
+        stats.skew
+    );
+    let bands: Vec<String> = stats
+        .depth
+        .iter()
+        .map(|band| format!("±{}bps {}/{}", band.bps, band.bid_size, band.ask_size))
+        .collect();
+    println!("\x1B[2;150HDepth bid/ask  {}\x1B[K", bands.join("  "));
+}
+
+// Function to print the book analytics as a timestamped line, for plain output.
+fn display_book_stats_plain(product_id: &str, stats: &BookStats) {
+    let bands: Vec<String> = stats
+        .depth
+        .iter()
+        .map(|band| format!("depth_{}bps={}/{}", band.bps, band.bid_size, band.ask_size))
+        .collect();
+    print_timestamped(&format!(
+        "STATS {} spread={} spread_bps={:.2} mid={} microprice={} imbalance_{}={:.3} skew={:.3} {}",
+        product_id,
+        display_price(&stats.spread.to_string(), product_id),
+        stats.spread_bps,
+        display_price(&stats.mid.to_string(), product_id),
+        display_price(&format!("{:.8}", stats.microprice.normalize()), product_id),
+        stats.imbalance_levels,
+        stats.imbalance,
+        stats.skew,
+        bands.join(" ")
+    ));
+}
+
+// Function to display the product book with levels of depth, highlighting the sizes that changed.
+fn display_product_book(product_book: &ProductBook, start_y: usize, diff: Option<&BookDiff>) {
     let max_levels = 30
         .min(product_book.pricebook.bids.len())
         .min(product_book.pricebook.asks.len());
 
     // Print header
     println!(
-        "{}{:<10} {:<10} {:<10} {:<10}",
-        format!("\x1B[{};150H", start_y),
-        "Price",
+        "\x1B[{};150H{:<10} {:<10} {:<10} {:<10}",
+        start_y,
+        price_header("Price"),
         "Bid Size",
-        "Price",
+        price_header("Price"),
         "Ask Size"
     );
 
@@ -55,14 +171,65 @@ fn display_product_book(product_book: &ProductBook, start_y: usize) {
     for i in 0..max_levels {
         let bid = &product_book.pricebook.bids[i];
         let ask = &product_book.pricebook.asks[i];
+        let product_id = &product_book.pricebook.product_id;
 
         println!(
-            "{}{:<10} {:<10} {:<10} {:<10}",
-            format!("\x1B[{};150H", start_y + i + 1),
-            bid.price,
-            bid.size,
-            ask.price,
-            ask.size
+            "\x1B[{};150H{:<10} {} {:<10} {}",
+            start_y + i + 1,
+            display_price(&bid.price, product_id),
+            highlight_size(diff, Side::Bid, &bid.price, &bid.size),
+            display_price(&ask.price, product_id),
+            highlight_size(diff, Side::Ask, &ask.price, &ask.size)
         );
     }
 }
+
+// Pads a level size, coloured green when it went up and red when it went down since the last redraw.
+fn highlight_size(diff: Option<&BookDiff>, side: Side, price: &str, size: &str) -> String {
+    let cell = format!("{:<10}", size);
+    let change = match (diff, parse_decimal(price)) {
+        (Some(diff), Ok(price)) => diff.change_at(side, price),
+        _ => None,
+    };
+    match change {
+        Some(change) if change.kind().is_size_up() => paint(&cell, GREEN),
+        Some(_) => paint(&cell, RED),
+        None => cell,
+    }
+}
+
+// Function to print the product book levels as timestamped lines, for plain output.
+fn display_product_book_plain(product_book: &ProductBook) {
+    let book = &product_book.pricebook;
+    let max_levels = 30.min(book.bids.len()).min(book.asks.len());
+
+    for i in 0..max_levels {
+        print_timestamped(&format!(
+            "BOOK {} level={} bid={} bid_size={} ask={} ask_size={}",
+            book.product_id,
+            i + 1,
+            display_price(&book.bids[i].price, &book.product_id),
+            book.bids[i].size,
+            display_price(&book.asks[i].price, &book.product_id),
+            book.asks[i].size
+        ));
+    }
+}
+
+// Function to print the best bid and ask of the book as a timestamped line, for plain output.
+fn display_top_of_book_plain(book: &OrderBook) {
+    let format_level = |level: Option<(rust_decimal::Decimal, rust_decimal::Decimal)>| match level {
+        Some((price, size)) => format!(
+            "{} size={}",
+            display_price(&price.to_string(), book.product_id()),
+            size
+        ),
+        None => "-".to_string(),
+    };
+    print_timestamped(&format!(
+        "BOOK {} bid={} ask={}",
+        book.product_id(),
+        format_level(book.best_bid()),
+        format_level(book.best_ask())
+    ));
+}
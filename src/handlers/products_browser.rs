@@ -0,0 +1,560 @@
+use crate::config::Config; // Importing the config to persist the selected columns.
+use crate::conversion::{display_price, reporting_currency}; // Importing the reporting currency display.
+use crate::models::Product; // Importing the Product model.
+use crate::terminal::{highlight, terminal_size}; // Importing terminal helpers.
+use anyhow::Result; // Importing the Result type from anyhow for error handling.
+use crossterm::cursor::{Hide, MoveTo, Show}; // Importing cursor commands.
+use crossterm::event::{self, Event, KeyCode, KeyEventKind}; // Importing keyboard event handling.
+use crossterm::style::Print; // Importing the Print command.
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen}; // Importing terminal modes.
+use crossterm::{execute, queue}; // Importing macros to issue terminal commands.
+use std::cmp::Ordering; // Importing Ordering for sorting rows.
+use std::io::{self, Write}; // Importing I/O utilities for drawing.
+
+// Columns shown when the user has not picked any.
+const DEFAULT_COLUMNS: [&str; 7] = [
+    "product_id",
+    "price",
+    "price_percentage_change_24h",
+    "volume_24h",
+    "base_name",
+    "quote_name",
+    "status",
+];
+// Fields holding prices, shown in the reporting currency when one is set.
+const PRICE_FIELDS: [&str; 2] = ["price", "mid_market_price"];
+// Widest a column is allowed to grow, in characters.
+const MAX_COLUMN_WIDTH: usize = 24;
+// Rows used by the title, header and help lines.
+const CHROME_ROWS: usize = 3;
+
+// What the browser is currently showing.
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Table,  // Scrollable products table.
+    Search, // Table with the search query being edited.
+    Picker, // Column picker.
+    Detail, // Every field of the selected product.
+}
+
+// State of the interactive products browser.
+struct Browser<'a> {
+    products: &'a [Product],     // All fetched products.
+    names: Vec<&'static str>,    // Field names, shared by every product.
+    values: Vec<Vec<String>>,    // Raw field values per product, used to search and sort.
+    widths: Vec<usize>,          // Display width of each field.
+    columns: Vec<usize>,         // Visible fields, as indexes into `names`.
+    rows: Vec<usize>,            // Filtered and sorted products, as indexes into `products`.
+    selected: usize,             // Selected position in `rows`.
+    row_offset: usize,           // First row shown on screen.
+    focus: usize,                // Focused position in `columns`.
+    column_offset: usize,        // First scrollable column shown on screen.
+    sort: Option<(usize, bool)>, // Sort field and whether it is descending.
+    search: String,              // Incremental search query.
+    mode: Mode,                  // Current mode.
+    picker_cursor: usize,        // Highlighted field in the column picker.
+    picker_checked: Vec<bool>,   // Fields ticked in the column picker.
+    message: String,             // Feedback shown in the title line.
+    config: Option<Config>, // Loaded config, updated when columns are saved; None when it failed to load.
+}
+
+// Restores the terminal when the browser exits, even on error.
+struct RawModeGuard;
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// Runs the interactive products browser until the user quits.
+pub fn browse_products(products: &[Product]) -> Result<()> {
+    if products.is_empty() {
+        println!("No products to browse.");
+        return Ok(());
+    }
+
+    let mut browser = Browser::new(products);
+
+    terminal::enable_raw_mode()?;
+    let _guard = RawModeGuard;
+    execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+
+    loop {
+        browser.draw()?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if !browser.handle_key(key.code) {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl<'a> Browser<'a> {
+    // Builds the browser state, restoring the saved column selection.
+    fn new(products: &'a [Product]) -> Self {
+        let names: Vec<&'static str> = products[0].fields().iter().map(|(n, _)| *n).collect();
+        let values: Vec<Vec<String>> = products
+            .iter()
+            .map(|p| p.fields().into_iter().map(|(_, v)| v).collect())
+            .collect();
+
+        // Size each column to its widest displayed value, within limits.
+        let widths = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                products
+                    .iter()
+                    .zip(&values)
+                    .map(|(p, v)| display_value(name, &v[i], p).chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(name.len())
+                    .min(MAX_COLUMN_WIDTH)
+            })
+            .collect();
+
+        // A config that failed to load is never saved, so the user's file is not overwritten.
+        let (config, message) = match Config::load() {
+            Ok(config) => (Some(config), String::new()),
+            Err(e) => (None, format!("Error loading config: {}", e)),
+        };
+        let saved: Vec<&str> = match &config {
+            Some(config) if !config.product_columns.is_empty() => {
+                config.product_columns.iter().map(|c| c.as_str()).collect()
+            }
+            _ => DEFAULT_COLUMNS.to_vec(),
+        };
+        let mut columns: Vec<usize> = saved
+            .iter()
+            .filter_map(|c| names.iter().position(|n| n == c))
+            .collect();
+        if columns.is_empty() {
+            columns.push(0);
+        }
+
+        let mut browser = Browser {
+            products,
+            picker_checked: vec![false; names.len()],
+            names,
+            values,
+            widths,
+            columns,
+            rows: Vec::new(),
+            selected: 0,
+            row_offset: 0,
+            focus: 0,
+            column_offset: 0,
+            sort: None,
+            search: String::new(),
+            mode: Mode::Table,
+            picker_cursor: 0,
+            message,
+            config,
+        };
+        browser.refresh_rows();
+        browser
+    }
+
+    // Returns a product's field as displayed, prices in the reporting currency when one is set.
+    fn cell(&self, product: usize, field: usize) -> String {
+        display_value(
+            self.names[field],
+            &self.values[product][field],
+            &self.products[product],
+        )
+    }
+
+    // Applies the search filter and sort order, keeping the selection in range.
+    fn refresh_rows(&mut self) {
+        let query = self.search.to_lowercase();
+        let product_id = self.field_index("product_id");
+        let display_name = self.field_index("display_name");
+        let base_name = self.field_index("base_name");
+        let quote_name = self.field_index("quote_name");
+
+        let mut rows: Vec<usize> = (0..self.products.len())
+            .filter(|&i| {
+                query.is_empty()
+                    || [product_id, display_name, base_name, quote_name]
+                        .iter()
+                        .flatten()
+                        .any(|&f| self.values[i][f].to_lowercase().contains(&query))
+            })
+            .collect();
+
+        if let Some((field, descending)) = self.sort {
+            rows.sort_by(|&a, &b| {
+                let ordering = compare_values(&self.values[a][field], &self.values[b][field]);
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        self.rows = rows;
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    // Returns the index of the named field.
+    fn field_index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| *n == name)
+    }
+
+    // Handles a key press, returning false when the browser should close.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        match self.mode {
+            Mode::Table => return self.handle_table_key(code),
+            Mode::Search => match code {
+                KeyCode::Char(c) => self.search.push(c),
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Enter => self.mode = Mode::Table, // Keeping the filter.
+                KeyCode::Esc => {
+                    self.search.clear();
+                    self.mode = Mode::Table;
+                }
+                _ => return true,
+            },
+            Mode::Picker => match code {
+                KeyCode::Up => self.picker_cursor = self.picker_cursor.saturating_sub(1),
+                KeyCode::Down => {
+                    self.picker_cursor = (self.picker_cursor + 1).min(self.names.len() - 1)
+                }
+                KeyCode::Char(' ') => {
+                    self.picker_checked[self.picker_cursor] =
+                        !self.picker_checked[self.picker_cursor]
+                }
+                KeyCode::Enter => self.save_columns(),
+                KeyCode::Esc => self.mode = Mode::Table,
+                _ => {}
+            },
+            Mode::Detail => {
+                if matches!(code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                    self.mode = Mode::Table;
+                }
+            }
+        }
+        self.refresh_rows();
+        true
+    }
+
+    // Handles a key press while browsing the table.
+    fn handle_table_key(&mut self, code: KeyCode) -> bool {
+        let page = self.visible_rows().max(1);
+        let last = self.rows.len().saturating_sub(1);
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(last),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(page),
+            KeyCode::PageDown => self.selected = (self.selected + page).min(last),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::Left => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Right => self.focus = (self.focus + 1).min(self.columns.len() - 1),
+            KeyCode::Char('s') => {
+                // Sorting by the focused column, flipping direction on repeat.
+                let field = self.columns[self.focus];
+                self.sort = match self.sort {
+                    Some((f, descending)) if f == field => Some((field, !descending)),
+                    _ => Some((field, false)),
+                };
+                self.refresh_rows();
+            }
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Char('c') => {
+                for (i, checked) in self.picker_checked.iter_mut().enumerate() {
+                    *checked = self.columns.contains(&i);
+                }
+                self.mode = Mode::Picker;
+            }
+            KeyCode::Enter if !self.rows.is_empty() => self.mode = Mode::Detail,
+            _ => {}
+        }
+        true
+    }
+
+    // Applies the picked columns and saves them to the config.
+    fn save_columns(&mut self) {
+        let columns: Vec<usize> = (0..self.names.len())
+            .filter(|&i| self.picker_checked[i])
+            .collect();
+        if columns.is_empty() {
+            self.message = "Pick at least one column.".to_string();
+            return;
+        }
+
+        self.columns = columns;
+        self.focus = 0;
+        self.column_offset = 0;
+        self.mode = Mode::Table;
+        let Some(config) = &mut self.config else {
+            self.message =
+                "Columns applied, not saved: the config file failed to load.".to_string();
+            return;
+        };
+        config.product_columns = self
+            .columns
+            .iter()
+            .map(|&i| self.names[i].to_string())
+            .collect();
+        self.message = match config.save() {
+            Ok(()) => "Columns saved.".to_string(),
+            Err(e) => format!("Error saving columns: {}", e),
+        };
+    }
+
+    // Number of table rows that fit on screen.
+    fn visible_rows(&self) -> usize {
+        terminal_size().1.saturating_sub(CHROME_ROWS)
+    }
+
+    // Draws the current mode to the terminal.
+    fn draw(&mut self) -> Result<()> {
+        let (width, height) = terminal_size();
+        let lines = match self.mode {
+            Mode::Table | Mode::Search => self.render_table(width, height),
+            Mode::Picker => self.render_picker(height),
+            Mode::Detail => self.render_detail(width, height),
+        };
+
+        let mut out = io::stdout();
+        queue!(out, Clear(ClearType::All))?;
+        for (y, line) in lines.iter().take(height).enumerate() {
+            queue!(out, MoveTo(0, y as u16), Print(line))?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    // Renders the products table with the title, header and help lines.
+    fn render_table(&mut self, width: usize, height: usize) -> Vec<String> {
+        let body_rows = height.saturating_sub(CHROME_ROWS).max(1);
+
+        // Keep the selected row on screen.
+        if self.selected < self.row_offset {
+            self.row_offset = self.selected;
+        } else if self.selected >= self.row_offset + body_rows {
+            self.row_offset = self.selected + 1 - body_rows;
+        }
+
+        let visible = self.visible_columns(width);
+
+        let sort_label = match self.sort {
+            Some((field, descending)) => format!(
+                "{} {}",
+                self.names[field],
+                if descending { "desc" } else { "asc" }
+            ),
+            None => "none".to_string(),
+        };
+        let search_label = if self.mode == Mode::Search {
+            format!("/{}_", self.search)
+        } else if self.search.is_empty() {
+            "-".to_string()
+        } else {
+            format!("/{}", self.search)
+        };
+        let currency_label = reporting_currency().map_or(String::new(), |currency| {
+            format!("  |  Prices: {}", currency)
+        });
+        let mut lines = vec![fit(
+            &format!(
+                "Products: {}/{}  |  Sort: {}  |  Search: {}{}  {}",
+                self.rows.len(),
+                self.products.len(),
+                sort_label,
+                search_label,
+                currency_label,
+                self.message
+            ),
+            width,
+        )];
+
+        // Header, with the focused column highlighted.
+        let mut header = String::new();
+        for &position in &visible {
+            let field = self.columns[position];
+            let cell = format!(
+                "{:<w$} ",
+                fit(self.names[field], self.widths[field]),
+                w = self.widths[field]
+            );
+            if position == self.focus {
+                header.push_str(&highlight(&cell));
+            } else {
+                header.push_str(&cell);
+            }
+        }
+        lines.push(header);
+
+        for (offset, &product) in self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(self.row_offset)
+            .take(body_rows)
+        {
+            let mut line = String::new();
+            for &position in &visible {
+                let field = self.columns[position];
+                line.push_str(&format!(
+                    "{:<w$} ",
+                    fit(&self.cell(product, field), self.widths[field]),
+                    w = self.widths[field]
+                ));
+            }
+            let line = fit(&line, width);
+            if offset == self.selected {
+                lines.push(highlight(&format!("{:<w$}", line, w = width)));
+            } else {
+                lines.push(line);
+            }
+        }
+
+        // Pad so the help line stays at the bottom.
+        while lines.len() < height - 1 {
+            lines.push(String::new());
+        }
+        lines.push(fit(
+            match self.mode {
+                Mode::Search => "Type to search  Enter keep filter  Esc clear",
+                _ => "↑↓/PgUp/PgDn move  ←→ column  s sort  / search  c columns  Enter details  q quit",
+            },
+            width,
+        ));
+        lines
+    }
+
+    // Works out which columns fit on screen, keeping the first column frozen on the left.
+    fn visible_columns(&mut self, width: usize) -> Vec<usize> {
+        // Scroll horizontally so the focused column is shown.
+        if self.focus > 0 && self.focus - 1 < self.column_offset {
+            self.column_offset = self.focus - 1;
+        }
+        loop {
+            let mut visible = vec![0];
+            let mut used = self.widths[self.columns[0]] + 1;
+            for position in (1 + self.column_offset)..self.columns.len() {
+                let needed = self.widths[self.columns[position]] + 1;
+                if used + needed > width && visible.len() > 1 {
+                    break;
+                }
+                used += needed;
+                visible.push(position);
+            }
+            if self.focus == 0 || visible.contains(&self.focus) {
+                return visible;
+            }
+            self.column_offset += 1;
+        }
+    }
+
+    // Renders the column picker.
+    fn render_picker(&self, height: usize) -> Vec<String> {
+        let body_rows = height.saturating_sub(CHROME_ROWS).max(1);
+        let offset = (self.picker_cursor + 1).saturating_sub(body_rows);
+
+        let mut lines = vec!["Choose columns".to_string(), String::new()];
+        for (i, name) in self.names.iter().enumerate().skip(offset).take(body_rows) {
+            let line = format!(
+                "[{}] {}",
+                if self.picker_checked[i] { "x" } else { " " },
+                name
+            );
+            if i == self.picker_cursor {
+                lines.push(highlight(&line));
+            } else {
+                lines.push(line);
+            }
+        }
+        while lines.len() < height - 1 {
+            lines.push(String::new());
+        }
+        lines.push("↑↓ move  Space toggle  Enter save  Esc cancel".to_string());
+        lines
+    }
+
+    // Renders every field of the selected product, in as many columns as needed to fit.
+    fn render_detail(&self, width: usize, height: usize) -> Vec<String> {
+        let product = self.rows[self.selected];
+        let body_rows = height.saturating_sub(CHROME_ROWS).max(1);
+        let name_width = self.names.iter().map(|n| n.len()).max().unwrap_or(0);
+        let columns = self.names.len().div_ceil(body_rows);
+        let column_width = (width / columns).max(name_width + 4);
+
+        let mut lines = vec![
+            format!("Details: {}", self.values[product][0]),
+            String::new(),
+        ];
+        for row in 0..body_rows.min(self.names.len()) {
+            let mut line = String::new();
+            for column in 0..columns {
+                let field = column * body_rows + row;
+                if field >= self.names.len() {
+                    break;
+                }
+                let entry = format!(
+                    "{:<nw$}  {}",
+                    self.names[field],
+                    self.cell(product, field),
+                    nw = name_width
+                );
+                line.push_str(&format!(
+                    "{:<cw$}",
+                    fit(&entry, column_width - 1),
+                    cw = column_width
+                ));
+            }
+            lines.push(fit(&line, width));
+        }
+        while lines.len() < height - 1 {
+            lines.push(String::new());
+        }
+        lines.push("Esc/Enter back".to_string());
+        lines
+    }
+}
+
+// Formats a raw field value for display, converting prices into the reporting currency.
+fn display_value(name: &str, value: &str, product: &Product) -> String {
+    if PRICE_FIELDS.contains(&name) {
+        display_price(value, &product.product_id)
+    } else {
+        value.to_string()
+    }
+}
+
+// Compares two field values numerically when both are numbers, otherwise as text.
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+// Truncates text to the given width, marking the cut with an ellipsis.
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut truncated: String = text.chars().take(width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
@@ -1,5 +1,9 @@
 use crate::api::products::fetch_products; // Importing the fetch_products function.
+use crate::conversion::{display_price, price_header}; // Importing the reporting currency display.
+use crate::handlers::products_browser::browse_products; // Importing the interactive products browser.
+use crate::handlers::screener_handler::run_screener; // Importing the product screener.
 use crate::models::Product; // Importing the Product model.
+use crate::terminal::is_plain; // Importing the output mode check.
 use anyhow::Result; // Importing the Result type from anyhow for error handling.
 use prettytable::{format, row, Cell, Row, Table}; // Importing prettytable components for table formatting and display.
 use std::io::{self, Write}; // Importing IO components for user input/output handling.
@@ -20,6 +24,8 @@ pub async fn fetch_products_handler() -> Result<()> {
         println!("Choose display mode:");
         println!("1. Synthetic view");
         println!("2. Complete view");
+        println!("3. Interactive browser");
+        println!("4. Screener");
         print!("Enter your choice: ");
         io::stdout().flush().unwrap(); // Flush the output buffer.
 
@@ -31,6 +37,13 @@ pub async fn fetch_products_handler() -> Result<()> {
         match choice {
             1 => display_synthetic_view(&products), // Display synthetic view.
             2 => display_complete_view(&products),  // Display complete view.
+            3 if is_plain() => {
+                // The browser needs an interactive terminal.
+                println!("Interactive browser needs a terminal, showing the complete view.");
+                display_complete_view(&products);
+            }
+            3 => browse_products(&products)?, // Browse products interactively.
+            4 => run_screener(&products)?,    // Filter and sort products by an expression.
             _ => {
                 println!("Invalid choice, please try again."); // Handle invalid input.
                 continue;
@@ -46,7 +59,7 @@ fn display_synthetic_view(products: &Vec<Product>) {
     let mut table = Table::new();
     table.add_row(row![
         "Product ID",
-        "Price",
+        price_header("Price"),
         "24h Change %",
         "Volume 24h",
         "Base Name",
@@ -58,7 +71,7 @@ fn display_synthetic_view(products: &Vec<Product>) {
     for product in products {
         table.add_row(row![
             product.product_id,
-            product.price,
+            display_price(&product.price, &product.product_id),
             product.price_percentage_change_24h,
             product.volume_24h,
             product.base_name,
@@ -76,7 +89,7 @@ fn display_complete_view(products: &Vec<Product>) {
     table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR); // Setting table format.
     table.add_row(Row::new(vec![
         Cell::new("Product ID"),
-        Cell::new("Price"),
+        Cell::new(&price_header("Price")),
         Cell::new("24h Change %"),
         Cell::new("Volume 24h"),
         Cell::new("Volume 24h Change %"),
@@ -101,7 +114,7 @@ fn display_complete_view(products: &Vec<Product>) {
         Cell::new("Quote Currency ID"),
         Cell::new("Base Currency ID"),
         Cell::new("FCM Trading Session Details"),
-        Cell::new("Mid Market Price"),
+        Cell::new(&price_header("Mid Market Price")),
         Cell::new("Alias"),
         Cell::new("Alias To"),
         Cell::new("Base Display Symbol"),
@@ -117,7 +130,7 @@ fn display_complete_view(products: &Vec<Product>) {
     for product in products {
         table.add_row(Row::new(vec![
             Cell::new(&product.product_id),
-            Cell::new(&product.price),
+            Cell::new(&display_price(&product.price, &product.product_id)),
             Cell::new(&product.price_percentage_change_24h),
             Cell::new(&product.volume_24h),
             Cell::new(&product.volume_percentage_change_24h),
@@ -142,7 +155,10 @@ fn display_complete_view(products: &Vec<Product>) {
             Cell::new(&product.quote_currency_id),
             Cell::new(&product.base_currency_id),
             Cell::new(&format!("{:?}", product.fcm_trading_session_details)),
-            Cell::new(&product.mid_market_price),
+            Cell::new(&display_price(
+                &product.mid_market_price,
+                &product.product_id,
+            )),
             Cell::new(&product.alias),
             Cell::new(&format!("{:?}", product.alias_to)),
             Cell::new(&product.base_display_symbol),
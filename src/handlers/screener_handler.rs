@@ -0,0 +1,189 @@
+use crate::api::products::fetch_products; // Importing the fetch_products function.
+use crate::config::Config; // Importing the config holding the saved screens.
+use crate::conversion::{display_price, price_header}; // Importing the reporting currency display.
+use crate::models::Product; // Importing the Product model.
+use crate::screener::{CompiledScreen, Screen}; // Importing the product screener.
+use crate::terminal::prompt; // Importing the shared prompt helper.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use prettytable::{Cell, Row, Table}; // Importing prettytable components for the results table.
+
+// Columns always shown in the results, before the fields the screen reads.
+const SCREEN_COLUMNS: [&str; 6] = [
+    "product_id",
+    "price",
+    "price_percentage_change_24h",
+    "volume_24h",
+    "quote_currency_id",
+    "status",
+];
+
+// Prompts for a filter or saved screen, shows the matching products and offers to save the screen.
+pub fn run_screener(products: &[Product]) -> Result<()> {
+    let mut config = Config::load()?;
+    if !config.screens.is_empty() {
+        let names: Vec<&str> = config.screens.keys().map(String::as_str).collect();
+        println!("Saved screens: {}", names.join(", "));
+    }
+    let input =
+        prompt("Enter a filter, e.g. quote=USD and volume_24h > 1e6, or a saved screen name: ");
+
+    if let Some(screen) = config.screens.get(&input) {
+        show_screen(products, &screen.compile()?);
+        return Ok(());
+    }
+    let sort = prompt("Sort by, e.g. volume_24h:desc,product_id (leave empty for none): ");
+    let limit = prompt("Show at most how many products? (leave empty for all): ");
+    let screen = Screen {
+        filter: input,
+        sort: parse_sort_keys(&sort),
+        limit: parse_limit(&limit)?,
+    };
+    show_screen(products, &screen.compile()?);
+
+    let name = prompt("Save this screen as (leave empty to skip): ");
+    if !name.is_empty() {
+        config.screens.insert(name.clone(), screen);
+        config.save()?;
+        println!("Saved screen {}.", name);
+    }
+    Ok(())
+}
+
+// Fetches the products and shows those a screen keeps, either given or saved under a name.
+//
+// When a saved screen is named, a filter, sort keys or limit given alongside replace its own,
+// and with `save` the screen that ran is saved under that name.
+pub async fn screen_command(
+    filter: Option<&str>,
+    sort: Option<&str>,
+    limit: Option<&str>,
+    name: Option<&str>,
+    save: Option<&str>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let mut screen = match name {
+        Some(name) => config
+            .screens
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("no saved screen named {}", name))?,
+        None => Screen::default(),
+    };
+    if let Some(filter) = filter {
+        screen.filter = filter.to_string();
+    }
+    if let Some(sort) = sort {
+        screen.sort = parse_sort_keys(sort);
+    }
+    if let Some(limit) = limit {
+        screen.limit = parse_limit(limit)?;
+    }
+    let compiled = screen.compile()?;
+
+    let products = fetch_products().await?;
+    show_screen(&products, &compiled);
+
+    if let Some(save) = save {
+        config.screens.insert(save.to_string(), screen);
+        config.save()?;
+        println!("Saved screen {}.", save);
+    }
+    Ok(())
+}
+
+// Prints the saved screens.
+pub fn list_screens() -> Result<()> {
+    let config = Config::load()?;
+    if config.screens.is_empty() {
+        println!("No saved screens.");
+    }
+    for (name, screen) in &config.screens {
+        let mut line = format!("{}: {}", name, screen.filter);
+        if !screen.sort.is_empty() {
+            line.push_str(&format!("  sort {}", screen.sort.join(",")));
+        }
+        if let Some(limit) = screen.limit {
+            line.push_str(&format!("  limit {}", limit));
+        }
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+// Removes a saved screen.
+pub fn delete_screen(name: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    if config.screens.remove(name).is_none() {
+        return Err(anyhow!("no saved screen named {}", name));
+    }
+    config.save()?;
+    println!("Deleted screen {}.", name);
+    Ok(())
+}
+
+// Function to display the products a screen keeps, with the fields it reads as extra columns.
+fn show_screen(products: &[Product], screen: &CompiledScreen) {
+    let matches = screen.apply(products);
+    let mut columns: Vec<&str> = SCREEN_COLUMNS.to_vec();
+    columns.extend(
+        screen
+            .fields()
+            .into_iter()
+            .filter(|field| !SCREEN_COLUMNS.contains(field)),
+    );
+
+    let mut table = Table::new();
+    table.add_row(Row::new(
+        columns
+            .iter()
+            .map(|column| match *column {
+                "price" | "mid_market_price" => Cell::new(&price_header(column)),
+                _ => Cell::new(column),
+            })
+            .collect(),
+    ));
+    for product in &matches {
+        let fields = product.fields();
+        table.add_row(Row::new(
+            columns
+                .iter()
+                .map(|column| {
+                    let value = fields
+                        .iter()
+                        .find(|(name, _)| name == column)
+                        .map_or("", |(_, value)| value.as_str());
+                    match *column {
+                        "price" | "mid_market_price" => {
+                            Cell::new(&display_price(value, &product.product_id))
+                        }
+                        _ => Cell::new(value),
+                    }
+                })
+                .collect(),
+        ));
+    }
+    table.printstd(); // Printing the table.
+    println!("{} of {} products shown.", matches.len(), products.len());
+}
+
+// Splits comma-separated sort keys.
+fn parse_sort_keys(keys: &str) -> Vec<String> {
+    keys.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Parses a row limit, empty meaning no limit.
+fn parse_limit(limit: &str) -> Result<Option<usize>> {
+    if limit.is_empty() {
+        return Ok(None);
+    }
+    limit
+        .parse::<usize>()
+        .ok()
+        .filter(|limit| *limit > 0)
+        .map(Some)
+        .ok_or_else(|| anyhow!("invalid limit {}", limit))
+}
@@ -0,0 +1,120 @@
+use crate::api::product_book::fetch_product_book_depth; // Importing the deep product book request.
+use crate::api::specific_product::fetch_specific_product; // Importing the product request for size rules.
+use crate::cli::{parse_amount, parse_side}; // Importing the argument parsers.
+use crate::conversion::{display_price, price_header}; // Importing the reporting currency display.
+use crate::slippage::{estimate_market_order, OrderAmount, OrderSide, SlippageEstimate}; // Importing the slippage calculator.
+use crate::task_runner::prompt_for_product_id; // Importing the product ID prompt.
+use crate::terminal::{paint, prompt, RED}; // Importing output helpers.
+use anyhow::Result; // Importing the Result type from anyhow for error handling.
+
+// Number of levels per side requested when walking the book.
+const BOOK_DEPTH: u32 = 500;
+
+// Prompts for a market order and shows what it would cost against the current book.
+pub async fn slippage_handler() -> Result<()> {
+    let product_id = prompt_for_product_id();
+    let side = match parse_side(&prompt("Enter the side (buy or sell): ")) {
+        Ok(side) => side,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    let size = match parse_amount(&prompt("Enter the size: ")) {
+        Ok(size) => size,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    let quote = prompt("Is the size in the base or quote currency? (b/q): ");
+    let amount = if quote.eq_ignore_ascii_case("q") {
+        OrderAmount::Quote(size)
+    } else {
+        OrderAmount::Base(size)
+    };
+
+    if let Err(e) = estimate_slippage(&product_id, side, amount).await {
+        println!("Error estimating slippage for {}: {:?}", product_id, e);
+    }
+    Ok(())
+}
+
+// Fetches the product and its book, then prints the estimate for a market order.
+pub async fn estimate_slippage(
+    product_id: &str,
+    side: OrderSide,
+    amount: OrderAmount,
+) -> Result<()> {
+    let product = fetch_specific_product(product_id).await?;
+    let book = fetch_product_book_depth(product_id, BOOK_DEPTH).await?;
+    let estimate = estimate_market_order(&book, &product, side, amount)?;
+    display_estimate(product_id, &amount, &estimate);
+    Ok(())
+}
+
+// Prints the estimate, one figure per line.
+fn display_estimate(product_id: &str, amount: &OrderAmount, estimate: &SlippageEstimate) {
+    let requested = match amount {
+        OrderAmount::Base(size) => format!("{} base", size),
+        OrderAmount::Quote(notional) => format!("{} quote", notional),
+    };
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    println!("Market {} {} {}", estimate.side, requested, product_id);
+    println!("{:<20} {}", "Base size", estimate.base_size);
+    let price = |price: String| display_price(&price, product_id);
+    println!(
+        "{:<20} {}",
+        price_header("Mid price"),
+        price(estimate.mid.to_string())
+    );
+    println!(
+        "{:<20} {}",
+        price_header("Average price"),
+        optional(
+            estimate
+                .average_price
+                .map(|p| price(p.round_dp(8).normalize().to_string()))
+        )
+    );
+    println!(
+        "{:<20} {}",
+        price_header("Worst price"),
+        optional(estimate.worst_price.map(|p| price(p.to_string())))
+    );
+    println!(
+        "{:<20} {}",
+        "Slippage vs mid",
+        optional(estimate.slippage_bps.map(|bps| format!("{:.2} bps", bps)))
+    );
+    println!("{:<20} {}", "Levels consumed", estimate.levels_consumed);
+    println!(
+        "{:<20} {} base for {} quote",
+        "Filled",
+        estimate.filled_base,
+        estimate.filled_quote.round_dp(8).normalize()
+    );
+    if !estimate.unfilled_base.is_zero() {
+        println!(
+            "{}",
+            paint(
+                &format!(
+                    "{:<20} {} base, the book ran out",
+                    "Unfilled", estimate.unfilled_base
+                ),
+                RED
+            )
+        );
+    }
+    if !estimate.unfilled_quote.is_zero() {
+        println!(
+            "{:<20} {} quote",
+            "Unfilled",
+            estimate.unfilled_quote.round_dp(8).normalize()
+        );
+    }
+    if let Some(limit) = &estimate.size_limit {
+        println!("{}", paint(&format!("Warning: {}", limit), RED));
+    }
+}
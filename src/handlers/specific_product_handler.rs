@@ -1,29 +1,50 @@
-use crate::api::specific_product::fetch_specific_product; // Importing the fetch_specific_product function.
-use crate::models::Product; // Importing the Product model.
+use crate::conversion::{display_price, price_header}; // Importing the reporting currency display.
+use crate::market_data::{self, recv_batch, DataKind, MarketEvent, Subscription}; // Importing the market data interface.
+use crate::terminal::{is_plain, print_timestamped}; // Importing the output mode helpers.
+use crate::websocket::messages::{ProductStatus, Ticker}; // Importing the ticker and status payloads.
 use anyhow::Result; // Importing the Result type from anyhow for error handling.
-use std::time::Duration; // Importing Duration from std for handling time intervals.
 use tokio::sync::watch; // Importing watch from tokio for asynchronous synchronization.
-use tokio::time; // Importing time from tokio for time handling;
 
-// Asynchronously fetches and displays specific product information repeatedly until stopped.
+// Asynchronously receives and displays specific product information until stopped.
 pub async fn fetch_specific_product_handler(
     mut stop: watch::Receiver<()>,
     product_id: String,
 ) -> Result<()> {
+    let subscription = Subscription::new(
+        std::slice::from_ref(&product_id),
+        &[DataKind::Ticker, DataKind::Products],
+    );
+    let mut events = market_data::source().subscribe(&subscription); // Product data from the selected transport.
+    let mut ticker: Option<Ticker> = None; // Latest ticker.
+    let mut status: Option<ProductStatus> = None; // Latest product status.
+
     loop {
         tokio::select! {
             _ = stop.changed() => { // Check if the stop signal has been received.
                 println!("Stopping fetch_specific_product_handler");
                 break;
             }
-            _ = time::sleep(Duration::from_millis(750)) => { // Wait for 50 milliseconds before fetching data again.
-                match fetch_specific_product(&product_id).await { // Fetch the specific product data.
-                    Ok(product) => {
+            batch = recv_batch(&mut events) => {
+                let Some(batch) = batch else { break }; // The source stopped.
+
+                for event in batch {
+                    match event {
+                        MarketEvent::Ticker(update) if update.product_id == product_id => ticker = Some(update),
+                        MarketEvent::ProductUpdate(update) if update.id == product_id => status = Some(update),
+                        MarketEvent::Notice(notice) => display_product_status(&notice), // Show transport errors and state changes.
+                        _ => {}
+                    }
+                }
+
+                // The product is shown once its price is known.
+                if let Some(ticker) = &ticker {
+                    if is_plain() {
+                        display_specific_product_plain(ticker, status.as_ref()); // Appending a timestamped line.
+                    } else {
                         // Move cursor to the specific location for this handler
                         print!("\x1B[45;1H");
-                        display_specific_product(&product); // Display the product data if successful.
-                    },
-                    Err(e) => println!("Error fetching product data for {}: {:?}", product_id, e), // Print error message if there's an error.
+                        display_specific_product(ticker, status.as_ref()); // Display the product data.
+                    }
                 }
             }
         }
@@ -32,11 +53,13 @@ pub async fn fetch_specific_product_handler(
 }
 
 // Displays the specific product information in a formatted table.
-fn display_specific_product(product: &Product) {
+fn display_specific_product(ticker: &Ticker, status: Option<&ProductStatus>) {
+    let (product_status, base, quote) = status_fields(status);
+
     println!(
         "{:<20} {:<15} {:<20} {:<20} {:<20} {:<10} {:<10} {:<10}",
         "Product ID",
-        "Price",
+        price_header("Price"),
         "24h Change %",
         "Volume 24h",
         "Volume 24h Change %",
@@ -46,14 +69,71 @@ fn display_specific_product(product: &Product) {
     );
 
     println!(
-        "{:<20} {:<15} {:<20} {:<20} {:<20} {:<10} {:<10} {:<10}",
-        product.product_id,
-        product.price,
-        product.price_percentage_change_24h,
-        product.volume_24h,
-        product.volume_percentage_change_24h,
-        product.status,
-        product.base_name,
-        product.quote_name
+        "{:<20} {:<15} {:<20} {:<20} {:<20} {:<10} {:<10} {:<10}\x1B[K",
+        ticker.product_id,
+        display_price(&ticker.price, &ticker.product_id),
+        ticker.price_percent_chg_24_h,
+        ticker.volume_24_h,
+        or_dash(&ticker.volume_percent_chg_24_h),
+        product_status,
+        base,
+        quote
     );
 }
+
+// Prints the specific product information as a timestamped line, for plain output.
+fn display_specific_product_plain(ticker: &Ticker, status: Option<&ProductStatus>) {
+    let (product_status, base, quote) = status_fields(status);
+    print_timestamped(&format!(
+        "PRODUCT {} price={} change_24h={} volume_24h={} volume_change_24h={} status={} base={} quote={}",
+        ticker.product_id,
+        display_price(&ticker.price, &ticker.product_id),
+        ticker.price_percent_chg_24_h,
+        ticker.volume_24_h,
+        or_dash(&ticker.volume_percent_chg_24_h),
+        product_status,
+        base,
+        quote
+    ));
+}
+
+// Displays a transport notice on the line below the product, or as a timestamped line.
+fn display_product_status(status: &str) {
+    if is_plain() {
+        print_timestamped(&format!("FEED {}", status));
+    } else {
+        println!("\x1B[48;1H{}\x1B[K", status);
+    }
+}
+
+// Returns the status and the base and quote names, or dashes until the first status arrives.
+//
+// The streaming feed does not send currency names, so their IDs are shown instead.
+fn status_fields(status: Option<&ProductStatus>) -> (&str, &str, &str) {
+    match status {
+        Some(status) => (
+            status.status.as_str(),
+            name_or_id(&status.base_name, &status.base_currency),
+            name_or_id(&status.quote_name, &status.quote_currency),
+        ),
+        None => ("-", "-", "-"),
+    }
+}
+
+// Returns a currency name, or its ID when the name is unknown.
+fn name_or_id<'a>(name: &'a str, id: &'a str) -> &'a str {
+    if name.is_empty() {
+        id
+    } else {
+        name
+    }
+}
+
+// Returns the value, or a dash when the transport does not provide it.
+fn or_dash(value: &str) -> &str {
+    if value.is_empty() {
+        "-"
+    } else {
+        value
+    }
+}
@@ -0,0 +1,172 @@
+use crate::bar_builder::{build_bars, BarBuilder, BarRule, TradeBar}; // Importing the trade bar builders.
+use crate::candle_export::{export_bars, ExportFormat}; // Importing the CSV and JSON export.
+use crate::chart::render_candlestick_chart; // Importing the candlestick chart renderer.
+use crate::conversion::{candles_to_reporting, display_price, reporting_currency}; // Importing the reporting currency display.
+use crate::market_data::{self, recv_batch, DataKind, MarketEvent, Subscription}; // Importing the market data interface.
+use crate::models::{Candle, MarketTrade, MarketTradesResponse}; // Importing the Candle and trade models.
+use crate::resample::MissingCandles; // Importing the empty interval policy.
+use crate::terminal::{is_plain, print_timestamped, terminal_size}; // Importing output helpers.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use chrono::Utc; // Importing Utc to close bars on time.
+use std::collections::VecDeque; // Importing VecDeque for the bar history.
+use std::path::Path; // Importing Path for the trades and output files.
+use std::time::Duration; // Importing Duration for the redraw interval.
+use tokio::sync::watch; // Importing watch from tokio for synchronization.
+use tokio::time; // Importing tokio time utilities.
+
+// Number of completed bars kept for the chart.
+const BARS_KEPT: usize = 500;
+// How long a bar stays open after its interval ends, for trades that arrive late.
+const CLOSE_GRACE_SECS: i64 = 2;
+
+// Receives trades for a product, builds bars from them by the rule and charts the completed
+// bars with the one still forming.
+pub async fn trade_bars_handler(
+    mut stop: watch::Receiver<()>,
+    product_id: String,
+    rule: BarRule,
+) -> Result<()> {
+    let subscription = Subscription::new(std::slice::from_ref(&product_id), &[DataKind::Trades]);
+    let mut events = market_data::source().subscribe(&subscription); // Trades from the selected transport.
+    let mut builder = BarBuilder::new(rule, MissingCandles::FillFlat);
+    let mut bars: VecDeque<TradeBar> = VecDeque::new(); // Completed bars, oldest first.
+    let mut refresh = time::interval(Duration::from_secs(1)); // Closes bars when trading is quiet.
+
+    loop {
+        tokio::select! {
+            _ = stop.changed() => {
+                println!("Stopping trade_bars_handler");
+                break;
+            }
+            batch = recv_batch(&mut events) => {
+                let Some(batch) = batch else { break }; // The source stopped.
+                let mut completed = Vec::new();
+                for event in batch {
+                    match event {
+                        MarketEvent::Trade(trade) => completed.extend(builder.push(&trade)),
+                        MarketEvent::Notice(notice) if is_plain() => {
+                            print_timestamped(&format!("FEED {}", notice))
+                        }
+                        _ => {}
+                    }
+                }
+                record(&product_id, &mut bars, completed);
+            }
+            _ = refresh.tick() => {
+                let now = Utc::now() - chrono::Duration::seconds(CLOSE_GRACE_SECS);
+                record(&product_id, &mut bars, builder.advance(now));
+                if !is_plain() {
+                    display_trade_bars(&product_id, rule, &bars, &builder);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Builds bars from recorded trades and writes them as CSV or JSON to stdout or a file.
+//
+// The trades file holds a JSON array of trades, or a market trades API response.
+pub fn bars_command(
+    trades_file: &Path,
+    rule: BarRule,
+    empty: MissingCandles,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<()> {
+    let contents = std::fs::read_to_string(trades_file)?;
+    let trades: Vec<MarketTrade> = match serde_json::from_str::<MarketTradesResponse>(&contents) {
+        Ok(response) => response.trades,
+        Err(_) => serde_json::from_str(&contents)?,
+    };
+    let bars = build_bars(&trades, rule, empty);
+    let exported = export_bars(&bars, format);
+    match output {
+        Some(path) => std::fs::write(path, exported)?,
+        None => print!("{}", exported),
+    }
+    Ok(())
+}
+
+// Parses a bar rule such as 5s, tick:500, volume:10, dollar:1000000 or imbalance:50.
+pub fn parse_bar_rule(text: &str) -> Result<BarRule> {
+    BarRule::parse(text).ok_or_else(|| {
+        anyhow!(
+            "invalid bar rule {}, expected e.g. 5s, tick:500, volume:10, dollar:1000000 or imbalance:50",
+            text
+        )
+    })
+}
+
+// Appends completed bars to the history, printing them in plain mode.
+fn record(product_id: &str, bars: &mut VecDeque<TradeBar>, completed: Vec<TradeBar>) {
+    for bar in completed {
+        if is_plain() {
+            print_timestamped(&format!(
+                "BAR {} start={} end={} open={} high={} low={} close={} volume={:.8} trades={} vwap={}",
+                product_id,
+                bar.start.to_rfc3339(),
+                bar.end.to_rfc3339(),
+                display_price(&bar.open.to_string(), product_id),
+                display_price(&bar.high.to_string(), product_id),
+                display_price(&bar.low.to_string(), product_id),
+                display_price(&bar.close.to_string(), product_id),
+                bar.volume,
+                bar.trade_count,
+                bar.vwap().map_or("-".to_string(), |v| display_price(&format!("{:.8}", v), product_id))
+            ));
+        }
+        bars.push_back(bar);
+        if bars.len() > BARS_KEPT {
+            bars.pop_front();
+        }
+    }
+}
+
+// Function to chart the completed bars and the forming bar, with a status line below.
+fn display_trade_bars(
+    product_id: &str,
+    rule: BarRule,
+    bars: &VecDeque<TradeBar>,
+    builder: &BarBuilder,
+) {
+    let mut candles: Vec<Candle> = bars.iter().map(TradeBar::to_candle).collect();
+    if let Some(current) = builder.current() {
+        candles.push(current.to_candle());
+    }
+    let candles = candles_to_reporting(&candles, product_id).unwrap_or(candles);
+
+    let (width, height) = terminal_size();
+    print!("\x1B[1;1H");
+    if candles.is_empty() {
+        println!("Waiting for trades on {}...\x1B[K", product_id);
+        return;
+    }
+    // Leave room for the status line below the chart.
+    for line in render_candlestick_chart(&candles, width, height.saturating_sub(2)) {
+        println!("{}\x1B[K", line);
+    }
+
+    let forming = match (builder.current(), rule) {
+        (Some(bar), BarRule::Time(_)) => format!(
+            "forming bar {} trades, closes at {}",
+            bar.trade_count,
+            bar.end.format("%H:%M:%S")
+        ),
+        (Some(bar), _) => format!("forming bar {} trades", bar.trade_count),
+        (None, _) => "no bar forming".to_string(),
+    };
+    let currency_note = reporting_currency()
+        .map(|currency| format!("  |  prices in {}", currency))
+        .unwrap_or_default();
+    print!(
+        "{} {} bars from trades{}  |  {} completed  |  {}  |  {} late trade(s) dropped\x1B[K",
+        product_id,
+        rule,
+        currency_note,
+        bars.len(),
+        forming,
+        builder.late_trades()
+    );
+    println!();
+}
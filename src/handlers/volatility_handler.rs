@@ -0,0 +1,165 @@
+use crate::api::candles::{fetch_candle_history, granularity_seconds}; // Importing the candle requests.
+use crate::indicators::{parse_candles, Ohlcv}; // Importing the numeric candles.
+use crate::volatility::{rolling_volatility, trailing_volatility, Estimator}; // Importing the volatility estimators.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use chrono::{Duration, TimeZone, Utc}; // Importing chrono for the fetched range and candle times.
+
+// Horizons of the term table, in days.
+const TERM_DAYS: [i64; 4] = [1, 7, 30, 90];
+
+// Fetches the last 90 days of candles for every product and prints their annualised volatility
+// over each horizon by each estimator, as a table or CSV.
+//
+// With a window, prints instead the volatility over the rolling window of that many candles
+// ending at each candle.
+pub async fn volatility_command(
+    product_ids: &[String],
+    granularity: &str,
+    estimators: &[Estimator],
+    window: Option<usize>,
+    csv: bool,
+) -> Result<()> {
+    let seconds = granularity_seconds(granularity)
+        .ok_or_else(|| anyhow!("unknown granularity {}", granularity))?;
+    let longest = TERM_DAYS.iter().max().copied().unwrap_or(0);
+    let now = Utc::now();
+    // One extra candle for the close before the longest horizon.
+    let start = now - Duration::days(longest) - Duration::seconds(seconds);
+
+    if csv {
+        let mut header = vec!["product".to_string()];
+        match window {
+            Some(_) => header.extend(
+                std::iter::once("time".to_string()).chain(
+                    estimators
+                        .iter()
+                        .map(|estimator| estimator.key().to_string()),
+                ),
+            ),
+            None => {
+                header.push("estimator".to_string());
+                header.extend(TERM_DAYS.iter().map(|days| format!("vol_{}d", days)));
+            }
+        }
+        println!("{}", header.join(","));
+    }
+    for product_id in product_ids {
+        let candles = fetch_candle_history(product_id, start, now, granularity).await?;
+        // Leave out the candle still forming.
+        let bars: Vec<Ohlcv> = parse_candles(&candles)
+            .into_iter()
+            .filter(|bar| bar.start + seconds <= now.timestamp())
+            .collect();
+        if let Some(window) = window {
+            let columns: Vec<Vec<Option<f64>>> = estimators
+                .iter()
+                .map(|estimator| rolling_volatility(&bars, *estimator, window, seconds))
+                .collect();
+            display_rolling(
+                product_id,
+                granularity,
+                window,
+                estimators,
+                &bars,
+                &columns,
+                csv,
+            );
+            continue;
+        }
+        let rows: Vec<(Estimator, Vec<Option<f64>>)> = estimators
+            .iter()
+            .map(|estimator| {
+                let term = TERM_DAYS
+                    .iter()
+                    .map(|days| trailing_volatility(&bars, *estimator, seconds, days * 86_400))
+                    .collect();
+                (*estimator, term)
+            })
+            .collect();
+
+        if csv {
+            for (estimator, term) in &rows {
+                let mut fields = vec![product_id.clone(), estimator.key().to_string()];
+                fields.extend(
+                    term.iter()
+                        .map(|value| value.map(|value| value.to_string()).unwrap_or_default()),
+                );
+                println!("{}", fields.join(","));
+            }
+        } else {
+            display_volatility(product_id, granularity, &rows);
+        }
+    }
+    Ok(())
+}
+
+// Function to print a product's term table, one row per estimator and one column per horizon.
+fn display_volatility(product_id: &str, granularity: &str, rows: &[(Estimator, Vec<Option<f64>>)]) {
+    println!(
+        "{} annualised volatility from {} candles",
+        product_id, granularity
+    );
+    let mut header = format!("{:<16}", "Estimator");
+    for days in TERM_DAYS {
+        header.push_str(&format!(" {:>9}", format!("{}d", days)));
+    }
+    println!("{}", header);
+    for (estimator, term) in rows {
+        let mut line = format!("{:<16}", estimator.name());
+        for value in term {
+            let text = value.map_or("n/a".to_string(), |value| format!("{:.1}%", value * 100.0));
+            line.push_str(&format!(" {:>9}", text));
+        }
+        println!("{}", line);
+    }
+    println!();
+}
+
+// Function to print a product's rolling volatility, one row per candle and one column per
+// estimator, as a table or CSV.
+fn display_rolling(
+    product_id: &str,
+    granularity: &str,
+    window: usize,
+    estimators: &[Estimator],
+    bars: &[Ohlcv],
+    columns: &[Vec<Option<f64>>],
+    csv: bool,
+) {
+    if !csv {
+        println!(
+            "{} annualised volatility over {} {} candles",
+            product_id, window, granularity
+        );
+        let mut header = format!("{:<26}", "Time");
+        for estimator in estimators {
+            header.push_str(&format!(" {:>16}", estimator.name()));
+        }
+        println!("{}", header);
+    }
+    for (i, bar) in bars.iter().enumerate() {
+        let time = Utc
+            .timestamp_opt(bar.start, 0)
+            .single()
+            .map_or(bar.start.to_string(), |time| time.to_rfc3339());
+        let values = columns.iter().map(|column| column[i]);
+        if csv {
+            let mut fields = vec![product_id.to_string(), time];
+            fields.extend(
+                values.map(|value| value.map(|value| value.to_string()).unwrap_or_default()),
+            );
+            println!("{}", fields.join(","));
+        } else {
+            let mut line = format!("{:<26}", time);
+            for value in values {
+                let text =
+                    value.map_or("n/a".to_string(), |value| format!("{:.1}%", value * 100.0));
+                line.push_str(&format!(" {:>16}", text));
+            }
+            println!("{}", line);
+        }
+    }
+    if !csv {
+        println!();
+    }
+}
@@ -0,0 +1,438 @@
+use crate::api::market_trades::fetch_best_bid_ask; // Importing the fetch_best_bid_ask function.
+use crate::api::products::fetch_products_by_ids; // Importing the batched products fetch.
+use crate::clear_screen::clear_screen; // Importing the clear_screen function.
+use crate::config::Config; // Importing the persisted config holding the watchlists.
+use crate::conversion::{display_price, price_header}; // Importing the reporting currency display.
+use crate::models::Product; // Importing the Product model.
+use crate::terminal::{is_plain, paint, print_timestamped, GREEN, RED}; // Importing output helpers.
+use anyhow::Result; // Importing the Result type from anyhow for error handling.
+use std::cmp::Ordering; // Importing Ordering for sorting rows.
+use std::collections::HashMap; // Importing HashMap to match quotes to products.
+use std::io::{self, BufRead, Write}; // Importing I/O utilities for user input.
+use std::sync::Arc; // Importing Arc to share the request limit between quote tasks.
+use std::time::Duration; // Importing Duration for time handling.
+use tokio::sync::{mpsc, Semaphore}; // Importing mpsc for user commands and Semaphore to limit requests.
+use tokio::task::JoinSet; // Importing JoinSet to fetch quotes concurrently.
+use tokio::time; // Importing time module from tokio for the polling interval.
+
+// Interval between two refreshes of the watchlist.
+const WATCHLIST_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// Maximum number of products requested in a single products call.
+const PRODUCTS_BATCH_SIZE: usize = 50;
+// Maximum number of ticker requests in flight at once, so large watchlists stay under the rate limit.
+const MAX_CONCURRENT_QUOTES: usize = 4;
+
+// Columns the watchlist table can be sorted by.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SortColumn {
+    Product,
+    Price,
+    Change,
+    Volume,
+    Spread,
+    Status,
+}
+
+impl SortColumn {
+    // Parses a column name typed by the user.
+    fn parse(name: &str) -> Option<SortColumn> {
+        match name.to_lowercase().as_str() {
+            "product" | "id" => Some(SortColumn::Product),
+            "price" => Some(SortColumn::Price),
+            "change" | "chg" => Some(SortColumn::Change),
+            "volume" | "vol" => Some(SortColumn::Volume),
+            "spread" => Some(SortColumn::Spread),
+            "status" => Some(SortColumn::Status),
+            _ => None,
+        }
+    }
+}
+
+// A single row of the watchlist table.
+struct WatchlistRow {
+    product_id: String,      // Product ID.
+    price: Option<f64>,      // Last price.
+    change: Option<f64>,     // 24h price change percentage.
+    volume: Option<f64>,     // 24h volume.
+    spread: Option<f64>,     // Absolute bid/ask spread.
+    spread_bps: Option<f64>, // Bid/ask spread in basis points of the mid price.
+    status: String,          // Trading status.
+}
+
+impl WatchlistRow {
+    // Builds a row from a product and its best bid and ask, if known.
+    fn new(product: &Product, quote: Option<&(Option<f64>, Option<f64>)>) -> Self {
+        let (spread, spread_bps) = match quote {
+            Some((Some(bid), Some(ask))) if *bid > 0.0 && *ask > 0.0 => {
+                let mid = (bid + ask) / 2.0;
+                (Some(ask - bid), Some((ask - bid) / mid * 10_000.0))
+            }
+            _ => (None, None),
+        };
+
+        WatchlistRow {
+            product_id: product.product_id.clone(),
+            price: product.price.parse().ok(),
+            change: product.price_percentage_change_24h.parse().ok(),
+            volume: product.volume_24h.parse().ok(),
+            spread,
+            spread_bps,
+            status: product.status.clone(),
+        }
+    }
+}
+
+// Asynchronously lets the user pick a watchlist and displays it until they quit.
+pub async fn fetch_watchlist_handler() -> Result<()> {
+    // Saving after a failed load would overwrite the file, so fix or move it first.
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Error loading config, watchlists are unavailable: {:?}", e);
+            return Ok(());
+        }
+    };
+
+    let name = match prompt_for_watchlist(&mut config) {
+        Some(name) => name,
+        None => return Ok(()), // Nothing to watch.
+    };
+    save_config(&config);
+
+    let mut commands = spawn_command_reader(); // Reading commands without blocking the polling.
+    let mut interval = time::interval(WATCHLIST_POLL_INTERVAL);
+    let mut sort = (SortColumn::Change, true); // Sorting by 24h change, descending.
+    let mut rows: Vec<WatchlistRow> = Vec::new();
+    let mut message = String::new(); // Feedback for the last command.
+    let mut status = String::new(); // Errors from the last refresh.
+
+    loop {
+        tokio::select! {
+            command = commands.recv() => {
+                let command = match command {
+                    Some(command) => command,
+                    None => break, // Input closed.
+                };
+                let mut parts = command.split_whitespace();
+                match (parts.next(), parts.next()) {
+                    (Some("q"), _) => break,
+                    (Some("s"), Some(column)) => match SortColumn::parse(column) {
+                        Some(column) => {
+                            // Selecting the same column again flips the direction.
+                            sort = if sort.0 == column { (column, !sort.1) } else { (column, true) };
+                            message.clear();
+                        }
+                        None => message = format!("Unknown column: {}", column),
+                    },
+                    (Some("a"), Some(product_id)) => {
+                        let product_id = product_id.to_uppercase();
+                        let products = config.watchlists.entry(name.clone()).or_default();
+                        if !products.contains(&product_id) {
+                            products.push(product_id.clone());
+                        }
+                        save_config(&config);
+                        message = format!("Added {}", product_id);
+                        interval.reset_immediately(); // Refreshing with the new product.
+                        continue;
+                    }
+                    (Some("r"), Some(product_id)) => {
+                        let product_id = product_id.to_uppercase();
+                        if let Some(products) = config.watchlists.get_mut(&name) {
+                            products.retain(|p| p != &product_id);
+                        }
+                        rows.retain(|row| row.product_id != product_id);
+                        save_config(&config);
+                        message = format!("Removed {}", product_id);
+                    }
+                    (None, _) => {}
+                    _ => message = format!("Unknown command: {}", command.trim()),
+                }
+                sort_rows(&mut rows, sort);
+                display_watchlist(&name, &rows, sort, &message, &status);
+            }
+            _ = interval.tick() => {
+                let product_ids = config.watchlists.get(&name).cloned().unwrap_or_default();
+                match fetch_watchlist_rows(&product_ids).await {
+                    Ok((fetched, failed)) => {
+                        rows = fetched;
+                        status = quote_errors(&failed);
+                    }
+                    Err(e) => status = format!("Error fetching watchlist: {:?}", e),
+                }
+                sort_rows(&mut rows, sort);
+                display_watchlist(&name, &rows, sort, &message, &status);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Prompts the user for a watchlist, creating it when it does not exist yet.
+fn prompt_for_watchlist(config: &mut Config) -> Option<String> {
+    if !config.watchlists.is_empty() {
+        println!("Saved watchlists:");
+        for (name, products) in &config.watchlists {
+            println!("  {} ({} products)", name, products.len());
+        }
+    }
+
+    print!("Enter the watchlist name: ");
+    io::stdout().flush().unwrap();
+    let mut name = String::new();
+    io::stdin().read_line(&mut name).unwrap();
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return None;
+    }
+
+    if !config.watchlists.contains_key(&name) {
+        print!("Enter the product IDs separated by commas (e.g., BTC-USD,ETH-USD): ");
+        io::stdout().flush().unwrap();
+        let mut products = String::new();
+        io::stdin().read_line(&mut products).unwrap();
+        let products: Vec<String> = products
+            .split(',')
+            .map(|p| p.trim().to_uppercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if products.is_empty() {
+            println!("A watchlist needs at least one product.");
+            return None;
+        }
+        config.watchlists.insert(name.clone(), products);
+    }
+
+    Some(name)
+}
+
+// Saves the config, reporting rather than propagating errors so the view keeps running.
+fn save_config(config: &Config) {
+    if let Err(e) = config.save() {
+        println!("Error saving watchlists: {:?}", e);
+    }
+}
+
+// Reads command lines from stdin on a separate thread until the user quits.
+fn spawn_command_reader() -> mpsc::UnboundedReceiver<String> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            let quit = line.trim() == "q";
+            if tx.send(line).is_err() || quit {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+// Fetches the products in batches and their best bid and ask a few at a time.
+//
+// Returns the rows and the products whose bid and ask could not be fetched, with the error.
+async fn fetch_watchlist_rows(
+    product_ids: &[String],
+) -> Result<(Vec<WatchlistRow>, Vec<(String, String)>)> {
+    let mut products = Vec::with_capacity(product_ids.len());
+    for batch in product_ids.chunks(PRODUCTS_BATCH_SIZE) {
+        products.extend(fetch_products_by_ids(batch).await?);
+    }
+
+    // The products endpoint has no bid/ask, so the ticker is queried for each product, with
+    // only a few requests in flight at once.
+    let limit = Arc::new(Semaphore::new(MAX_CONCURRENT_QUOTES));
+    let mut quotes = JoinSet::new();
+    for product_id in product_ids {
+        let product_id = product_id.clone();
+        let limit = Arc::clone(&limit);
+        quotes.spawn(async move {
+            let _permit = limit.acquire_owned().await;
+            let quote = fetch_best_bid_ask(&product_id).await.map(|q| {
+                (
+                    q.best_bid.and_then(|b| b.parse().ok()),
+                    q.best_ask.and_then(|a| a.parse().ok()),
+                )
+            });
+            (product_id, quote)
+        });
+    }
+    let mut quotes_by_product = HashMap::new();
+    let mut failed = Vec::new();
+    while let Some(result) = quotes.join_next().await {
+        match result {
+            Ok((product_id, Ok(quote))) => {
+                quotes_by_product.insert(product_id, quote);
+            }
+            Ok((product_id, Err(e))) => failed.push((product_id, e.to_string())),
+            Err(e) => failed.push(("?".to_string(), e.to_string())),
+        }
+    }
+    failed.sort();
+
+    let rows = products
+        .iter()
+        .map(|product| WatchlistRow::new(product, quotes_by_product.get(&product.product_id)))
+        .collect();
+    Ok((rows, failed))
+}
+
+// Summarises the failed bid/ask requests for the status line, empty when none failed.
+fn quote_errors(failed: &[(String, String)]) -> String {
+    match failed {
+        [] => String::new(),
+        [(product_id, error)] => format!("Error fetching bid/ask for {}: {}", product_id, error),
+        [(product_id, error), rest @ ..] => format!(
+            "Error fetching bid/ask for {} and {} more: {}",
+            product_id,
+            rest.len(),
+            error
+        ),
+    }
+}
+
+// Sorts the rows by the selected column, keeping missing values last.
+fn sort_rows(rows: &mut [WatchlistRow], (column, descending): (SortColumn, bool)) {
+    rows.sort_by(|a, b| {
+        let ordering = match column {
+            SortColumn::Product => Some(a.product_id.cmp(&b.product_id)),
+            SortColumn::Status => Some(a.status.cmp(&b.status)),
+            SortColumn::Price => compare_numbers(a.price, b.price),
+            SortColumn::Change => compare_numbers(a.change, b.change),
+            SortColumn::Volume => compare_numbers(a.volume, b.volume),
+            SortColumn::Spread => compare_numbers(a.spread_bps, b.spread_bps),
+        };
+        match ordering {
+            Some(ordering) if descending => ordering.reverse(),
+            Some(ordering) => ordering,
+            None => compare_missing(a, b, column),
+        }
+    });
+}
+
+// Compares two optional numbers, returning None when either is missing.
+fn compare_numbers(a: Option<f64>, b: Option<f64>) -> Option<Ordering> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(&b),
+        _ => None,
+    }
+}
+
+// Orders rows with a missing value for the column after rows that have one.
+fn compare_missing(a: &WatchlistRow, b: &WatchlistRow, column: SortColumn) -> Ordering {
+    let value = |row: &WatchlistRow| match column {
+        SortColumn::Price => row.price,
+        SortColumn::Change => row.change,
+        SortColumn::Volume => row.volume,
+        SortColumn::Spread => row.spread_bps,
+        SortColumn::Product | SortColumn::Status => None,
+    };
+    value(a).is_none().cmp(&value(b).is_none())
+}
+
+// Displays the watchlist as a table, colouring products by their 24h change.
+fn display_watchlist(
+    name: &str,
+    rows: &[WatchlistRow],
+    (column, descending): (SortColumn, bool),
+    message: &str,
+    status: &str,
+) {
+    if is_plain() {
+        display_watchlist_plain(name, rows, message, status);
+        return;
+    }
+
+    clear_screen();
+    println!(
+        "Watchlist: {}  |  Updated: {}  |  Sorted by {:?} ({})",
+        name,
+        chrono::Local::now().format("%H:%M:%S"),
+        column,
+        if descending { "desc" } else { "asc" }
+    );
+    println!(
+        "{:<15} {:>15} {:>10} {:>18} {:>12} {:>10} {:<10}",
+        "Product ID",
+        price_header("Price"),
+        "24h %",
+        "Volume 24h",
+        price_header("Spread"),
+        "Spread bps",
+        "Status"
+    );
+
+    for row in rows {
+        let line = format!(
+            "{:<15} {:>15} {:>10} {:>18} {:>12} {:>10} {:<10}",
+            row.product_id,
+            display_price(&format_number(row.price, 8), &row.product_id),
+            format_number(row.change, 2),
+            format_number(row.volume, 2),
+            display_price(&format_number(row.spread, 8), &row.product_id),
+            format_number(row.spread_bps, 2),
+            row.status
+        );
+        match row.change {
+            Some(change) if change > 0.0 => println!("{}", paint(&line, GREEN)),
+            Some(change) if change < 0.0 => println!("{}", paint(&line, RED)),
+            _ => println!("{}", line),
+        }
+    }
+
+    println!();
+    if !status.is_empty() {
+        println!("{}", paint(status, RED));
+    }
+    if !message.is_empty() {
+        println!("{}", message);
+    }
+    println!(
+        "Commands: s <product|price|change|volume|spread|status>, a <product>, r <product>, q"
+    );
+    print!("> ");
+    io::stdout().flush().unwrap();
+}
+
+// Prints the watchlist rows as timestamped lines, for plain output.
+fn display_watchlist_plain(name: &str, rows: &[WatchlistRow], message: &str, status: &str) {
+    for line in [message, status] {
+        if !line.is_empty() {
+            print_timestamped(&format!("WATCHLIST {} {}", name, line));
+        }
+    }
+    for row in rows {
+        print_timestamped(&format!(
+            "WATCHLIST {} {} price={} change_24h={} volume_24h={} spread={} spread_bps={} status={}",
+            name,
+            row.product_id,
+            display_price(&format_number(row.price, 8), &row.product_id),
+            format_number(row.change, 2),
+            format_number(row.volume, 2),
+            display_price(&format_number(row.spread, 8), &row.product_id),
+            format_number(row.spread_bps, 2),
+            row.status
+        ));
+    }
+}
+
+// Formats an optional number, trimming trailing zeros and showing "-" when missing.
+fn format_number(value: Option<f64>, decimals: usize) -> String {
+    match value {
+        Some(value) => {
+            let formatted = format!("{:.*}", decimals, value);
+            if formatted.contains('.') {
+                formatted
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            } else {
+                formatted
+            }
+        }
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,673 @@
+use crate::models::Candle; // Importing the Candle model.
+use std::collections::VecDeque; // Importing VecDeque for the sliding windows.
+use std::fmt; // Importing fmt to label indicators.
+
+// Indicator values, one per candle, None during the warm-up.
+pub type Series = Vec<Option<f64>>;
+
+// Numeric candle used by the indicators.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ohlcv {
+    pub start: i64,  // Start time as a Unix timestamp.
+    pub open: f64,   // Opening price.
+    pub high: f64,   // Highest price.
+    pub low: f64,    // Lowest price.
+    pub close: f64,  // Closing price.
+    pub volume: f64, // Traded volume.
+}
+
+impl Ohlcv {
+    // Parses a raw API candle, returning None if any field is not numeric.
+    pub fn from_candle(candle: &Candle) -> Option<Ohlcv> {
+        Some(Ohlcv {
+            start: candle.start.parse().ok()?,
+            open: candle.open.parse().ok()?,
+            high: candle.high.parse().ok()?,
+            low: candle.low.parse().ok()?,
+            close: candle.close.parse().ok()?,
+            volume: candle.volume.parse().ok()?,
+        })
+    }
+}
+
+// Parses candles and sorts them oldest first, skipping candles with non-numeric fields.
+pub fn parse_candles(candles: &[Candle]) -> Vec<Ohlcv> {
+    let mut bars: Vec<Ohlcv> = candles.iter().filter_map(Ohlcv::from_candle).collect();
+    bars.sort_by_key(|bar| bar.start);
+    bars
+}
+
+// A technical indicator and its parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indicator {
+    // Simple moving average of the close.
+    Sma(usize),
+    // Exponential moving average of the close, seeded with the SMA.
+    Ema(usize),
+    // Linearly weighted moving average of the close.
+    Wma(usize),
+    // Relative strength index with Wilder smoothing.
+    Rsi(usize),
+    // MACD line, signal line and histogram.
+    Macd {
+        fast: usize,
+        slow: usize,
+        signal: usize,
+    },
+    // Bollinger Bands: SMA plus and minus a multiple of the standard deviation.
+    Bollinger {
+        period: usize,
+        width: f64,
+    },
+    // Average true range with Wilder smoothing.
+    Atr(usize),
+    // Stochastic oscillator %K and its SMA %D.
+    Stochastic {
+        k: usize,
+        d: usize,
+    },
+    // On-balance volume.
+    Obv,
+    // Volume weighted average price, cumulative from the first candle.
+    Vwap,
+}
+
+// One output series of an indicator, aligned with the candles it was computed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorColumn {
+    pub key: String,    // Machine-readable name, e.g. sma_20.
+    pub label: String,  // Display name, e.g. SMA(20).
+    pub values: Series, // One value per candle.
+}
+
+impl Indicator {
+    // Parses an indicator spec such as sma:20, macd:12:26:9, bb:20:2 or obv.
+    //
+    // Parameters that are left out take their usual defaults.
+    pub fn parse(spec: &str) -> Option<Indicator> {
+        let mut parts = spec.trim().split(':');
+        let name = parts.next()?.to_lowercase();
+        let params: Vec<&str> = parts.collect();
+        let period = |index: usize, default: usize| -> Option<usize> {
+            match params.get(index) {
+                Some(value) => value.parse().ok().filter(|p| *p > 0),
+                None => Some(default),
+            }
+        };
+        let expected = match name.as_str() {
+            "obv" | "vwap" => 0,
+            "macd" => 3,
+            "bb" | "bollinger" | "stoch" | "stochastic" => 2,
+            _ => 1,
+        };
+        if params.len() > expected {
+            return None;
+        }
+
+        let indicator = match name.as_str() {
+            "sma" => Indicator::Sma(period(0, 20)?),
+            "ema" => Indicator::Ema(period(0, 20)?),
+            "wma" => Indicator::Wma(period(0, 20)?),
+            "rsi" => Indicator::Rsi(period(0, 14)?),
+            "macd" => {
+                let (fast, slow, signal) = (period(0, 12)?, period(1, 26)?, period(2, 9)?);
+                if fast >= slow {
+                    return None;
+                }
+                Indicator::Macd { fast, slow, signal }
+            }
+            "bb" | "bollinger" => Indicator::Bollinger {
+                period: period(0, 20)?,
+                width: match params.get(1) {
+                    Some(value) => value.parse().ok().filter(|w: &f64| *w > 0.0)?,
+                    None => 2.0,
+                },
+            },
+            "atr" => Indicator::Atr(period(0, 14)?),
+            "stoch" | "stochastic" => Indicator::Stochastic {
+                k: period(0, 14)?,
+                d: period(1, 3)?,
+            },
+            "obv" => Indicator::Obv,
+            "vwap" => Indicator::Vwap,
+            _ => return None,
+        };
+        Some(indicator)
+    }
+
+    // Computes the indicator's output series over candles sorted oldest first.
+    pub fn compute(&self, bars: &[Ohlcv]) -> Vec<IndicatorColumn> {
+        let closes: Vec<f64> = bars.iter().map(|bar| bar.close).collect();
+        let column =
+            |key: String, label: String, values: Series| IndicatorColumn { key, label, values };
+        match *self {
+            Indicator::Sma(period) => vec![column(
+                format!("sma_{}", period),
+                self.to_string(),
+                sma(&closes, period),
+            )],
+            Indicator::Ema(period) => vec![column(
+                format!("ema_{}", period),
+                self.to_string(),
+                ema(&closes, period),
+            )],
+            Indicator::Wma(period) => vec![column(
+                format!("wma_{}", period),
+                self.to_string(),
+                wma(&closes, period),
+            )],
+            Indicator::Rsi(period) => vec![column(
+                format!("rsi_{}", period),
+                self.to_string(),
+                rsi(&closes, period),
+            )],
+            Indicator::Macd { fast, slow, signal } => {
+                let (line, signal_line, histogram) = macd(&closes, fast, slow, signal);
+                let suffix = format!("{}_{}_{}", fast, slow, signal);
+                vec![
+                    column(format!("macd_{}", suffix), self.to_string(), line),
+                    column(
+                        format!("macd_signal_{}", suffix),
+                        "Signal".to_string(),
+                        signal_line,
+                    ),
+                    column(
+                        format!("macd_hist_{}", suffix),
+                        "Hist".to_string(),
+                        histogram,
+                    ),
+                ]
+            }
+            Indicator::Bollinger { period, width } => {
+                let (upper, middle, lower) = bollinger(&closes, period, width);
+                let suffix = format!("{}_{}", period, width);
+                vec![
+                    column(
+                        format!("bb_upper_{}", suffix),
+                        "BB Upper".to_string(),
+                        upper,
+                    ),
+                    column(format!("bb_middle_{}", suffix), self.to_string(), middle),
+                    column(
+                        format!("bb_lower_{}", suffix),
+                        "BB Lower".to_string(),
+                        lower,
+                    ),
+                ]
+            }
+            Indicator::Atr(period) => vec![column(
+                format!("atr_{}", period),
+                self.to_string(),
+                atr(bars, period),
+            )],
+            Indicator::Stochastic { k, d } => {
+                let (k_line, d_line) = stochastic(bars, k, d);
+                vec![
+                    column(format!("stoch_k_{}_{}", k, d), self.to_string(), k_line),
+                    column(format!("stoch_d_{}_{}", k, d), "%D".to_string(), d_line),
+                ]
+            }
+            Indicator::Obv => vec![column("obv".to_string(), self.to_string(), obv(bars))],
+            Indicator::Vwap => vec![column("vwap".to_string(), self.to_string(), vwap(bars))],
+        }
+    }
+}
+
+impl fmt::Display for Indicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Indicator::Sma(period) => write!(f, "SMA({})", period),
+            Indicator::Ema(period) => write!(f, "EMA({})", period),
+            Indicator::Wma(period) => write!(f, "WMA({})", period),
+            Indicator::Rsi(period) => write!(f, "RSI({})", period),
+            Indicator::Macd { fast, slow, signal } => {
+                write!(f, "MACD({},{},{})", fast, slow, signal)
+            }
+            Indicator::Bollinger { period, width } => write!(f, "BB({},{})", period, width),
+            Indicator::Atr(period) => write!(f, "ATR({})", period),
+            Indicator::Stochastic { k, d } => write!(f, "%K({},{})", k, d),
+            Indicator::Obv => write!(f, "OBV"),
+            Indicator::Vwap => write!(f, "VWAP"),
+        }
+    }
+}
+
+// Computes every indicator over candles sorted oldest first, in the order given.
+pub fn compute_indicators(bars: &[Ohlcv], indicators: &[Indicator]) -> Vec<IndicatorColumn> {
+    indicators
+        .iter()
+        .flat_map(|indicator| indicator.compute(bars))
+        .collect()
+}
+
+// Simple moving average. The first value is at index period - 1.
+pub fn sma(values: &[f64], period: usize) -> Series {
+    let mut result = vec![None; values.len()];
+    if period == 0 {
+        return result;
+    }
+    let mut sum = 0.0;
+    for (i, value) in values.iter().enumerate() {
+        sum += value;
+        if i >= period {
+            sum -= values[i - period];
+        }
+        if i + 1 >= period {
+            result[i] = Some(sum / period as f64);
+        }
+    }
+    result
+}
+
+// Exponential moving average with smoothing 2 / (period + 1), seeded with the SMA of the first
+// `period` values. The first value is at index period - 1.
+pub fn ema(values: &[f64], period: usize) -> Series {
+    smoothed(values, period, 2.0 / (period as f64 + 1.0))
+}
+
+// Linearly weighted moving average, the newest value weighted `period` and the oldest 1.
+pub fn wma(values: &[f64], period: usize) -> Series {
+    let mut result = vec![None; values.len()];
+    if period == 0 {
+        return result;
+    }
+    let weights = (period * (period + 1) / 2) as f64;
+    for i in (period - 1)..values.len() {
+        let window = &values[i + 1 - period..=i];
+        let weighted: f64 = window
+            .iter()
+            .enumerate()
+            .map(|(j, value)| value * (j + 1) as f64)
+            .sum();
+        result[i] = Some(weighted / weights);
+    }
+    result
+}
+
+// Relative strength index with Wilder smoothing of gains and losses. The first value is at
+// index `period`, once `period` price changes are known.
+pub fn rsi(values: &[f64], period: usize) -> Series {
+    let mut result = vec![None; values.len()];
+    if period == 0 || values.len() <= period {
+        return result;
+    }
+    let changes: Vec<f64> = values.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let gains: Vec<f64> = changes.iter().map(|change| change.max(0.0)).collect();
+    let losses: Vec<f64> = changes.iter().map(|change| (-change).max(0.0)).collect();
+    let alpha = 1.0 / period as f64;
+    let average_gains = smoothed(&gains, period, alpha);
+    let average_losses = smoothed(&losses, period, alpha);
+    for (i, (gain, loss)) in average_gains.iter().zip(&average_losses).enumerate() {
+        if let (Some(gain), Some(loss)) = (gain, loss) {
+            // Change i is between values i and i + 1.
+            result[i + 1] = Some(if *loss == 0.0 {
+                if *gain == 0.0 {
+                    50.0
+                } else {
+                    100.0
+                }
+            } else {
+                100.0 - 100.0 / (1.0 + gain / loss)
+            });
+        }
+    }
+    result
+}
+
+// MACD line (fast EMA minus slow EMA), signal line (EMA of the MACD line) and histogram
+// (MACD minus signal). The MACD line starts at index slow - 1 and the signal `signal - 1`
+// values later.
+pub fn macd(values: &[f64], fast: usize, slow: usize, signal: usize) -> (Series, Series, Series) {
+    let fast_ema = ema(values, fast);
+    let slow_ema = ema(values, slow);
+    let line: Series = fast_ema
+        .iter()
+        .zip(&slow_ema)
+        .map(|(fast, slow)| Some((*fast)? - (*slow)?))
+        .collect();
+    let signal_line = on_defined(&line, |defined| ema(defined, signal));
+    let histogram = line
+        .iter()
+        .zip(&signal_line)
+        .map(|(line, signal)| Some((*line)? - (*signal)?))
+        .collect();
+    (line, signal_line, histogram)
+}
+
+// Bollinger Bands: upper, middle (SMA) and lower bands, `width` population standard deviations
+// from the middle. The first values are at index period - 1.
+pub fn bollinger(values: &[f64], period: usize, width: f64) -> (Series, Series, Series) {
+    let middle = sma(values, period);
+    let mut upper = vec![None; values.len()];
+    let mut lower = vec![None; values.len()];
+    for (i, mean) in middle.iter().enumerate() {
+        if let Some(mean) = mean {
+            let window = &values[i + 1 - period..=i];
+            let variance = window
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / period as f64;
+            let deviation = variance.sqrt();
+            upper[i] = Some(mean + width * deviation);
+            lower[i] = Some(mean - width * deviation);
+        }
+    }
+    (upper, middle, lower)
+}
+
+// Average true range with Wilder smoothing, seeded with the mean of the first `period` true
+// ranges. True ranges start at the second candle, which has a previous close, so the first
+// value is at index `period`, as TA-Lib computes it.
+pub fn atr(bars: &[Ohlcv], period: usize) -> Series {
+    let mut result = vec![None; bars.len()];
+    let true_ranges: Vec<f64> = bars
+        .windows(2)
+        .map(|pair| {
+            let (previous, bar) = (pair[0].close, &pair[1]);
+            (bar.high - bar.low)
+                .max((bar.high - previous).abs())
+                .max((bar.low - previous).abs())
+        })
+        .collect();
+    for (i, value) in smoothed(&true_ranges, period, 1.0 / period as f64)
+        .into_iter()
+        .enumerate()
+    {
+        // True range i is of candle i + 1.
+        result[i + 1] = value;
+    }
+    result
+}
+
+// Stochastic oscillator: %K is where the close sits in the high-low range of the last `k`
+// candles, from 0 to 100, and %D is the SMA of %K over `d` values. %K is 50 when the range is
+// empty. %K starts at index k - 1 and %D `d - 1` values later.
+pub fn stochastic(bars: &[Ohlcv], k: usize, d: usize) -> (Series, Series) {
+    let mut k_line = vec![None; bars.len()];
+    if k == 0 {
+        return (k_line.clone(), k_line);
+    }
+    let mut highs: VecDeque<usize> = VecDeque::new(); // Indices of decreasing highs.
+    let mut lows: VecDeque<usize> = VecDeque::new(); // Indices of increasing lows.
+    for (i, bar) in bars.iter().enumerate() {
+        while highs.back().is_some_and(|&j| bars[j].high <= bar.high) {
+            highs.pop_back();
+        }
+        highs.push_back(i);
+        while lows.back().is_some_and(|&j| bars[j].low >= bar.low) {
+            lows.pop_back();
+        }
+        lows.push_back(i);
+        if i + 1 < k {
+            continue;
+        }
+        while highs.front().is_some_and(|&j| j + k <= i) {
+            highs.pop_front();
+        }
+        while lows.front().is_some_and(|&j| j + k <= i) {
+            lows.pop_front();
+        }
+        let highest = bars[highs[0]].high;
+        let lowest = bars[lows[0]].low;
+        k_line[i] = Some(if highest > lowest {
+            100.0 * (bar.close - lowest) / (highest - lowest)
+        } else {
+            50.0
+        });
+    }
+    let d_line = on_defined(&k_line, |defined| sma(defined, d));
+    (k_line, d_line)
+}
+
+// On-balance volume, starting at 0 on the first candle and adding or subtracting each
+// candle's volume as its close rises or falls.
+pub fn obv(bars: &[Ohlcv]) -> Series {
+    let mut total = 0.0;
+    bars.iter()
+        .enumerate()
+        .map(|(i, bar)| {
+            if let Some(previous) = i.checked_sub(1).map(|j| bars[j].close) {
+                if bar.close > previous {
+                    total += bar.volume;
+                } else if bar.close < previous {
+                    total -= bar.volume;
+                }
+            }
+            Some(total)
+        })
+        .collect()
+}
+
+// Volume weighted average of the typical price (high + low + close) / 3, cumulative from the
+// first candle. None until some volume has traded.
+pub fn vwap(bars: &[Ohlcv]) -> Series {
+    let mut notional = 0.0;
+    let mut volume = 0.0;
+    bars.iter()
+        .map(|bar| {
+            notional += (bar.high + bar.low + bar.close) / 3.0 * bar.volume;
+            volume += bar.volume;
+            (volume > 0.0).then(|| notional / volume)
+        })
+        .collect()
+}
+
+// Exponential smoothing with factor `alpha`, seeded with the mean of the first `period` values.
+fn smoothed(values: &[f64], period: usize, alpha: f64) -> Series {
+    let mut result = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return result;
+    }
+    let mut average = values[..period].iter().sum::<f64>() / period as f64;
+    result[period - 1] = Some(average);
+    for i in period..values.len() {
+        average += alpha * (values[i] - average);
+        result[i] = Some(average);
+    }
+    result
+}
+
+// Applies a series function to the defined tail of a series, keeping the leading gap.
+fn on_defined(series: &[Option<f64>], apply: impl Fn(&[f64]) -> Series) -> Series {
+    let offset = series
+        .iter()
+        .position(Option::is_some)
+        .unwrap_or(series.len());
+    let defined: Vec<f64> = series[offset..].iter().map(|v| v.unwrap_or(0.0)).collect();
+    let mut result = vec![None; offset];
+    result.extend(apply(&defined));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Closes of the 10-day moving average examples in the StockCharts ChartSchool articles.
+    const MA_CLOSES: [f64; 30] = [
+        22.27, 22.19, 22.08, 22.17, 22.18, 22.13, 22.23, 22.43, 22.24, 22.29, 22.15, 22.39, 22.38,
+        22.61, 23.36, 24.05, 23.75, 23.83, 23.95, 23.63, 23.82, 23.87, 23.65, 23.19, 23.10, 23.33,
+        22.68, 23.10, 22.40, 22.17,
+    ];
+
+    // Closes of the 14-period RSI example in the StockCharts ChartSchool article.
+    const RSI_CLOSES: [f64; 33] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+        46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64, 46.21, 46.25, 45.71, 46.45, 45.78, 45.35,
+        44.03, 44.18, 44.22, 44.57, 43.42, 42.66, 43.13,
+    ];
+
+    // Asserts that a series has `warm_up` empty values followed by values within `tolerance`
+    // of the expected ones.
+    fn assert_series(actual: &Series, warm_up: usize, expected: &[f64], tolerance: f64) {
+        assert_eq!(actual.len(), warm_up + expected.len());
+        assert!(
+            actual[..warm_up].iter().all(Option::is_none),
+            "warm-up values should be empty: {:?}",
+            &actual[..warm_up]
+        );
+        for (i, (value, expected)) in actual[warm_up..].iter().zip(expected).enumerate() {
+            let value = value.unwrap_or_else(|| panic!("value {} is empty", warm_up + i));
+            assert!(
+                (value - expected).abs() <= tolerance,
+                "value {} is {}, expected {}",
+                warm_up + i,
+                value,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn sma_matches_reference() {
+        let expected = [
+            22.22, 22.21, 22.23, 22.26, 22.31, 22.42, 22.61, 22.77, 22.91, 23.08, 23.21, 23.38,
+            23.53, 23.65, 23.71, 23.69, 23.61, 23.51, 23.43, 23.28, 23.13,
+        ];
+        assert_series(&sma(&MA_CLOSES, 10), 9, &expected, 0.01);
+    }
+
+    #[test]
+    fn ema_matches_reference() {
+        let expected = [
+            22.22, 22.21, 22.24, 22.27, 22.33, 22.52, 22.80, 22.97, 23.13, 23.28, 23.34, 23.43,
+            23.51, 23.54, 23.47, 23.40, 23.39, 23.26, 23.23, 23.08, 22.92,
+        ];
+        assert_series(&ema(&MA_CLOSES, 10), 9, &expected, 0.01);
+    }
+
+    #[test]
+    fn rsi_matches_reference() {
+        // Wilder's RSI without rounding the intermediate averages, as TA-Lib computes it.
+        let expected = [
+            70.4641, 66.2496, 66.4809, 69.3469, 66.2947, 57.9150, 62.8807, 63.2088, 56.0116,
+            62.3399, 54.6710, 50.3869, 40.0194, 41.4926, 41.9023, 45.4994, 37.3228, 33.0904,
+            37.7888,
+        ];
+        assert_series(&rsi(&RSI_CLOSES, 14), 14, &expected, 0.001);
+    }
+
+    #[test]
+    fn macd_of_a_linear_series_is_the_ema_lag_difference() {
+        // An SMA-seeded EMA of a straight line trails it by (period - 1) / 2, so MACD(12, 26, 9)
+        // is exactly (26 - 12) / 2 once the slow EMA starts.
+        let values: Vec<f64> = (0..40).map(f64::from).collect();
+        let (line, signal, histogram) = macd(&values, 12, 26, 9);
+        assert_series(&line, 25, &[7.0; 15], 1e-9);
+        assert_series(&signal, 33, &[7.0; 7], 1e-9);
+        assert_series(&histogram, 33, &[0.0; 7], 1e-9);
+    }
+
+    #[test]
+    fn bollinger_bands_use_the_population_deviation() {
+        let (upper, middle, lower) = bollinger(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 5, 2.0);
+        let deviation = 2f64.sqrt(); // Population deviation of 1..=5 and of 2..=6.
+        assert_series(&middle, 4, &[3.0, 4.0], 1e-12);
+        assert_series(
+            &upper,
+            4,
+            &[3.0 + 2.0 * deviation, 4.0 + 2.0 * deviation],
+            1e-12,
+        );
+        assert_series(
+            &lower,
+            4,
+            &[3.0 - 2.0 * deviation, 4.0 - 2.0 * deviation],
+            1e-12,
+        );
+    }
+
+    // Highs, lows, closes and volumes of 15 candles for the candle-based indicators.
+    const HIGHS: [f64; 15] = [
+        48.70, 48.72, 48.90, 48.87, 48.82, 49.05, 49.20, 49.35, 49.92, 50.19, 50.12, 49.66, 49.88,
+        50.19, 50.36,
+    ];
+    const LOWS: [f64; 15] = [
+        47.79, 48.14, 48.39, 48.37, 48.24, 48.64, 48.94, 48.86, 49.50, 49.87, 49.20, 48.90, 49.43,
+        49.73, 49.26,
+    ];
+    const CLOSES: [f64; 15] = [
+        48.16, 48.61, 48.75, 48.63, 48.74, 49.03, 49.07, 49.32, 49.91, 50.13, 49.53, 49.50, 49.75,
+        50.03, 50.31,
+    ];
+    const VOLUMES: [f64; 15] = [
+        1000.0, 1200.0, 900.0, 1500.0, 1100.0, 1300.0, 800.0, 1700.0, 2100.0, 1600.0, 1400.0,
+        900.0, 1000.0, 1250.0, 1800.0,
+    ];
+
+    fn candles() -> Vec<Ohlcv> {
+        (0..CLOSES.len())
+            .map(|i| Ohlcv {
+                start: i as i64 * 60,
+                open: CLOSES[i.saturating_sub(1)],
+                high: HIGHS[i],
+                low: LOWS[i],
+                close: CLOSES[i],
+                volume: VOLUMES[i],
+            })
+            .collect()
+    }
+
+    // The expected values of the candle-based indicators below were worked out separately from
+    // their textbook formulas.
+
+    #[test]
+    fn wma_matches_reference() {
+        let expected = [
+            48.615, 48.696, 48.835, 48.948, 49.129, 49.477, 49.796, 49.765, 49.676, 49.669, 49.790,
+            50.033,
+        ];
+        assert_series(&wma(&CLOSES, 4), 3, &expected, 1e-9);
+    }
+
+    #[test]
+    fn atr_starts_after_period_true_ranges_like_ta_lib() {
+        let expected = [
+            0.516, 0.4648, 0.46984, 0.495872, 0.460698, 0.554558, 0.595646, 0.566517, 0.545214,
+            0.656171,
+        ];
+        assert_series(&atr(&candles(), 5), 5, &expected, 1e-6);
+    }
+
+    #[test]
+    fn stochastic_matches_reference() {
+        let (k, d) = stochastic(&candles(), 5, 3);
+        let expected_k = [
+            85.585586, 97.802198, 86.458333, 97.297297, 99.404762, 96.129032, 50.37594, 48.120301,
+            65.891473, 87.596899, 96.575342,
+        ];
+        let expected_d = [
+            89.948706, 93.852609, 94.386798, 97.610364, 81.969911, 64.875091, 54.795904, 67.202891,
+            83.354572,
+        ];
+        assert_series(&k, 4, &expected_k, 1e-6);
+        assert_series(&d, 6, &expected_d, 1e-6);
+    }
+
+    #[test]
+    fn obv_adds_volume_on_up_closes_and_subtracts_it_on_down_closes() {
+        let expected = [
+            0.0, 1200.0, 2100.0, 600.0, 1700.0, 3000.0, 3800.0, 5500.0, 7600.0, 9200.0, 7800.0,
+            6900.0, 7900.0, 9150.0, 10950.0,
+        ];
+        assert_series(&obv(&candles()), 0, &expected, 1e-9);
+    }
+
+    #[test]
+    fn vwap_matches_reference() {
+        let expected = [
+            48.216667, 48.365758, 48.456989, 48.511232, 48.528363, 48.598619, 48.646966, 48.741754,
+            48.929109, 49.066591, 49.119338, 49.132925, 49.166485, 49.224009, 49.293308,
+        ];
+        assert_series(&vwap(&candles()), 0, &expected, 1e-6);
+    }
+
+    #[test]
+    fn short_inputs_stay_in_warm_up() {
+        assert_eq!(sma(&[1.0, 2.0], 3), vec![None, None]);
+        assert_eq!(ema(&[1.0, 2.0], 3), vec![None, None]);
+        assert_eq!(rsi(&[1.0, 2.0, 3.0], 3), vec![None, None, None]);
+        assert_eq!(atr(&candles()[..3], 3), vec![None, None, None]);
+    }
+}
@@ -0,0 +1,31 @@
+pub mod api;
+pub mod arbitrage;
+pub mod bar_builder;
+pub mod book_analytics;
+pub mod book_diff;
+pub mod candle_export;
+pub mod candle_series;
+pub mod chart;
+pub mod clear_screen;
+pub mod cli;
+pub mod config;
+pub mod conversion;
+pub mod correlation;
+pub mod handlers;
+pub mod indicators;
+pub mod market_data;
+pub mod market_overview;
+pub mod menu;
+pub mod models;
+pub mod order_book;
+pub mod order_validation;
+pub mod resample;
+pub mod screener;
+pub mod slippage;
+pub mod task_runner;
+pub mod terminal;
+pub mod trade_flow;
+pub mod trade_tape;
+pub mod utils;
+pub mod volatility;
+pub mod websocket;
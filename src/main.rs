@@ -1,14 +1,43 @@
-mod api;
-mod clear_screen;
-mod handlers;
-mod menu;
-mod models;
-mod task_runner;
-mod utils;
-
 use anyhow::Result;
+use coinbase_api_client::config::Config;
+use coinbase_api_client::handlers::conversion_handler::request_reporting_currency;
+use coinbase_api_client::{book_diff, cli, market_data, menu, terminal, trade_flow};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    menu::show_menu().await
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = cli::parse_args(&args)?;
+
+    // Switch to plain output when requested or when stdout is not a terminal.
+    terminal::configure_output(options.plain);
+
+    // Select how the live views receive market data, REST polling by default.
+    if let Some(transport) = options.transport {
+        market_data::set_transport(transport);
+    }
+
+    // Log order book level changes to a file, or to stdout with "-".
+    if let Some(path) = options.book_changes {
+        book_diff::set_change_log_path(path);
+    }
+
+    // Use other trade-flow windows than the saved ones for this run.
+    if let Some(windows) = options.flow_windows {
+        trade_flow::set_window_override(windows);
+    }
+
+    // Show prices in a reporting currency, chosen with --currency or saved from the menu. It is
+    // only applied by the commands and views that show prices, so the others need no network.
+    if let Some(currency) = options.currency {
+        request_reporting_currency(&currency, true);
+    } else if let Some(currency) = Config::load().ok().and_then(|c| c.reporting_currency) {
+        request_reporting_currency(&currency, false);
+    }
+
+    // Run a single command when one is given, otherwise show the menu.
+    if options.command.is_empty() {
+        menu::show_menu().await
+    } else {
+        cli::run_command(&options.command).await
+    }
 }
@@ -0,0 +1,157 @@
+pub mod polling;
+pub mod streaming;
+
+use crate::models::MarketTrade; // Importing the trade model shared by both transports.
+use crate::order_book::{OrderBook, Side}; // Importing the book types carried by book events.
+use crate::utils::WEBSOCKET_URL; // Importing the feed URL.
+use crate::websocket::messages::{CandleUpdate, ProductStatus, Ticker}; // Importing the normalised payloads.
+use polling::PollingSource; // Importing the REST polling backend.
+use rust_decimal::Decimal; // Importing Decimal for book deltas.
+use std::sync::atomic::{AtomicBool, Ordering}; // Importing atomics for the selected transport.
+use streaming::StreamingSource; // Importing the WebSocket backend.
+use tokio::sync::mpsc; // Importing mpsc to hand events to the consumer.
+
+// Capacity of the channel between a source and its consumer.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+// Whether views read market data from the WebSocket feed instead of REST polling.
+static STREAMING: AtomicBool = AtomicBool::new(false);
+
+// Kinds of market data a view can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataKind {
+    Trades,   // Individual trades.
+    Book,     // Order book snapshots and level changes.
+    Ticker,   // Last price, 24h change and volume.
+    Candles,  // Five minute candles.
+    Products, // Product status and trading rules.
+}
+
+// Products and data kinds requested by a view.
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    pub product_ids: Vec<String>, // Products to receive data for.
+    pub kinds: Vec<DataKind>,     // Kinds of data to receive.
+}
+
+impl Subscription {
+    // Creates a subscription for the given products and kinds.
+    pub fn new(product_ids: &[String], kinds: &[DataKind]) -> Self {
+        Subscription {
+            product_ids: product_ids.to_vec(),
+            kinds: kinds.to_vec(),
+        }
+    }
+
+    // Returns true when the subscription includes the given kind.
+    pub fn wants(&self, kind: DataKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+}
+
+// Market data events, identical whichever transport produced them.
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    // A trade, delivered in chronological order.
+    Trade(MarketTrade),
+    // A complete order book that replaces any book held for the product.
+    BookSnapshot(OrderBook),
+    // A single level change on top of the last snapshot, zero size removes the level.
+    BookDelta {
+        product_id: String,
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+    },
+    // Latest ticker for a product.
+    Ticker(Ticker),
+    // A new or updated candle.
+    Candle(CandleUpdate),
+    // Product status and trading rules.
+    ProductUpdate(ProductStatus),
+    // Trades for a product may have been missed.
+    Gap {
+        product_id: String,
+    },
+    // Books and tapes must be rebuilt from the snapshots that follow.
+    Reset(String),
+    // Transport state worth showing, such as errors, disconnects or silent products.
+    Notice(String),
+}
+
+// A source of market data events.
+pub trait MarketDataSource: Send + Sync {
+    // Short name of the transport, for display.
+    fn name(&self) -> &'static str;
+
+    // Starts delivering events for the subscription. Delivery stops when the receiver is dropped.
+    fn subscribe(&self, subscription: &Subscription) -> mpsc::Receiver<MarketEvent>;
+}
+
+// Transports the views can read market data from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Polling,   // REST endpoints polled on an interval.
+    Streaming, // The public WebSocket feed.
+}
+
+impl Transport {
+    // Parses a transport name as accepted on the command line.
+    pub fn parse(name: &str) -> Option<Transport> {
+        match name.to_lowercase().as_str() {
+            "rest" | "poll" | "polling" => Some(Transport::Polling),
+            "ws" | "websocket" | "stream" | "streaming" => Some(Transport::Streaming),
+            _ => None,
+        }
+    }
+
+    // Returns a readable name for the transport.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Transport::Polling => "REST polling",
+            Transport::Streaming => "WebSocket streaming",
+        }
+    }
+}
+
+// Selects the transport used by the live views.
+pub fn set_transport(transport: Transport) {
+    STREAMING.store(transport == Transport::Streaming, Ordering::Relaxed);
+}
+
+// Returns the transport used by the live views.
+pub fn transport() -> Transport {
+    if STREAMING.load(Ordering::Relaxed) {
+        Transport::Streaming
+    } else {
+        Transport::Polling
+    }
+}
+
+// Creates a source for the given transport.
+pub fn source_for(transport: Transport) -> Box<dyn MarketDataSource> {
+    match transport {
+        Transport::Polling => Box::new(PollingSource::default()),
+        Transport::Streaming => Box::new(StreamingSource::new(WEBSOCKET_URL)),
+    }
+}
+
+// Creates a source for the selected transport.
+pub fn source() -> Box<dyn MarketDataSource> {
+    source_for(transport())
+}
+
+// Waits for the next event and collects any others already queued, so views redraw once per burst.
+// Returns None once the source has stopped.
+pub async fn recv_batch(events: &mut mpsc::Receiver<MarketEvent>) -> Option<Vec<MarketEvent>> {
+    let mut batch = vec![events.recv().await?];
+    while let Ok(event) = events.try_recv() {
+        batch.push(event);
+    }
+    Some(batch)
+}
+
+// Creates the channel between a source and its consumer.
+fn event_channel() -> (mpsc::Sender<MarketEvent>, mpsc::Receiver<MarketEvent>) {
+    mpsc::channel(EVENT_CHANNEL_CAPACITY)
+}
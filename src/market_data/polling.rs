@@ -0,0 +1,238 @@
+use super::{event_channel, DataKind, MarketDataSource, MarketEvent, Subscription}; // Importing the source interface.
+use crate::api::candles::fetch_candles; // Importing the candles endpoint.
+use crate::api::market_trades::fetch_market_trades; // Importing the market trades endpoint.
+use crate::api::product_book::fetch_product_book; // Importing the product book endpoint.
+use crate::api::specific_product::fetch_specific_product; // Importing the product endpoint.
+use crate::models::Product; // Importing the Product model.
+use crate::order_book::OrderBook; // Importing the order book built from REST snapshots.
+use crate::trade_tape::TradeTape; // Importing the tape used to drop repeated trades.
+use crate::websocket::messages::{CandleUpdate, ProductStatus, Ticker}; // Importing the normalised payloads.
+use chrono::Utc; // Importing Utc to build candle time ranges.
+use std::time::Duration; // Importing Duration for poll intervals.
+use tokio::sync::mpsc; // Importing mpsc to hand events to the consumer.
+use tokio::time; // Importing tokio time utilities.
+
+// Number of trade IDs remembered per product to drop trades repeated across polls.
+const TRADE_HISTORY: usize = 1000;
+// Granularity of polled candles, matching the candles feed.
+const CANDLE_GRANULARITY: &str = "FIVE_MINUTE";
+// Time range of polled candles, covering the current and the previous candle.
+const CANDLE_LOOKBACK_SECONDS: i64 = 600;
+
+// Market data source that polls the REST endpoints.
+#[derive(Debug, Clone)]
+pub struct PollingSource {
+    pub interval: Duration, // Delay between polls of trades, books and products.
+    pub candle_interval: Duration, // Delay between polls of candles.
+}
+
+impl Default for PollingSource {
+    fn default() -> Self {
+        PollingSource {
+            interval: Duration::from_millis(750),
+            candle_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl MarketDataSource for PollingSource {
+    fn name(&self) -> &'static str {
+        "REST polling"
+    }
+
+    // Starts one polling task per product and kind of data.
+    fn subscribe(&self, subscription: &Subscription) -> mpsc::Receiver<MarketEvent> {
+        let (tx, rx) = event_channel();
+        for product_id in &subscription.product_ids {
+            if subscription.wants(DataKind::Trades) {
+                tokio::spawn(poll_trades(product_id.clone(), self.interval, tx.clone()));
+            }
+            if subscription.wants(DataKind::Book) {
+                tokio::spawn(poll_book(product_id.clone(), self.interval, tx.clone()));
+            }
+            // Tickers and product updates both come from the product endpoint.
+            let ticker = subscription.wants(DataKind::Ticker);
+            let products = subscription.wants(DataKind::Products);
+            if ticker || products {
+                tokio::spawn(poll_product(
+                    product_id.clone(),
+                    self.interval,
+                    ticker,
+                    products,
+                    tx.clone(),
+                ));
+            }
+            if subscription.wants(DataKind::Candles) {
+                tokio::spawn(poll_candles(
+                    product_id.clone(),
+                    self.candle_interval,
+                    tx.clone(),
+                ));
+            }
+        }
+        rx
+    }
+}
+
+// Polls trades, forwarding only the ones not seen before in chronological order.
+async fn poll_trades(product_id: String, interval: Duration, tx: mpsc::Sender<MarketEvent>) {
+    let mut tape = TradeTape::new(TRADE_HISTORY);
+    while wait(interval, &tx).await {
+        let events = match fetch_market_trades(&product_id).await {
+            Ok(trades) => {
+                let update = tape.ingest(trades);
+                let mut events = Vec::new();
+                if update.possible_gap {
+                    events.push(MarketEvent::Gap {
+                        product_id: product_id.clone(),
+                    });
+                }
+                events.extend(update.new_trades.into_iter().rev().map(MarketEvent::Trade));
+                events
+            }
+            Err(e) => vec![MarketEvent::Notice(format!(
+                "Error fetching market trades for {}: {}",
+                product_id, e
+            ))],
+        };
+        if !send_all(&tx, events).await {
+            return;
+        }
+    }
+}
+
+// Polls the order book, forwarding every poll as a snapshot.
+async fn poll_book(product_id: String, interval: Duration, tx: mpsc::Sender<MarketEvent>) {
+    while wait(interval, &tx).await {
+        let event = match fetch_product_book(&product_id).await {
+            Ok(product_book) => match OrderBook::from_product_book(&product_book) {
+                Ok(book) => MarketEvent::BookSnapshot(book),
+                Err(e) => MarketEvent::Notice(format!(
+                    "Error reading product book for {}: {}",
+                    product_id, e
+                )),
+            },
+            Err(e) => MarketEvent::Notice(format!(
+                "Error fetching product book for {}: {}",
+                product_id, e
+            )),
+        };
+        if tx.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+// Polls the product, forwarding it as a ticker and/or a product update.
+async fn poll_product(
+    product_id: String,
+    interval: Duration,
+    ticker: bool,
+    products: bool,
+    tx: mpsc::Sender<MarketEvent>,
+) {
+    while wait(interval, &tx).await {
+        let events = match fetch_specific_product(&product_id).await {
+            Ok(product) => {
+                let mut events = Vec::new();
+                if ticker {
+                    events.push(MarketEvent::Ticker(ticker_from_product(&product)));
+                }
+                if products {
+                    events.push(MarketEvent::ProductUpdate(status_from_product(&product)));
+                }
+                events
+            }
+            Err(e) => vec![MarketEvent::Notice(format!(
+                "Error fetching product data for {}: {}",
+                product_id, e
+            ))],
+        };
+        if !send_all(&tx, events).await {
+            return;
+        }
+    }
+}
+
+// Polls the latest candles, forwarding them oldest first.
+async fn poll_candles(product_id: String, interval: Duration, tx: mpsc::Sender<MarketEvent>) {
+    while wait(interval, &tx).await {
+        let end = Utc::now();
+        let start = end - chrono::Duration::seconds(CANDLE_LOOKBACK_SECONDS);
+        let events = match fetch_candles(
+            &product_id,
+            &start.to_rfc3339(),
+            &end.to_rfc3339(),
+            CANDLE_GRANULARITY,
+        )
+        .await
+        {
+            // The endpoint returns candles newest first.
+            Ok(candles) => candles
+                .into_iter()
+                .rev()
+                .map(|candle| {
+                    MarketEvent::Candle(CandleUpdate {
+                        product_id: product_id.clone(),
+                        candle,
+                    })
+                })
+                .collect(),
+            Err(e) => vec![MarketEvent::Notice(format!(
+                "Error fetching candles for {}: {}",
+                product_id, e
+            ))],
+        };
+        if !send_all(&tx, events).await {
+            return;
+        }
+    }
+}
+
+// Builds a ticker from the fields the product endpoint shares with the ticker feed.
+fn ticker_from_product(product: &Product) -> Ticker {
+    Ticker {
+        product_id: product.product_id.clone(),
+        price: product.price.clone(),
+        volume_24_h: product.volume_24h.clone(),
+        price_percent_chg_24_h: product.price_percentage_change_24h.clone(),
+        volume_percent_chg_24_h: product.volume_percentage_change_24h.clone(),
+        ..Ticker::default()
+    }
+}
+
+// Builds a product status from the fields the product endpoint shares with the status feed.
+fn status_from_product(product: &Product) -> ProductStatus {
+    ProductStatus {
+        id: product.product_id.clone(),
+        product_type: product.product_type.clone(),
+        base_currency: product.base_currency_id.clone(),
+        quote_currency: product.quote_currency_id.clone(),
+        base_name: product.base_name.clone(),
+        quote_name: product.quote_name.clone(),
+        base_increment: product.base_increment.clone(),
+        quote_increment: product.quote_increment.clone(),
+        display_name: product.display_name.clone(),
+        status: product.status.clone(),
+        status_message: String::new(),
+        min_market_funds: product.quote_min_size.clone(),
+    }
+}
+
+// Waits for the next poll, returning false once the consumer has gone away.
+async fn wait(interval: Duration, tx: &mpsc::Sender<MarketEvent>) -> bool {
+    tokio::select! {
+        _ = tx.closed() => false,
+        _ = time::sleep(interval) => true,
+    }
+}
+
+// Sends events in order, returning false once the consumer has gone away.
+async fn send_all(tx: &mpsc::Sender<MarketEvent>, events: Vec<MarketEvent>) -> bool {
+    for event in events {
+        if tx.send(event).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
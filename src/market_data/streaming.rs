@@ -0,0 +1,169 @@
+use super::{event_channel, DataKind, MarketDataSource, MarketEvent, Subscription}; // Importing the source interface.
+use crate::order_book::{parse_updates, OrderBook}; // Importing book types for level2 events.
+use crate::websocket::feed::{spawn_feed, FeedConfig, FeedEvent, ResetReason}; // Importing the supervised feed.
+use crate::websocket::messages::{Channel, Level2Event, StreamEvent}; // Importing feed channels and events.
+use tokio::sync::mpsc; // Importing mpsc to hand events to the consumer.
+
+// Market data source that reads the public WebSocket feed.
+#[derive(Debug, Clone)]
+pub struct StreamingSource {
+    url: String, // Feed URL.
+}
+
+impl StreamingSource {
+    // Creates a source reading the feed at the given URL (e.g. WEBSOCKET_URL or a local test server).
+    pub fn new(url: &str) -> Self {
+        StreamingSource {
+            url: url.to_string(),
+        }
+    }
+}
+
+impl MarketDataSource for StreamingSource {
+    fn name(&self) -> &'static str {
+        "WebSocket streaming"
+    }
+
+    // Starts a supervised feed for the subscribed channels and translates its events.
+    fn subscribe(&self, subscription: &Subscription) -> mpsc::Receiver<MarketEvent> {
+        let channels: Vec<Channel> = subscription
+            .kinds
+            .iter()
+            .map(|kind| channel(*kind))
+            .collect();
+        let config = FeedConfig::new(&self.url, &channels, &subscription.product_ids);
+        let (tx, rx) = event_channel();
+        tokio::spawn(forward(config, tx));
+        rx
+    }
+}
+
+// Returns the feed channel carrying a kind of data.
+fn channel(kind: DataKind) -> Channel {
+    match kind {
+        DataKind::Trades => Channel::MarketTrades,
+        DataKind::Book => Channel::Level2,
+        DataKind::Ticker => Channel::Ticker,
+        DataKind::Candles => Channel::Candles,
+        DataKind::Products => Channel::Status,
+    }
+}
+
+// Translates feed events until either side goes away. Dropping the feed receiver stops the feed.
+//
+// When a message cannot be applied to a book, the feed is restarted so a fresh connection
+// delivers the snapshots the consumer rebuilds from.
+async fn forward(config: FeedConfig, tx: mpsc::Sender<MarketEvent>) {
+    let mut feed = spawn_feed(config.clone());
+    loop {
+        let event = tokio::select! {
+            _ = tx.closed() => return,
+            event = feed.recv() => match event {
+                Some(event) => event,
+                None => return,
+            },
+        };
+        let from_message = matches!(event, FeedEvent::Message(_));
+        let events = translate(event);
+        let resync = from_message && events.iter().any(|e| matches!(e, MarketEvent::Reset(_)));
+        for event in events {
+            if tx.send(event).await.is_err() {
+                return;
+            }
+        }
+        if resync {
+            feed = spawn_feed(config.clone());
+        }
+    }
+}
+
+// Converts one feed event into market data events.
+fn translate(event: FeedEvent) -> Vec<MarketEvent> {
+    match event {
+        FeedEvent::Message(message) => message
+            .events
+            .into_iter()
+            .flat_map(translate_stream_event)
+            .collect(),
+        FeedEvent::Reset(ResetReason::Reconnected) => {
+            vec![MarketEvent::Reset("feed reconnected".to_string())]
+        }
+        FeedEvent::Reset(ResetReason::SequenceGap { expected, received }) => {
+            vec![MarketEvent::Reset(format!(
+                "sequence gap: expected {}, received {}",
+                expected, received
+            ))]
+        }
+        FeedEvent::Connected => vec![MarketEvent::Notice("Feed connected".to_string())],
+        FeedEvent::Stale {
+            product_id,
+            silent_for,
+        } => vec![MarketEvent::Notice(format!(
+            "No updates for {} in {}s",
+            product_id,
+            silent_for.as_secs()
+        ))],
+        FeedEvent::Recovered { product_id } => vec![MarketEvent::Notice(format!(
+            "Updates resumed for {}",
+            product_id
+        ))],
+        FeedEvent::Disconnected { reason, retry_in } => vec![MarketEvent::Notice(format!(
+            "Feed disconnected ({}), retrying in {:?}",
+            reason, retry_in
+        ))],
+        FeedEvent::Error(e) => vec![MarketEvent::Notice(format!("Feed error: {}", e))],
+    }
+}
+
+// Converts one typed feed event into market data events.
+fn translate_stream_event(event: StreamEvent) -> Vec<MarketEvent> {
+    match event {
+        StreamEvent::Ticker(e) | StreamEvent::TickerBatch(e) => {
+            e.tickers.into_iter().map(MarketEvent::Ticker).collect()
+        }
+        StreamEvent::Level2(e) => translate_level2(e),
+        // The feed sends trades newest first.
+        StreamEvent::MarketTrades(e) => {
+            e.trades.into_iter().rev().map(MarketEvent::Trade).collect()
+        }
+        StreamEvent::Candles(e) => e.candles.into_iter().map(MarketEvent::Candle).collect(),
+        StreamEvent::Status(e) => e
+            .products
+            .into_iter()
+            .map(MarketEvent::ProductUpdate)
+            .collect(),
+        StreamEvent::Heartbeat(_) | StreamEvent::Subscriptions(_) => Vec::new(),
+    }
+}
+
+// Converts a level2 snapshot into a book, and level2 updates into deltas.
+fn translate_level2(event: Level2Event) -> Vec<MarketEvent> {
+    if event.kind == "snapshot" {
+        let mut book = OrderBook::new(&event.product_id);
+        return match book.apply_event(&event) {
+            Ok(()) => vec![MarketEvent::BookSnapshot(book)],
+            Err(e) => vec![MarketEvent::Reset(format!(
+                "invalid order book snapshot for {}: {}",
+                event.product_id, e
+            ))],
+        };
+    }
+
+    // A batch with an invalid level is dropped whole, and the book must then be rebuilt since
+    // it no longer matches the feed.
+    match parse_updates(&event.updates) {
+        Ok(levels) => levels
+            .into_iter()
+            .map(|(side, price, size)| MarketEvent::BookDelta {
+                product_id: event.product_id.clone(),
+                side,
+                price,
+                size,
+            })
+            .collect(),
+        Err(e) => vec![MarketEvent::Reset(format!(
+            "invalid order book update for {}: {}",
+            event.product_id, e
+        ))],
+    }
+}
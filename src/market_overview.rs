@@ -0,0 +1,203 @@
+use crate::conversion::ConversionGraph; // Importing the conversion graph to total volume in one currency.
+use crate::models::Product; // Importing the Product model.
+use std::collections::HashMap; // Importing HashMap to total volume by group.
+
+// A product's 24h price move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mover {
+    pub product_id: String, // Product ID.
+    pub price: String,      // Current price, as reported.
+    pub change: f64,        // 24h price change percentage.
+}
+
+// A product's approximate 24h volume in its quote currency and in the overview currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeLeader {
+    pub product_id: String,     // Product ID.
+    pub quote_currency: String, // Currency the quote volume is counted in.
+    pub quote_volume: f64,      // Approximate 24h quote volume.
+    pub volume: Option<f64>,    // Quote volume in the overview currency, None without a rate.
+}
+
+impl VolumeLeader {
+    // Returns the volume the leaders are ranked by: the converted one, or the quote volume as
+    // reported when the quote currency has no rate.
+    pub fn ranking_volume(&self) -> f64 {
+        self.volume.unwrap_or(self.quote_volume)
+    }
+}
+
+// Total 24h volume of a group of products.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeShare {
+    pub name: String,    // Quote currency or product type.
+    pub products: usize, // Number of products in the group.
+    pub volume: f64,     // Summed approximate 24h volume, in the quote or overview currency.
+}
+
+// Summary of the whole market from one products listing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketOverview {
+    pub product_count: usize,              // Products listed.
+    pub tradable_count: usize,             // Products online with trading enabled.
+    pub gainers: Vec<Mover>,               // Largest 24h rises, largest first.
+    pub losers: Vec<Mover>,                // Largest 24h falls, largest first.
+    pub volume_leaders: Vec<VolumeLeader>, // Highest 24h quote volume, highest first.
+    pub new_listings: Vec<String>,         // Products flagged as new.
+    pub auction_mode: Vec<String>,         // Products in auction mode.
+    pub limit_only: Vec<String>,           // Products accepting limit orders only.
+    pub cancel_only: Vec<String>,          // Products accepting cancels only.
+    pub volume_by_quote: Vec<VolumeShare>, // Volume per quote currency, highest first.
+    pub volume_by_type: Vec<VolumeShare>,  // Volume per product type in `volume_currency`.
+    pub volume_currency: String,           // Currency volume by product type is totalled in.
+    pub unconverted: usize,                // Products without a rate, left out of volume by type.
+}
+
+impl MarketOverview {
+    // Builds the overview, keeping `top` products in each ranking.
+    //
+    // Rankings and volume totals only count tradable products, those online with trading
+    // enabled, while the mode lists cover every product. Volume by product type spans quote
+    // currencies, so each product's quote volume is converted into `currency` at the rates of
+    // the listed products first; products whose quote currency cannot be converted are left
+    // out of it and counted. Volume leaders are ranked on the same converted volumes.
+    pub fn from_products(products: &[Product], top: usize, currency: &str) -> Self {
+        let tradable: Vec<&Product> = products
+            .iter()
+            .filter(|product| is_tradable(product))
+            .collect();
+
+        let graph = ConversionGraph::from_products(products);
+        let mut rates: HashMap<String, Option<f64>> = HashMap::new();
+        let mut converted_volume = |product: &Product| {
+            let quote = product.quote_currency_id.to_uppercase();
+            let rate = *rates
+                .entry(quote)
+                .or_insert_with_key(|quote| graph.rate(quote, currency));
+            Some(quote_volume(product)? * rate?)
+        };
+        let mut unconverted = 0;
+        let converted: Vec<(&Product, Option<f64>)> = tradable
+            .iter()
+            .map(|product| {
+                let volume = converted_volume(product);
+                if volume.is_none() && quote_volume(product).is_some() {
+                    unconverted += 1;
+                }
+                (*product, volume)
+            })
+            .collect();
+
+        let mut movers: Vec<Mover> = tradable
+            .iter()
+            .filter_map(|product| {
+                Some(Mover {
+                    product_id: product.product_id.clone(),
+                    price: product.price.clone(),
+                    change: product.price_percentage_change_24h.parse().ok()?,
+                })
+            })
+            .filter(|mover| mover.change.is_finite())
+            .collect();
+        movers.sort_by(|a, b| b.change.total_cmp(&a.change));
+        let gainers: Vec<Mover> = movers
+            .iter()
+            .filter(|mover| mover.change > 0.0)
+            .take(top)
+            .cloned()
+            .collect();
+        let losers: Vec<Mover> = movers
+            .iter()
+            .rev()
+            .filter(|mover| mover.change < 0.0)
+            .take(top)
+            .cloned()
+            .collect();
+
+        let mut volume_leaders: Vec<VolumeLeader> = converted
+            .iter()
+            .filter_map(|(product, volume)| {
+                Some(VolumeLeader {
+                    product_id: product.product_id.clone(),
+                    quote_currency: product.quote_currency_id.clone(),
+                    quote_volume: quote_volume(product)?,
+                    volume: *volume,
+                })
+            })
+            .collect();
+        volume_leaders.sort_by(|a, b| b.ranking_volume().total_cmp(&a.ranking_volume()));
+        volume_leaders.truncate(top);
+
+        let ids = |flag: fn(&Product) -> bool| -> Vec<String> {
+            products
+                .iter()
+                .filter(|product| flag(product))
+                .map(|product| product.product_id.clone())
+                .collect()
+        };
+
+        MarketOverview {
+            product_count: products.len(),
+            tradable_count: tradable.len(),
+            gainers,
+            losers,
+            volume_leaders,
+            new_listings: ids(|product| product.new),
+            auction_mode: ids(|product| product.auction_mode),
+            limit_only: ids(|product| product.limit_only),
+            cancel_only: ids(|product| product.cancel_only),
+            volume_by_quote: volume_shares(
+                tradable
+                    .iter()
+                    .map(|product| (&product.quote_currency_id, quote_volume(product))),
+            ),
+            volume_by_type: volume_shares(
+                converted
+                    .into_iter()
+                    .map(|(product, volume)| (&product.product_type, volume)),
+            ),
+            volume_currency: currency.to_uppercase(),
+            unconverted,
+        }
+    }
+}
+
+// Returns true when a product is online with trading enabled.
+fn is_tradable(product: &Product) -> bool {
+    product.status.eq_ignore_ascii_case("online")
+        && !product.trading_disabled
+        && !product.is_disabled
+}
+
+// Returns a product's approximate 24h quote volume, if reported.
+fn quote_volume(product: &Product) -> Option<f64> {
+    product
+        .approximate_quote_24h_volume
+        .parse()
+        .ok()
+        .filter(|volume: &f64| volume.is_finite())
+}
+
+// Totals the products' volume per group, given as each product's group and volume, highest
+// volume first.
+fn volume_shares<'a>(
+    products: impl Iterator<Item = (&'a String, Option<f64>)>,
+) -> Vec<VolumeShare> {
+    let mut totals: HashMap<&str, (usize, f64)> = HashMap::new();
+    for (name, volume) in products {
+        let name = if name.is_empty() { "unknown" } else { name };
+        let total = totals.entry(name).or_default();
+        total.0 += 1;
+        total.1 += volume.unwrap_or(0.0);
+    }
+    let mut shares: Vec<VolumeShare> = totals
+        .into_iter()
+        .map(|(name, (products, volume))| VolumeShare {
+            name: name.to_string(),
+            products,
+            volume,
+        })
+        .collect();
+    shares.sort_by(|a, b| b.volume.total_cmp(&a.volume).then(a.name.cmp(&b.name)));
+    shares
+}
@@ -1,19 +1,37 @@
 use crate::clear_screen;
+use crate::config::Config;
 use crate::handlers::{
-    candles_handler::fetch_candles_handler, market_trades_handler::fetch_market_trades_handler,
-    product_book_handler::fetch_product_book_handler, products_handler::fetch_products_handler,
+    arbitrage_handler::{arbitrage_handler, prompt_for_arbitrage},
+    candles_handler::fetch_candles_handler,
+    conversion_handler::{
+        apply_requested_currency, convert_handler, current_reporting_currency,
+        reporting_currency_handler,
+    },
+    live_candles_handler::{live_candles_handler, prompt_for_live_candles},
+    market_overview_handler::fetch_market_overview_handler,
+    market_trades_handler::fetch_market_trades_handler,
+    product_book_handler::fetch_product_book_handler,
+    products_handler::fetch_products_handler,
     server_time_handler::fetch_server_time_handler,
+    slippage_handler::slippage_handler,
     specific_product_handler::fetch_specific_product_handler,
+    trade_bars_handler::{parse_bar_rule, trade_bars_handler},
+    watchlist_handler::fetch_watchlist_handler,
 };
-use crate::task_runner::{fetch_all_data, start_looping_task};
+use crate::market_data::{self, Transport};
+use crate::task_runner::{fetch_all_data, run_until_ctrl_c, start_looping_task};
+use crate::terminal::prompt;
 use anyhow::Result;
 use std::io::{self, Write};
 
+// Menu choices whose views show prices, and so apply the reporting currency first.
+const PRICE_VIEWS: [u8; 11] = [1, 3, 4, 5, 6, 7, 8, 10, 11, 12, 13];
+
 pub async fn show_menu() -> Result<()> {
     loop {
         // Display menu options to the user
-        println!("");
-        println!("");
+        println!();
+        println!();
         println!("Menu:");
         println!("1. Fetch and print all products");
         println!("2. Fetch and print server time");
@@ -24,7 +42,22 @@ pub async fn show_menu() -> Result<()> {
         println!(
             "7. Fetch and print all data (trades, specific product, book) for a specific product"
         );
-        println!("8. Exit");
+        println!("8. Watch a list of products");
+        println!(
+            "9. Switch live data transport (current: {})",
+            market_data::transport().name()
+        );
+        println!("10. Estimate market order slippage for a specific product");
+        println!("11. Chart live bars (time, tick, volume, dollar, imbalance) built from the trade stream");
+        println!("12. Watch live candles for a specific product");
+        println!("13. Show a market overview (top movers, volume leaders, product modes)");
+        println!("14. Monitor triangular arbitrage and cross rates");
+        println!(
+            "15. Set the reporting currency for prices (current: {})",
+            current_reporting_currency().unwrap_or_else(|| "quote currency".to_string())
+        );
+        println!("16. Convert an amount between currencies");
+        println!("17. Exit");
         print!("Enter your choice: ");
         io::stdout().flush().unwrap();
 
@@ -32,6 +65,11 @@ pub async fn show_menu() -> Result<()> {
         let mut choice = String::new();
         io::stdin().read_line(&mut choice).unwrap();
         let choice = choice.trim().parse::<u8>().unwrap_or(0);
+        if PRICE_VIEWS.contains(&choice) {
+            if let Err(e) = apply_requested_currency().await {
+                println!("Showing prices in quote currencies: {}", e);
+            }
+        }
 
         // Match the user's choice and execute the corresponding handler
         match choice {
@@ -63,7 +101,84 @@ pub async fn show_menu() -> Result<()> {
                 clear_screen::clear_screen();
                 start_looping_task(fetch_all_data).await?;
             }
-            8 => break, // Exit the loop
+            8 => {
+                clear_screen::clear_screen();
+                fetch_watchlist_handler().await?;
+            }
+            9 => {
+                // Toggle between REST polling and the WebSocket feed for the live views.
+                let transport = match market_data::transport() {
+                    Transport::Polling => Transport::Streaming,
+                    Transport::Streaming => Transport::Polling,
+                };
+                market_data::set_transport(transport);
+                println!("Live views now use {}.", transport.name());
+            }
+            10 => {
+                clear_screen::clear_screen();
+                slippage_handler().await?;
+            }
+            11 => {
+                let rule = prompt("Enter the bar rule (e.g. 5s, 30s, tick:100, volume:10, dollar:1000000, imbalance:50): ");
+                match parse_bar_rule(&rule) {
+                    Ok(rule) => {
+                        clear_screen::clear_screen();
+                        start_looping_task(move |rx, product_id| {
+                            trade_bars_handler(rx, product_id, rule)
+                        })
+                        .await?;
+                    }
+                    Err(e) => println!("{}", e),
+                }
+            }
+            12 => match prompt_for_live_candles() {
+                Ok(settings) => {
+                    clear_screen::clear_screen();
+                    start_looping_task(move |rx, product_id| {
+                        live_candles_handler(rx, product_id, settings.clone())
+                    })
+                    .await?;
+                }
+                Err(e) => println!("{}", e),
+            },
+            13 => {
+                clear_screen::clear_screen();
+                fetch_market_overview_handler().await?;
+            }
+            14 => {
+                // A config that fails to load is left as it is rather than overwritten.
+                let mut config = match Config::load() {
+                    Ok(config) => Some(config),
+                    Err(e) => {
+                        println!(
+                            "Error loading config, the settings will not be saved: {}",
+                            e
+                        );
+                        None
+                    }
+                };
+                let saved = config
+                    .as_ref()
+                    .map(|c| c.arbitrage.clone())
+                    .unwrap_or_default();
+                match prompt_for_arbitrage(saved) {
+                    Ok(settings) => {
+                        // Keep the entered settings for next time.
+                        if let Some(config) = &mut config {
+                            config.arbitrage = settings.clone();
+                            if let Err(e) = config.save() {
+                                println!("Error saving config: {}", e);
+                            }
+                        }
+                        clear_screen::clear_screen();
+                        run_until_ctrl_c(|rx| arbitrage_handler(rx, settings)).await?;
+                    }
+                    Err(e) => println!("{}", e),
+                }
+            }
+            15 => reporting_currency_handler().await?,
+            16 => convert_handler().await?,
+            17 => break, // Exit the loop
             _ => println!("Invalid choice, please try again."),
         }
     }
@@ -108,6 +108,101 @@ pub struct Product {
     pub approximate_quote_24h_volume: String, // Approximate 24h quote volume.
 }
 
+impl Product {
+    // Names of the product fields, in the order `fields` returns them.
+    pub const FIELD_NAMES: [&'static str; 36] = [
+        "product_id",
+        "price",
+        "price_percentage_change_24h",
+        "volume_24h",
+        "volume_percentage_change_24h",
+        "base_increment",
+        "quote_increment",
+        "quote_min_size",
+        "quote_max_size",
+        "base_min_size",
+        "base_max_size",
+        "base_name",
+        "quote_name",
+        "watched",
+        "is_disabled",
+        "new",
+        "status",
+        "cancel_only",
+        "limit_only",
+        "post_only",
+        "trading_disabled",
+        "auction_mode",
+        "product_type",
+        "quote_currency_id",
+        "base_currency_id",
+        "fcm_trading_session_details",
+        "mid_market_price",
+        "alias",
+        "alias_to",
+        "base_display_symbol",
+        "quote_display_symbol",
+        "view_only",
+        "price_increment",
+        "display_name",
+        "product_venue",
+        "approximate_quote_24h_volume",
+    ];
+
+    // Returns every field of the product as (field name, display value) pairs, in declaration order.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        Product::FIELD_NAMES
+            .into_iter()
+            .zip(self.field_values())
+            .collect()
+    }
+
+    // Returns the display value of every field, in the order of `FIELD_NAMES`.
+    fn field_values(&self) -> [String; 36] {
+        [
+            self.product_id.clone(),
+            self.price.clone(),
+            self.price_percentage_change_24h.clone(),
+            self.volume_24h.clone(),
+            self.volume_percentage_change_24h.clone(),
+            self.base_increment.clone(),
+            self.quote_increment.clone(),
+            self.quote_min_size.clone(),
+            self.quote_max_size.clone(),
+            self.base_min_size.clone(),
+            self.base_max_size.clone(),
+            self.base_name.clone(),
+            self.quote_name.clone(),
+            self.watched.to_string(),
+            self.is_disabled.to_string(),
+            self.new.to_string(),
+            self.status.clone(),
+            self.cancel_only.to_string(),
+            self.limit_only.to_string(),
+            self.post_only.to_string(),
+            self.trading_disabled.to_string(),
+            self.auction_mode.to_string(),
+            self.product_type.clone(),
+            self.quote_currency_id.clone(),
+            self.base_currency_id.clone(),
+            self.fcm_trading_session_details
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            self.mid_market_price.clone(),
+            self.alias.clone(),
+            self.alias_to.join(","),
+            self.base_display_symbol.clone(),
+            self.quote_display_symbol.clone(),
+            self.view_only.to_string(),
+            self.price_increment.clone(),
+            self.display_name.clone(),
+            self.product_venue.clone(),
+            self.approximate_quote_24h_volume.clone(),
+        ]
+    }
+}
+
 // Struct to represent a response containing a list of products.
 #[derive(Deserialize, Debug)]
 pub struct ProductsResponse {
@@ -115,7 +210,7 @@ pub struct ProductsResponse {
 }
 
 // Struct to represent a single candle (OHLC data).
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Candle {
     pub start: String,  // Start time of the candle.
     pub low: String,    // Low price during the candle period.
@@ -132,7 +227,7 @@ pub struct CandlesResponse {
 }
 
 // Struct to represent a market trade.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct MarketTrade {
     pub trade_id: String,    // ID of the trade.
     pub product_id: String,  // ID of the product.
@@ -149,3 +244,57 @@ pub struct MarketTrade {
 pub struct MarketTradesResponse {
     pub trades: Vec<MarketTrade>, // List of market trades.
 }
+
+// Struct to represent the best bid and ask reported alongside the market trades.
+#[derive(Deserialize, Debug)]
+pub struct BestBidAsk {
+    pub best_bid: Option<String>, // Best bid price (optional).
+    pub best_ask: Option<String>, // Best ask price (optional).
+}
+
+#[cfg(test)]
+impl Product {
+    // Builds an online spot product such as ETH-USD for tests, priced at `price` with 0.01 price
+    // and 0.00000001 base increments and no size limits.
+    pub(crate) fn for_test(product_id: &str, price: &str) -> Product {
+        let (base, quote) = product_id.split_once('-').unwrap_or((product_id, ""));
+        Product {
+            product_id: product_id.to_string(),
+            price: price.to_string(),
+            price_percentage_change_24h: "0".to_string(),
+            volume_24h: "0".to_string(),
+            volume_percentage_change_24h: "0".to_string(),
+            base_increment: "0.00000001".to_string(),
+            quote_increment: "0.01".to_string(),
+            quote_min_size: "0".to_string(),
+            quote_max_size: "0".to_string(),
+            base_min_size: "0".to_string(),
+            base_max_size: "0".to_string(),
+            base_name: base.to_string(),
+            quote_name: quote.to_string(),
+            watched: false,
+            is_disabled: false,
+            new: false,
+            status: "online".to_string(),
+            cancel_only: false,
+            limit_only: false,
+            post_only: false,
+            trading_disabled: false,
+            auction_mode: false,
+            product_type: "SPOT".to_string(),
+            quote_currency_id: quote.to_string(),
+            base_currency_id: base.to_string(),
+            fcm_trading_session_details: None,
+            mid_market_price: price.to_string(),
+            alias: String::new(),
+            alias_to: Vec::new(),
+            base_display_symbol: base.to_string(),
+            quote_display_symbol: quote.to_string(),
+            view_only: false,
+            price_increment: "0.01".to_string(),
+            display_name: product_id.replace('-', "/"),
+            product_venue: "CBE".to_string(),
+            approximate_quote_24h_volume: "0".to_string(),
+        }
+    }
+}
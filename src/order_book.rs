@@ -0,0 +1,351 @@
+use crate::models::{ProductBook, ProductBookEntry, ProductBookPriceBook}; // Importing the REST book models.
+use crate::websocket::messages::{Level2Event, Level2Update}; // Importing the level2 feed events.
+use rust_decimal::Decimal; // Importing Decimal for exact prices and sizes.
+use std::collections::BTreeMap; // Importing BTreeMap to keep levels ordered by price.
+use std::fmt; // Importing fmt to describe book errors.
+use std::str::FromStr; // Importing FromStr to parse decimals.
+
+// Side of the order book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+impl Side {
+    // Parses the side names used by the REST API and the feed.
+    pub fn parse(side: &str) -> Option<Side> {
+        match side.to_lowercase().as_str() {
+            "bid" | "buy" => Some(Side::Bid),
+            "ask" | "offer" | "sell" => Some(Side::Ask),
+            _ => None,
+        }
+    }
+}
+
+// Errors raised while maintaining an order book.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookError {
+    SequenceGap { expected: u64, received: u64 }, // Messages were skipped, the book must be rebuilt.
+    NotSynced,                                    // An update arrived before a snapshot.
+    InvalidLevel(String),                         // A level could not be parsed.
+    OneSided,                                     // The book has no bids or no asks.
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookError::SequenceGap { expected, received } => write!(
+                f,
+                "sequence gap: expected {}, received {}",
+                expected, received
+            ),
+            BookError::NotSynced => write!(f, "order book has not received a snapshot"),
+            BookError::InvalidLevel(level) => write!(f, "invalid order book level: {}", level),
+            BookError::OneSided => write!(f, "order book has no bids or no asks"),
+        }
+    }
+}
+
+impl std::error::Error for BookError {}
+
+// Tracks feed sequence numbers and reports skipped messages.
+//
+// The feed numbers every message on a connection, across all channels, so one tracker
+// should observe every message received on the connection.
+#[derive(Debug, Default)]
+pub struct SequenceTracker {
+    last: Option<u64>, // Highest sequence number observed.
+}
+
+impl SequenceTracker {
+    // Records a sequence number, returning an error when messages were skipped.
+    //
+    // Duplicate and older sequence numbers are ignored, so a replayed message neither moves the
+    // tracker back nor makes the next message look like a gap.
+    pub fn observe(&mut self, sequence: u64) -> Result<(), BookError> {
+        match self.last {
+            Some(last) if sequence <= last => Ok(()),
+            Some(last) if sequence > last + 1 => {
+                self.last = Some(sequence);
+                Err(BookError::SequenceGap {
+                    expected: last + 1,
+                    received: sequence,
+                })
+            }
+            _ => {
+                self.last = Some(sequence);
+                Ok(())
+            }
+        }
+    }
+
+    // Forgets the last sequence number, e.g. after reconnecting.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+// In-memory order book keyed by exact decimal price.
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    product_id: String,               // ID of the product.
+    bids: BTreeMap<Decimal, Decimal>, // Bid sizes by price.
+    asks: BTreeMap<Decimal, Decimal>, // Ask sizes by price.
+    synced: bool,                     // Whether a snapshot has been applied since the last reset.
+}
+
+impl OrderBook {
+    // Creates an empty book waiting for a snapshot.
+    pub fn new(product_id: &str) -> Self {
+        OrderBook {
+            product_id: product_id.to_string(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            synced: false,
+        }
+    }
+
+    // Builds a synced book from a REST product book snapshot.
+    pub fn from_product_book(product_book: &ProductBook) -> Result<Self, BookError> {
+        let pricebook = &product_book.pricebook;
+        let mut book = OrderBook::new(&pricebook.product_id);
+        for entry in &pricebook.bids {
+            book.set_level(
+                Side::Bid,
+                parse_decimal(&entry.price)?,
+                parse_decimal(&entry.size)?,
+            );
+        }
+        for entry in &pricebook.asks {
+            book.set_level(
+                Side::Ask,
+                parse_decimal(&entry.price)?,
+                parse_decimal(&entry.size)?,
+            );
+        }
+        book.synced = true;
+        Ok(book)
+    }
+
+    // Applies a level2 feed event, dispatching on whether it is a snapshot or an update.
+    pub fn apply_event(&mut self, event: &Level2Event) -> Result<(), BookError> {
+        if event.kind == "snapshot" {
+            self.apply_snapshot(&event.updates)
+        } else {
+            self.apply_updates(&event.updates)
+        }
+    }
+
+    // Replaces the book content with a snapshot.
+    //
+    // On error the book is left empty and unsynced, waiting for the next snapshot.
+    pub fn apply_snapshot(&mut self, levels: &[Level2Update]) -> Result<(), BookError> {
+        self.invalidate();
+        for (side, price, size) in parse_updates(levels)? {
+            self.set_level(side, price, size);
+        }
+        self.synced = true;
+        Ok(())
+    }
+
+    // Applies incremental level changes on top of a snapshot.
+    //
+    // The whole batch is parsed before any level changes, so an invalid update leaves the book
+    // as it was.
+    pub fn apply_updates(&mut self, updates: &[Level2Update]) -> Result<(), BookError> {
+        if !self.synced {
+            return Err(BookError::NotSynced);
+        }
+        for (side, price, size) in parse_updates(updates)? {
+            self.set_level(side, price, size);
+        }
+        Ok(())
+    }
+
+    // Sets the size at a price level, removing the level when the size is zero.
+    pub fn set_level(&mut self, side: Side, price: Decimal, size: Decimal) {
+        let levels = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        if size.is_zero() {
+            levels.remove(&price);
+        } else {
+            levels.insert(price, size);
+        }
+    }
+
+    // Marks the book as stale so updates are rejected until the next snapshot.
+    pub fn invalidate(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.synced = false;
+    }
+
+    // Returns true once a snapshot has been applied and no gap has been reported since.
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    // Returns the ID of the product.
+    pub fn product_id(&self) -> &str {
+        &self.product_id
+    }
+
+    // Returns the highest bid as (price, size).
+    pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
+        self.bids.iter().next_back().map(|(p, s)| (*p, *s))
+    }
+
+    // Returns the lowest ask as (price, size).
+    pub fn best_ask(&self) -> Option<(Decimal, Decimal)> {
+        self.asks.iter().next().map(|(p, s)| (*p, *s))
+    }
+
+    // Iterates over bids from the best (highest) price down.
+    pub fn bids(&self) -> impl Iterator<Item = (&Decimal, &Decimal)> {
+        self.bids.iter().rev()
+    }
+
+    // Iterates over asks from the best (lowest) price up.
+    pub fn asks(&self) -> impl Iterator<Item = (&Decimal, &Decimal)> {
+        self.asks.iter()
+    }
+
+    // Returns the levels of one side as sizes by price.
+    pub fn levels(&self, side: Side) -> &BTreeMap<Decimal, Decimal> {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    // Returns up to `depth` best bids as (price, size).
+    pub fn top_bids(&self, depth: usize) -> Vec<(Decimal, Decimal)> {
+        self.bids().take(depth).map(|(p, s)| (*p, *s)).collect()
+    }
+
+    // Returns up to `depth` best asks as (price, size).
+    pub fn top_asks(&self, depth: usize) -> Vec<(Decimal, Decimal)> {
+        self.asks().take(depth).map(|(p, s)| (*p, *s)).collect()
+    }
+
+    // Converts the top `depth` levels into the REST product book model, for display and analytics.
+    pub fn to_product_book(&self, depth: usize) -> ProductBook {
+        let entries = |levels: Vec<(Decimal, Decimal)>| {
+            levels
+                .into_iter()
+                .map(|(price, size)| ProductBookEntry {
+                    price: price.to_string(),
+                    size: size.to_string(),
+                })
+                .collect()
+        };
+        ProductBook {
+            pricebook: ProductBookPriceBook {
+                product_id: self.product_id.clone(),
+                bids: entries(self.top_bids(depth)),
+                asks: entries(self.top_asks(depth)),
+                time: chrono::Utc::now().to_rfc3339(),
+            },
+        }
+    }
+}
+
+// Parses level changes from the feed into (side, price, size), failing on the first invalid one.
+pub fn parse_updates(levels: &[Level2Update]) -> Result<Vec<(Side, Decimal, Decimal)>, BookError> {
+    levels
+        .iter()
+        .map(|level| {
+            let side = Side::parse(&level.side)
+                .ok_or_else(|| BookError::InvalidLevel(format!("unknown side {}", level.side)))?;
+            Ok((
+                side,
+                parse_decimal(&level.price_level)?,
+                parse_decimal(&level.new_quantity)?,
+            ))
+        })
+        .collect()
+}
+
+// Parses a decimal string from the API, accepting scientific notation.
+pub fn parse_decimal(value: &str) -> Result<Decimal, BookError> {
+    Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .map_err(|_| BookError::InvalidLevel(value.to_string()))
+}
+
+// Parses REST levels into (price, size) pairs, keeping their order.
+pub fn parse_levels(entries: &[ProductBookEntry]) -> Result<Vec<(Decimal, Decimal)>, BookError> {
+    entries
+        .iter()
+        .map(|entry| Ok((parse_decimal(&entry.price)?, parse_decimal(&entry.size)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(side: &str, price: &str, size: &str) -> Level2Update {
+        Level2Update {
+            side: side.to_string(),
+            event_time: String::new(),
+            price_level: price.to_string(),
+            new_quantity: size.to_string(),
+        }
+    }
+
+    #[test]
+    fn invalid_update_leaves_the_book_unchanged() {
+        let mut book = OrderBook::new("BTC-USD");
+        book.apply_snapshot(&[level("bid", "100", "1"), level("offer", "101", "2")])
+            .unwrap();
+
+        let result = book.apply_updates(&[level("bid", "100", "0"), level("bid", "99", "x")]);
+
+        assert_eq!(result, Err(BookError::InvalidLevel("x".to_string())));
+        assert!(book.is_synced());
+        assert_eq!(
+            book.best_bid(),
+            Some((Decimal::from(100), Decimal::from(1)))
+        );
+        assert_eq!(
+            book.best_ask(),
+            Some((Decimal::from(101), Decimal::from(2)))
+        );
+    }
+
+    #[test]
+    fn invalid_snapshot_leaves_the_book_unsynced() {
+        let mut book = OrderBook::new("BTC-USD");
+        book.apply_snapshot(&[level("bid", "100", "1")]).unwrap();
+
+        assert!(book
+            .apply_snapshot(&[level("bid", "100", "1"), level("middle", "101", "1")])
+            .is_err());
+        assert!(!book.is_synced());
+        assert_eq!(book.best_bid(), None);
+    }
+
+    #[test]
+    fn sequence_tracker_ignores_duplicate_and_older_messages() {
+        let mut tracker = SequenceTracker::default();
+        assert_eq!(tracker.observe(5), Ok(()));
+        assert_eq!(tracker.observe(6), Ok(()));
+        assert_eq!(tracker.observe(6), Ok(()));
+        assert_eq!(tracker.observe(4), Ok(()));
+        assert_eq!(tracker.observe(7), Ok(()));
+        assert_eq!(
+            tracker.observe(9),
+            Err(BookError::SequenceGap {
+                expected: 8,
+                received: 9,
+            })
+        );
+        assert_eq!(tracker.observe(10), Ok(()));
+        tracker.reset();
+        assert_eq!(tracker.observe(1), Ok(()));
+    }
+}
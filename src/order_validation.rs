@@ -0,0 +1,482 @@
+use crate::models::Product; // Importing the Product model and its trading rules.
+use crate::order_book::parse_decimal; // Importing decimal parsing.
+use crate::slippage::{round_to_increment, size_limit, OrderAmount, OrderSide, SizeLimit}; // Importing order types and size checks.
+use rust_decimal::Decimal; // Importing Decimal for exact prices and sizes.
+use std::fmt; // Importing fmt to describe order types and violations.
+
+// Type of an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    Market, // Fills immediately against the book.
+    Limit,  // Rests at a limit price.
+}
+
+impl OrderType {
+    // Parses an order type name.
+    pub fn parse(kind: &str) -> Option<OrderType> {
+        match kind.to_lowercase().as_str() {
+            "market" => Some(OrderType::Market),
+            "limit" => Some(OrderType::Limit),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderType::Market => write!(f, "MARKET"),
+            OrderType::Limit => write!(f, "LIMIT"),
+        }
+    }
+}
+
+// A proposed order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    pub side: OrderSide,              // Side of the order.
+    pub order_type: OrderType,        // Market or limit.
+    pub amount: OrderAmount,          // Base size, or quote funds for market orders.
+    pub limit_price: Option<Decimal>, // Limit price, required for limit orders.
+    pub post_only: bool,              // Whether the order may only add liquidity.
+}
+
+// A trading rule the order breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    // Trading is disabled for the product.
+    TradingDisabled,
+    // The product status is not online.
+    NotOnline(String),
+    // The product only accepts cancellations.
+    CancelOnly,
+    // Market orders are not accepted.
+    LimitOnly,
+    // Only post-only orders are accepted.
+    PostOnly,
+    // Market orders cannot be post-only.
+    PostOnlyMarket,
+    // A limit order has no price.
+    MissingPrice,
+    // A market order has a price.
+    UnexpectedPrice,
+    // A limit order is sized in quote currency.
+    QuoteSizedLimit,
+    // An amount or price is zero or negative.
+    NonPositive(&'static str),
+    // The base size breaks base_min_size or base_max_size.
+    BaseSize(SizeLimit),
+    // The notional is below quote_min_size.
+    QuoteBelowMinimum { notional: Decimal, min: Decimal },
+    // The notional is above quote_max_size.
+    QuoteAboveMaximum { notional: Decimal, max: Decimal },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TradingDisabled => write!(f, "trading is disabled for this product"),
+            Violation::NotOnline(status) => write!(f, "product status is {}, not online", status),
+            Violation::CancelOnly => write!(f, "product is in cancel-only mode"),
+            Violation::LimitOnly => write!(f, "product only accepts limit orders"),
+            Violation::PostOnly => write!(f, "product only accepts post-only orders"),
+            Violation::PostOnlyMarket => write!(f, "market orders cannot be post-only"),
+            Violation::MissingPrice => write!(f, "limit orders need a limit price"),
+            Violation::UnexpectedPrice => write!(f, "market orders do not take a limit price"),
+            Violation::QuoteSizedLimit => write!(f, "limit orders must be sized in base currency"),
+            Violation::NonPositive(field) => write!(f, "{} must be positive after rounding", field),
+            Violation::BaseSize(limit) => write!(f, "base {}", limit),
+            Violation::QuoteBelowMinimum { notional, min } => {
+                write!(f, "notional {} is below the minimum of {}", notional, min)
+            }
+            Violation::QuoteAboveMaximum { notional, max } => {
+                write!(f, "notional {} is above the maximum of {}", notional, max)
+            }
+        }
+    }
+}
+
+// Error raised when the product's trading rules cannot be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    // A product field the order is checked against is missing or not a number.
+    InvalidProductField { field: &'static str, value: String },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::InvalidProductField { field, value } => {
+                write!(f, "product field {} is not a number: {:?}", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// The order rounded to the product's increments, with every rule it breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatedOrder {
+    pub order: OrderRequest,        // Order with amount and price rounded.
+    pub notional: Option<Decimal>,  // Quote notional, when it can be known without the book.
+    pub violations: Vec<Violation>, // Rules the rounded order breaks.
+}
+
+impl ValidatedOrder {
+    // Returns true when the rounded order breaks no rule.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+// Rounds an order to the product's increments and checks it against the product's trading rules.
+//
+// Sizes and funds are rounded down. Limit prices are rounded away from the market (down for buys,
+// up for sells), so rounding never makes an order more aggressive. Increments must be numbers,
+// while a size limit left empty or zero does not apply.
+pub fn validate_order(
+    product: &Product,
+    request: &OrderRequest,
+) -> Result<ValidatedOrder, ValidationError> {
+    let mut order = request.clone();
+    let mut violations = Vec::new();
+
+    // Product state.
+    if product.trading_disabled || product.is_disabled {
+        violations.push(Violation::TradingDisabled);
+    }
+    if !product.status.is_empty() && !product.status.eq_ignore_ascii_case("online") {
+        violations.push(Violation::NotOnline(product.status.clone()));
+    }
+    if product.cancel_only {
+        violations.push(Violation::CancelOnly);
+    }
+
+    // Order type rules.
+    match order.order_type {
+        OrderType::Market => {
+            if product.limit_only {
+                violations.push(Violation::LimitOnly);
+            }
+            if order.post_only {
+                violations.push(Violation::PostOnlyMarket);
+            }
+            if order.limit_price.is_some() {
+                violations.push(Violation::UnexpectedPrice);
+            }
+        }
+        OrderType::Limit => {
+            if product.post_only && !order.post_only {
+                violations.push(Violation::PostOnly);
+            }
+            if order.limit_price.is_none() {
+                violations.push(Violation::MissingPrice);
+            }
+            if let OrderAmount::Quote(_) = order.amount {
+                violations.push(Violation::QuoteSizedLimit);
+            }
+        }
+    }
+
+    // Rounding.
+    let price_increment = product_field("price_increment", &product.price_increment)?;
+    order.limit_price = order.limit_price.map(|price| match order.side {
+        OrderSide::Buy => round_to_increment(price, price_increment),
+        OrderSide::Sell => round_up_to_increment(price, price_increment),
+    });
+    order.amount = match order.amount {
+        OrderAmount::Base(size) => OrderAmount::Base(round_to_increment(
+            size,
+            product_field("base_increment", &product.base_increment)?,
+        )),
+        OrderAmount::Quote(funds) => OrderAmount::Quote(round_to_increment(
+            funds,
+            product_field("quote_increment", &product.quote_increment)?,
+        )),
+    };
+
+    // Sizes and notional.
+    if let Some(price) = order.limit_price {
+        if price <= Decimal::ZERO {
+            violations.push(Violation::NonPositive("limit price"));
+        }
+    }
+    let notional = match order.amount {
+        OrderAmount::Base(size) => {
+            if size <= Decimal::ZERO {
+                violations.push(Violation::NonPositive("size"));
+            }
+            let min = product_limit("base_min_size", &product.base_min_size)?;
+            let max = product_limit("base_max_size", &product.base_max_size)?;
+            if let Some(limit) = size_limit(size, min.unwrap_or_default(), max) {
+                violations.push(Violation::BaseSize(limit));
+            }
+            order.limit_price.map(|price| (size * price).normalize())
+        }
+        OrderAmount::Quote(funds) => {
+            if funds <= Decimal::ZERO {
+                violations.push(Violation::NonPositive("funds"));
+            }
+            Some(funds)
+        }
+    };
+    if let Some(notional) = notional {
+        let min = product_limit("quote_min_size", &product.quote_min_size)?;
+        let max = product_limit("quote_max_size", &product.quote_max_size)?;
+        match (min, max) {
+            (Some(min), _) if notional < min => {
+                violations.push(Violation::QuoteBelowMinimum { notional, min })
+            }
+            (_, Some(max)) if notional > max => {
+                violations.push(Violation::QuoteAboveMaximum { notional, max })
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ValidatedOrder {
+        order,
+        notional,
+        violations,
+    })
+}
+
+// Parses a product field the order cannot be checked without.
+fn product_field(field: &'static str, value: &str) -> Result<Decimal, ValidationError> {
+    parse_decimal(value).map_err(|_| ValidationError::InvalidProductField {
+        field,
+        value: value.to_string(),
+    })
+}
+
+// Parses a product size limit, None when it is left empty or zero.
+fn product_limit(field: &'static str, value: &str) -> Result<Option<Decimal>, ValidationError> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(product_field(field, value)?).filter(|limit| !limit.is_zero()))
+}
+
+// Rounds a value up to a multiple of the increment, leaving it unchanged when the increment is zero.
+pub fn round_up_to_increment(value: Decimal, increment: Decimal) -> Decimal {
+    if increment <= Decimal::ZERO {
+        return value;
+    }
+    ((value / increment).ceil() * increment).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn limit(side: OrderSide, size: &str, price: &str) -> OrderRequest {
+        OrderRequest {
+            side,
+            order_type: OrderType::Limit,
+            amount: OrderAmount::Base(dec(size)),
+            limit_price: Some(dec(price)),
+            post_only: false,
+        }
+    }
+
+    fn market(side: OrderSide, amount: OrderAmount) -> OrderRequest {
+        OrderRequest {
+            side,
+            order_type: OrderType::Market,
+            amount,
+            limit_price: None,
+            post_only: false,
+        }
+    }
+
+    fn violations(product: &Product, request: &OrderRequest) -> Vec<Violation> {
+        validate_order(product, request).unwrap().violations
+    }
+
+    #[test]
+    fn limit_prices_round_away_from_the_market() {
+        let mut product = Product::for_test("ETH-USD", "2000");
+        product.base_increment = "0.001".to_string();
+
+        let buy = validate_order(&product, &limit(OrderSide::Buy, "1.23456", "1999.999")).unwrap();
+        assert_eq!(buy.order.limit_price, Some(dec("1999.99")));
+        assert_eq!(buy.order.amount, OrderAmount::Base(dec("1.234")));
+
+        let sell =
+            validate_order(&product, &limit(OrderSide::Sell, "1.23456", "2000.001")).unwrap();
+        assert_eq!(sell.order.limit_price, Some(dec("2000.01")));
+        assert_eq!(sell.order.amount, OrderAmount::Base(dec("1.234")));
+        assert_eq!(sell.notional, Some(dec("2468.01234")));
+        assert!(sell.is_valid());
+    }
+
+    #[test]
+    fn round_up_to_increment_leaves_multiples_alone() {
+        assert_eq!(round_up_to_increment(dec("10.00"), dec("0.05")), dec("10"));
+        assert_eq!(
+            round_up_to_increment(dec("10.01"), dec("0.05")),
+            dec("10.05")
+        );
+        assert_eq!(
+            round_up_to_increment(dec("10.01"), Decimal::ZERO),
+            dec("10.01")
+        );
+    }
+
+    #[test]
+    fn product_state_violations() {
+        let mut product = Product::for_test("ETH-USD", "2000");
+        product.trading_disabled = true;
+        product.status = "delisted".to_string();
+        product.cancel_only = true;
+        assert_eq!(
+            violations(&product, &limit(OrderSide::Buy, "1", "2000")),
+            vec![
+                Violation::TradingDisabled,
+                Violation::NotOnline("delisted".to_string()),
+                Violation::CancelOnly,
+            ]
+        );
+    }
+
+    #[test]
+    fn market_order_violations() {
+        let mut product = Product::for_test("ETH-USD", "2000");
+        product.limit_only = true;
+        let mut request = market(OrderSide::Buy, OrderAmount::Base(dec("1")));
+        request.post_only = true;
+        request.limit_price = Some(dec("2000"));
+        assert_eq!(
+            violations(&product, &request),
+            vec![
+                Violation::LimitOnly,
+                Violation::PostOnlyMarket,
+                Violation::UnexpectedPrice,
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_order_violations() {
+        let mut product = Product::for_test("ETH-USD", "2000");
+        product.post_only = true;
+        let mut request = limit(OrderSide::Buy, "1", "2000");
+        request.limit_price = None;
+        request.amount = OrderAmount::Quote(dec("100"));
+        assert_eq!(
+            violations(&product, &request),
+            vec![
+                Violation::PostOnly,
+                Violation::MissingPrice,
+                Violation::QuoteSizedLimit,
+            ]
+        );
+    }
+
+    #[test]
+    fn amounts_that_round_to_zero_are_not_positive() {
+        let product = Product::for_test("ETH-USD", "2000");
+        assert_eq!(
+            violations(&product, &limit(OrderSide::Buy, "0.000000001", "0.001")),
+            vec![
+                Violation::NonPositive("limit price"),
+                Violation::NonPositive("size"),
+            ]
+        );
+        assert_eq!(
+            violations(
+                &product,
+                &market(OrderSide::Buy, OrderAmount::Quote(dec("0.001")))
+            ),
+            vec![Violation::NonPositive("funds")]
+        );
+    }
+
+    #[test]
+    fn base_size_limits() {
+        let mut product = Product::for_test("ETH-USD", "2000");
+        product.base_min_size = "0.01".to_string();
+        product.base_max_size = "100".to_string();
+        assert_eq!(
+            violations(&product, &limit(OrderSide::Buy, "0.005", "2000")),
+            vec![Violation::BaseSize(SizeLimit::BelowMinimum {
+                size: dec("0.005"),
+                min: dec("0.01"),
+            })]
+        );
+        assert_eq!(
+            violations(&product, &limit(OrderSide::Sell, "150", "2000")),
+            vec![Violation::BaseSize(SizeLimit::AboveMaximum {
+                size: dec("150"),
+                max: dec("100"),
+            })]
+        );
+    }
+
+    #[test]
+    fn notional_limits() {
+        let mut product = Product::for_test("ETH-USD", "2000");
+        product.quote_min_size = "10".to_string();
+        product.quote_max_size = "1000000".to_string();
+        assert_eq!(
+            violations(&product, &limit(OrderSide::Buy, "0.004", "2000")),
+            vec![Violation::QuoteBelowMinimum {
+                notional: dec("8"),
+                min: dec("10"),
+            }]
+        );
+        assert_eq!(
+            violations(
+                &product,
+                &market(OrderSide::Sell, OrderAmount::Quote(dec("2000000")))
+            ),
+            vec![Violation::QuoteAboveMaximum {
+                notional: dec("2000000"),
+                max: dec("1000000"),
+            }]
+        );
+        // A market order sized in base has no notional without the book.
+        let validated = validate_order(
+            &product,
+            &market(OrderSide::Buy, OrderAmount::Base(dec("0.001"))),
+        )
+        .unwrap();
+        assert_eq!(validated.notional, None);
+        assert!(validated.is_valid());
+    }
+
+    #[test]
+    fn empty_limits_do_not_apply_but_bad_increments_are_named() {
+        let mut product = Product::for_test("ETH-USD", "2000");
+        product.quote_max_size = String::new();
+        product.base_max_size = String::new();
+        assert!(
+            validate_order(&product, &limit(OrderSide::Buy, "1", "2000"))
+                .unwrap()
+                .is_valid()
+        );
+
+        product.price_increment = String::new();
+        assert_eq!(
+            validate_order(&product, &limit(OrderSide::Buy, "1", "2000")),
+            Err(ValidationError::InvalidProductField {
+                field: "price_increment",
+                value: String::new(),
+            })
+        );
+        product.price_increment = "0.01".to_string();
+        product.quote_min_size = "n/a".to_string();
+        assert_eq!(
+            validate_order(&product, &limit(OrderSide::Buy, "1", "2000"))
+                .unwrap_err()
+                .to_string(),
+            "product field quote_min_size is not a number: \"n/a\""
+        );
+    }
+}
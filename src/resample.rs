@@ -0,0 +1,523 @@
+use crate::indicators::{parse_candles, IndicatorColumn, Ohlcv}; // Importing the numeric candles.
+use crate::models::Candle; // Importing the Candle model.
+use chrono::{DateTime, Datelike, FixedOffset, LocalResult, NaiveDate, Offset, TimeZone, Utc}; // Importing chrono for calendar buckets.
+use chrono_tz::Tz; // Importing IANA time zones for daylight saving aware buckets.
+use std::fmt; // Importing fmt to describe timeframes and errors.
+
+// Seconds in a day.
+const DAY: i64 = 86_400;
+// Days between the Unix epoch (a Thursday) and the Monday that starts its week.
+const EPOCH_WEEKDAY_OFFSET: i64 = 3;
+
+// A timeframe to resample candles into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timeframe {
+    // A fixed length of local time in seconds, aligned to local midnight when it divides a day.
+    Fixed(i64),
+    // Calendar weeks starting on Monday.
+    Weeks(u32),
+    // Calendar months starting on the first.
+    Months(u32),
+}
+
+// What to do with buckets that no source candle falls into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingCandles {
+    Skip,     // Leave the bucket out.
+    FillFlat, // Emit a flat candle at the previous close with zero volume.
+}
+
+// Time zone the buckets are aligned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Fixed(FixedOffset), // A constant offset from UTC.
+    Named(Tz),          // An IANA zone, whose offset follows its daylight saving rules.
+}
+
+impl Zone {
+    // Returns the UTC zone.
+    pub fn utc() -> Zone {
+        Zone::Fixed(FixedOffset::east_opt(0).unwrap())
+    }
+
+    // Returns the offset from UTC in seconds in effect at a timestamp.
+    pub fn offset_at(&self, timestamp: i64) -> i64 {
+        match self {
+            Zone::Fixed(offset) => offset.local_minus_utc() as i64,
+            Zone::Named(tz) => DateTime::from_timestamp(timestamp, 0)
+                .map(|time| tz.offset_from_utc_datetime(&time.naive_utc()).fix())
+                .map_or(0, |offset| offset.local_minus_utc() as i64),
+        }
+    }
+
+    // Returns the local wall-clock time of a timestamp, in seconds from the local epoch.
+    fn local_time(&self, timestamp: i64) -> i64 {
+        timestamp + self.offset_at(timestamp)
+    }
+
+    // Returns the timestamp of a local wall-clock time. A time repeated when clocks go back is
+    // the earlier of its two instants, and a time skipped when clocks go forward is read with the
+    // offset before the change, so a boundary in the gap lands when the change happens.
+    fn timestamp_of(&self, local: i64) -> i64 {
+        let Zone::Named(tz) = self else {
+            return local - self.offset_at(local);
+        };
+        let Some(naive) = DateTime::from_timestamp(local, 0).map(|time| time.naive_utc()) else {
+            return local;
+        };
+        match tz.from_local_datetime(&naive) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.timestamp(),
+            // Offsets change at most twice a year, so a day earlier is before the change.
+            LocalResult::None => local - self.offset_at(local - DAY),
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Fixed(offset) => write!(f, "{}", offset),
+            Zone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+// How candles are resampled.
+#[derive(Debug, Clone, Copy)]
+pub struct Resampling {
+    pub timeframe: Timeframe,    // Target timeframe.
+    pub zone: Zone,              // Time zone the buckets are aligned to.
+    pub missing: MissingCandles, // What to do with buckets without candles.
+}
+
+// Why candles cannot be resampled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResampleError {
+    // The timeframe is not a whole number of source candles.
+    Misaligned { timeframe: Timeframe, source: i64 },
+    // The time zone would split source candles across buckets.
+    ZoneMisaligned { zone: Zone, source: i64 },
+}
+
+impl fmt::Display for ResampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResampleError::Misaligned { timeframe, source } => write!(
+                f,
+                "timeframe {} is not a multiple of the {}s source candles",
+                timeframe, source
+            ),
+            ResampleError::ZoneMisaligned { zone, source } => write!(
+                f,
+                "time zone {} puts bucket boundaries between the {}s source candles",
+                zone, source
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResampleError {}
+
+impl Timeframe {
+    // Parses a timeframe such as 4h, 12h, 3d, 1w or 1M (m is minutes, M is months).
+    pub fn parse(text: &str) -> Option<Timeframe> {
+        let text = text.trim();
+        let unit = text.chars().last()?;
+        let count: i64 = text[..text.len() - unit.len_utf8()].parse().ok()?;
+        if count <= 0 {
+            return None;
+        }
+        match unit {
+            's' => Some(Timeframe::Fixed(count)),
+            'm' => Some(Timeframe::Fixed(count * 60)),
+            'h' => Some(Timeframe::Fixed(count * 3600)),
+            'd' => Some(Timeframe::Fixed(count * DAY)),
+            'w' => Some(Timeframe::Weeks(u32::try_from(count).ok()?)),
+            'M' => Some(Timeframe::Months(u32::try_from(count).ok()?)),
+            _ => None,
+        }
+    }
+
+    // Returns the start of the bucket holding a timestamp, with buckets aligned in the given zone.
+    //
+    // Boundaries are computed in local time for each date, so with a named zone a daily bucket
+    // lasts 23 or 25 hours on the days clocks change.
+    pub fn bucket_start(&self, timestamp: i64, zone: Zone) -> i64 {
+        zone.timestamp_of(self.local_bucket_start(zone.local_time(timestamp)))
+    }
+
+    // Returns the start of the bucket after the one starting at `start`.
+    pub fn next_bucket(&self, start: i64, zone: Zone) -> i64 {
+        let local = self.local_bucket_start(zone.local_time(start));
+        let next = match *self {
+            Timeframe::Fixed(length) => local + length,
+            Timeframe::Weeks(count) => local + 7 * count as i64 * DAY,
+            Timeframe::Months(count) => {
+                let date = local_date(local);
+                let month = date.year() as i64 * 12 + date.month0() as i64;
+                month_start(month + count as i64)
+            }
+        };
+        zone.timestamp_of(next)
+    }
+
+    // Returns the local start of the bucket holding a local time.
+    fn local_bucket_start(&self, local: i64) -> i64 {
+        match *self {
+            Timeframe::Fixed(length) => local.div_euclid(length) * length,
+            Timeframe::Weeks(count) => {
+                let length = 7 * count as i64;
+                let day = local.div_euclid(DAY) + EPOCH_WEEKDAY_OFFSET;
+                (day.div_euclid(length) * length - EPOCH_WEEKDAY_OFFSET) * DAY
+            }
+            Timeframe::Months(count) => {
+                let date = local_date(local);
+                let month = date.year() as i64 * 12 + date.month0() as i64;
+                month_start(month.div_euclid(count as i64) * count as i64)
+            }
+        }
+    }
+
+    // Checks that every bucket holds a whole number of source candles.
+    //
+    // Named zones are checked bucket by bucket while resampling, as their offset changes.
+    fn check_source(&self, source: i64, zone: Zone) -> Result<(), ResampleError> {
+        let length = match *self {
+            Timeframe::Fixed(length) => length,
+            Timeframe::Weeks(_) | Timeframe::Months(_) => DAY,
+        };
+        if source <= 0 || length % source != 0 {
+            return Err(ResampleError::Misaligned {
+                timeframe: *self,
+                source,
+            });
+        }
+        if let Zone::Fixed(offset) = zone {
+            if offset.local_minus_utc() as i64 % source != 0 {
+                return Err(ResampleError::ZoneMisaligned { zone, source });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Timeframe::Fixed(length) if length % DAY == 0 => write!(f, "{}d", length / DAY),
+            Timeframe::Fixed(length) if length % 3600 == 0 => write!(f, "{}h", length / 3600),
+            Timeframe::Fixed(length) if length % 60 == 0 => write!(f, "{}m", length / 60),
+            Timeframe::Fixed(length) => write!(f, "{}s", length),
+            Timeframe::Weeks(count) => write!(f, "{}w", count),
+            Timeframe::Months(count) => write!(f, "{}M", count),
+        }
+    }
+}
+
+// A resampled candle with the number of source candles it was built from.
+#[derive(Debug, Clone)]
+pub struct ResampledCandle {
+    pub candle: Candle,          // Aggregated candle, in the API's string format.
+    pub source_candles: usize,   // Source candles that fell into the bucket.
+    pub expected_candles: usize, // Source candles the bucket would hold without gaps.
+}
+
+impl ResampledCandle {
+    // Returns true when no source candle is missing from the bucket.
+    pub fn is_complete(&self) -> bool {
+        self.source_candles >= self.expected_candles
+    }
+}
+
+// Aggregates candles of `source` seconds into the resampling's timeframe, with buckets aligned
+// in its time zone.
+//
+// Each bucket opens at its first source candle's open, closes at its last close, and takes the
+// highest high, lowest low and summed volume. The API leaves out candles without trades, so
+// buckets also report how many source candles they hold against how many they could hold.
+// Buckets with no source candle at all are skipped or filled as asked. The result is oldest first.
+pub fn resample(
+    candles: &[Candle],
+    source: i64,
+    resampling: &Resampling,
+) -> Result<Vec<ResampledCandle>, ResampleError> {
+    let Resampling {
+        timeframe,
+        zone,
+        missing,
+    } = *resampling;
+    timeframe.check_source(source, zone)?;
+    let mut bars = parse_candles(candles);
+    bars.dedup_by_key(|bar| bar.start);
+
+    let mut resampled: Vec<ResampledCandle> = Vec::new();
+    let mut bars = bars.into_iter().peekable();
+    let Some(first) = bars.peek() else {
+        return Ok(resampled);
+    };
+    let mut start = timeframe.bucket_start(first.start, zone);
+    let mut previous_close: Option<f64> = None;
+
+    while bars.peek().is_some() {
+        let end = timeframe.next_bucket(start, zone);
+        if start.rem_euclid(source) != 0 || end.rem_euclid(source) != 0 {
+            return Err(ResampleError::ZoneMisaligned { zone, source });
+        }
+        let mut bucket: Option<Ohlcv> = None;
+        let mut count = 0;
+        while let Some(bar) = bars.next_if(|bar| bar.start < end) {
+            count += 1;
+            bucket = Some(match bucket {
+                None => Ohlcv { start, ..bar },
+                Some(open) => Ohlcv {
+                    high: open.high.max(bar.high),
+                    low: open.low.min(bar.low),
+                    close: bar.close,
+                    volume: open.volume + bar.volume,
+                    ..open
+                },
+            });
+        }
+
+        let bucket = match (bucket, missing, previous_close) {
+            (Some(bucket), _, _) => Some(bucket),
+            (None, MissingCandles::FillFlat, Some(close)) => Some(Ohlcv {
+                start,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0.0,
+            }),
+            (None, _, _) => None,
+        };
+        if let Some(bucket) = bucket {
+            previous_close = Some(bucket.close);
+            resampled.push(ResampledCandle {
+                candle: to_candle(&bucket),
+                source_candles: count,
+                expected_candles: ((end - start) / source) as usize,
+            });
+        }
+        start = end;
+    }
+    Ok(resampled)
+}
+
+// Builds extra table and export columns with the source and expected candle counts.
+pub fn coverage_columns(resampled: &[ResampledCandle]) -> Vec<IndicatorColumn> {
+    vec![
+        IndicatorColumn {
+            key: "source_candles".to_string(),
+            label: "Source".to_string(),
+            values: resampled
+                .iter()
+                .map(|r| Some(r.source_candles as f64))
+                .collect(),
+        },
+        IndicatorColumn {
+            key: "expected_candles".to_string(),
+            label: "Expected".to_string(),
+            values: resampled
+                .iter()
+                .map(|r| Some(r.expected_candles as f64))
+                .collect(),
+        },
+    ]
+}
+
+// Parses a time zone: an offset such as UTC, Z, +09:00 or -0500, or an IANA name such as
+// Europe/Paris.
+pub fn parse_zone(text: &str) -> Option<Zone> {
+    parse_offset(text)
+        .map(Zone::Fixed)
+        .or_else(|| text.trim().parse::<Tz>().ok().map(Zone::Named))
+}
+
+// Parses a time-zone offset such as UTC, Z, +09:00 or -0500.
+pub fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("utc") || text.eq_ignore_ascii_case("z") {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match text.chars().next()? {
+        '+' => (1, &text[1..]),
+        '-' => (-1, &text[1..]),
+        _ => return None,
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+// Converts a numeric candle back to the API's string format.
+fn to_candle(bar: &Ohlcv) -> Candle {
+    Candle {
+        start: bar.start.to_string(),
+        low: bar.low.to_string(),
+        high: bar.high.to_string(),
+        open: bar.open.to_string(),
+        close: bar.close.to_string(),
+        volume: bar.volume.to_string(),
+    }
+}
+
+// Returns the calendar date of a local timestamp.
+fn local_date(local: i64) -> NaiveDate {
+    Utc.timestamp_opt(local.div_euclid(DAY) * DAY, 0)
+        .single()
+        .map(|time| time.date_naive())
+        .unwrap_or_default()
+}
+
+// Returns the local timestamp of midnight on the first day of a month counted from year 0.
+fn month_start(month: i64) -> i64 {
+    NaiveDate::from_ymd_opt(
+        month.div_euclid(12) as i32,
+        month.rem_euclid(12) as u32 + 1,
+        1,
+    )
+    .and_then(|date| date.and_hms_opt(0, 0, 0))
+    .map(|time| time.and_utc().timestamp())
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hourly candles from `from` (UTC), each opening at its index and closing one higher.
+    fn hourly(from: (i32, u32, u32, u32), hours: i64) -> Vec<Candle> {
+        let (year, month, day, hour) = from;
+        let start = Utc
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .timestamp();
+        (0..hours)
+            .map(|i| {
+                to_candle(&Ohlcv {
+                    start: start + i * 3600,
+                    open: i as f64,
+                    high: i as f64 + 1.0,
+                    low: i as f64,
+                    close: i as f64 + 1.0,
+                    volume: 1.0,
+                })
+            })
+            .collect()
+    }
+
+    // Returns the UTC start and the source and expected counts of each bucket.
+    fn buckets(candles: &[Candle], timeframe: &str, zone: &str) -> Vec<(String, usize, usize)> {
+        let resampling = Resampling {
+            timeframe: Timeframe::parse(timeframe).unwrap(),
+            zone: parse_zone(zone).unwrap(),
+            missing: MissingCandles::Skip,
+        };
+        resample(candles, 3600, &resampling)
+            .unwrap()
+            .into_iter()
+            .map(|bucket| {
+                let start = DateTime::from_timestamp(bucket.candle.start.parse().unwrap(), 0);
+                (
+                    start.unwrap().format("%Y-%m-%dT%H:%MZ").to_string(),
+                    bucket.source_candles,
+                    bucket.expected_candles,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn daily_buckets_follow_daylight_saving_changes() {
+        // Paris moves from +01:00 to +02:00 on 2024-03-31, which lasts 23 hours.
+        let spring = hourly((2024, 3, 29, 23), 71);
+        assert_eq!(
+            buckets(&spring, "1d", "Europe/Paris"),
+            [
+                ("2024-03-29T23:00Z".to_string(), 24, 24),
+                ("2024-03-30T23:00Z".to_string(), 23, 23),
+                ("2024-03-31T22:00Z".to_string(), 24, 24),
+            ]
+        );
+
+        // And back to +01:00 on 2024-10-27, which lasts 25 hours.
+        let autumn = hourly((2024, 10, 25, 22), 73);
+        assert_eq!(
+            buckets(&autumn, "1d", "Europe/Paris"),
+            [
+                ("2024-10-25T22:00Z".to_string(), 24, 24),
+                ("2024-10-26T22:00Z".to_string(), 25, 25),
+                ("2024-10-27T23:00Z".to_string(), 24, 24),
+            ]
+        );
+    }
+
+    #[test]
+    fn hourly_buckets_skip_the_missing_local_hour() {
+        // In New York 02:00 does not exist on 2024-03-10; 01:00 EST is 06:00Z and 03:00 EDT 07:00Z.
+        let candles = hourly((2024, 3, 10, 5), 4);
+        let starts: Vec<String> = buckets(&candles, "1h", "America/New_York")
+            .into_iter()
+            .map(|(start, _, expected)| {
+                assert_eq!(expected, 1);
+                start
+            })
+            .collect();
+        assert_eq!(
+            starts,
+            [
+                "2024-03-10T05:00Z",
+                "2024-03-10T06:00Z",
+                "2024-03-10T07:00Z",
+                "2024-03-10T08:00Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_offsets_keep_every_day_the_same_length() {
+        let candles = hourly((2024, 3, 29, 15), 48);
+        assert_eq!(
+            buckets(&candles, "1d", "+09:00"),
+            [
+                ("2024-03-29T15:00Z".to_string(), 24, 24),
+                ("2024-03-30T15:00Z".to_string(), 24, 24),
+            ]
+        );
+    }
+
+    #[test]
+    fn zones_that_split_source_candles_are_rejected() {
+        let resampling = Resampling {
+            timeframe: Timeframe::Fixed(DAY),
+            zone: parse_zone("Asia/Kolkata").unwrap(),
+            missing: MissingCandles::Skip,
+        };
+        assert!(matches!(
+            resample(&hourly((2024, 1, 1, 0), 24), 3600, &resampling),
+            Err(ResampleError::ZoneMisaligned { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_offsets_and_iana_names() {
+        assert_eq!(parse_zone("utc"), Some(Zone::utc()));
+        assert_eq!(
+            parse_zone("+09:00"),
+            Some(Zone::Fixed(FixedOffset::east_opt(9 * 3600).unwrap()))
+        );
+        assert_eq!(
+            parse_zone("Europe/Paris"),
+            Some(Zone::Named(chrono_tz::Europe::Paris))
+        );
+        assert_eq!(parse_zone("Mars/Olympus"), None);
+    }
+}
@@ -0,0 +1,648 @@
+use crate::models::Product; // Importing the Product model.
+use serde::{Deserialize, Serialize}; // Importing serde traits to save screens in the config.
+use std::cmp::Ordering; // Importing Ordering for sorting products.
+use std::fmt; // Importing fmt to describe errors.
+
+// Short names accepted for product fields in filters and sort keys.
+const FIELD_ALIASES: [(&str, &str); 9] = [
+    ("id", "product_id"),
+    ("base", "base_currency_id"),
+    ("quote", "quote_currency_id"),
+    ("type", "product_type"),
+    ("venue", "product_venue"),
+    ("change_24h", "price_percentage_change_24h"),
+    ("volume_change_24h", "volume_percentage_change_24h"),
+    ("quote_volume_24h", "approximate_quote_24h_volume"),
+    ("mid", "mid_market_price"),
+];
+
+// A screen as typed and saved: a filter expression, sort keys and a row limit.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Screen {
+    #[serde(default)]
+    pub filter: String, // Filter expression, empty to keep every product.
+    #[serde(default)]
+    pub sort: Vec<String>, // Sort keys such as volume_24h:desc, applied in order.
+    #[serde(default)]
+    pub limit: Option<usize>, // Largest number of products shown.
+}
+
+impl Screen {
+    // Parses the filter and sort keys, ready to apply to products.
+    pub fn compile(&self) -> Result<CompiledScreen, ScreenError> {
+        Ok(CompiledScreen {
+            filter: Filter::parse(&self.filter)?,
+            sort: self
+                .sort
+                .iter()
+                .map(|key| SortKey::parse(key))
+                .collect::<Result<_, _>>()?,
+            limit: self.limit,
+        })
+    }
+}
+
+// Why a screen cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScreenError {
+    // The filter expression is malformed at a character position.
+    Syntax { position: usize, message: String },
+    // A filter or sort key names a field products do not have.
+    UnknownField(String),
+    // A sort key has an order other than asc or desc.
+    InvalidSort(String),
+}
+
+impl fmt::Display for ScreenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScreenError::Syntax { position, message } => {
+                write!(f, "{} at position {}", message, position + 1)
+            }
+            ScreenError::UnknownField(name) => write!(f, "unknown product field {}", name),
+            ScreenError::InvalidSort(key) => {
+                write!(
+                    f,
+                    "invalid sort key {}, expected FIELD or FIELD:asc|desc",
+                    key
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScreenError {}
+
+// A parsed screen.
+#[derive(Debug, Clone)]
+pub struct CompiledScreen {
+    pub filter: Filter,       // Products kept.
+    pub sort: Vec<SortKey>,   // Order of the kept products.
+    pub limit: Option<usize>, // Largest number of products returned.
+}
+
+impl CompiledScreen {
+    // Returns the products matching the filter, sorted and limited.
+    pub fn apply<'a>(&self, products: &'a [Product]) -> Vec<&'a Product> {
+        let mut rows: Vec<(&Product, Vec<(&'static str, String)>)> = products
+            .iter()
+            .map(|product| (product, product.fields()))
+            .filter(|(_, fields)| self.filter.matches(fields))
+            .collect();
+        rows.sort_by(|(_, a), (_, b)| {
+            self.sort
+                .iter()
+                .map(|key| key.compare(a, b))
+                .find(|order| order.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        rows.into_iter()
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|(product, _)| product)
+            .collect()
+    }
+
+    // Returns the fields the filter and sort keys read, in first use order.
+    pub fn fields(&self) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        self.filter.collect_fields(&mut fields);
+        for key in &self.sort {
+            if !fields.contains(&key.field) {
+                fields.push(key.field);
+            }
+        }
+        fields
+    }
+}
+
+// Comparison operators of a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Equal,        // = or ==
+    NotEqual,     // !=
+    Greater,      // >
+    GreaterEqual, // >=
+    Less,         // <
+    LessEqual,    // <=
+    Contains,     // ~, case-insensitive substring
+}
+
+// A filter expression over product fields.
+//
+// Comparisons such as `volume_24h > 1e6` or `quote=USD` are combined with `and`, `or`, `not`
+// and parentheses; a field on its own, e.g. `new`, matches when it is true. Values compare as
+// numbers when both sides are numeric and as case-insensitive text otherwise, and an ordering
+// against a number never matches a field that is not numeric.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    All,
+    Compare {
+        field: &'static str,
+        operator: Operator,
+        value: String,
+    },
+    Flag(&'static str),
+    Not(Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+impl Filter {
+    // Parses a filter expression, an empty one keeping every product.
+    pub fn parse(text: &str) -> Result<Filter, ScreenError> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Ok(Filter::All);
+        }
+        let mut parser = Parser {
+            tokens,
+            next: 0,
+            end: text.chars().count(),
+        };
+        let filter = parser.or()?;
+        match parser.peek() {
+            None => Ok(filter),
+            Some((position, token)) => Err(ScreenError::Syntax {
+                position: *position,
+                message: format!("unexpected {}", token),
+            }),
+        }
+    }
+
+    // Returns true when a product, given as its field pairs, matches.
+    pub fn matches(&self, fields: &[(&'static str, String)]) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Compare {
+                field,
+                operator,
+                value,
+            } => compare(field_value(fields, field), *operator, value),
+            Filter::Flag(field) => field_value(fields, field).eq_ignore_ascii_case("true"),
+            Filter::Not(filter) => !filter.matches(fields),
+            Filter::And(a, b) => a.matches(fields) && b.matches(fields),
+            Filter::Or(a, b) => a.matches(fields) || b.matches(fields),
+        }
+    }
+
+    // Appends the fields the filter reads that are not listed yet.
+    fn collect_fields(&self, fields: &mut Vec<&'static str>) {
+        match self {
+            Filter::All => {}
+            Filter::Compare { field, .. } | Filter::Flag(field) => {
+                if !fields.contains(field) {
+                    fields.push(field);
+                }
+            }
+            Filter::Not(filter) => filter.collect_fields(fields),
+            Filter::And(a, b) | Filter::Or(a, b) => {
+                a.collect_fields(fields);
+                b.collect_fields(fields);
+            }
+        }
+    }
+}
+
+// A field to sort by and its direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: &'static str, // Product field.
+    pub descending: bool,    // True for largest first.
+}
+
+impl SortKey {
+    // Parses a sort key such as volume_24h, volume_24h:desc or change_24h:asc.
+    pub fn parse(key: &str) -> Result<SortKey, ScreenError> {
+        let (name, order) = key.trim().split_once(':').unwrap_or((key.trim(), "asc"));
+        let descending = match order.to_lowercase().as_str() {
+            "asc" => false,
+            "desc" => true,
+            _ => return Err(ScreenError::InvalidSort(key.to_string())),
+        };
+        Ok(SortKey {
+            field: resolve_field(name)?,
+            descending,
+        })
+    }
+
+    // Orders two products, given as their field pairs: numbers by value before text.
+    fn compare(&self, a: &[(&'static str, String)], b: &[(&'static str, String)]) -> Ordering {
+        let (a, b) = (field_value(a, self.field), field_value(b, self.field));
+        let order = match (a.parse::<f64>().ok(), b.parse::<f64>().ok()) {
+            (Some(x), Some(y)) => x.total_cmp(&y),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => a.to_lowercase().cmp(&b.to_lowercase()),
+        };
+        if self.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+// Resolves a field name or alias to the product field.
+pub fn resolve_field(name: &str) -> Result<&'static str, ScreenError> {
+    let name = name.to_lowercase();
+    let name = FIELD_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name.as_str(), |(_, field)| field);
+    Product::FIELD_NAMES
+        .iter()
+        .find(|field| **field == name)
+        .copied()
+        .ok_or_else(|| ScreenError::UnknownField(name.to_string()))
+}
+
+// Returns a field's value from a product's field pairs.
+fn field_value<'a>(fields: &'a [(&'static str, String)], field: &str) -> &'a str {
+    fields
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map_or("", |(_, value)| value.as_str())
+}
+
+// Compares a field value with a filter value.
+fn compare(actual: &str, operator: Operator, expected: &str) -> bool {
+    if operator == Operator::Contains {
+        return actual.to_lowercase().contains(&expected.to_lowercase());
+    }
+    let order = match (actual.parse::<f64>().ok(), expected.parse::<f64>().ok()) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        // Text such as an empty volume is not ordered against numbers.
+        (None, Some(_)) if !matches!(operator, Operator::Equal | Operator::NotEqual) => {
+            return false;
+        }
+        _ => actual.to_lowercase().cmp(&expected.to_lowercase()),
+    };
+    match operator {
+        Operator::Equal => order.is_eq(),
+        Operator::NotEqual => order.is_ne(),
+        Operator::Greater => order.is_gt(),
+        Operator::GreaterEqual => order.is_ge(),
+        Operator::Less => order.is_lt(),
+        Operator::LessEqual => order.is_le(),
+        Operator::Contains => unreachable!(),
+    }
+}
+
+// Tokens of a filter expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),   // Field name, keyword or bare value.
+    Quoted(String), // Value in single or double quotes.
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Quoted(text) => write!(f, "\"{}\"", text),
+            Token::Operator(_) => write!(f, "operator"),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+        }
+    }
+}
+
+// Splits a filter expression into tokens with their character positions.
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, ScreenError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '~' => Token::Operator(Operator::Contains),
+            '=' | '!' | '<' | '>' => {
+                let equals = chars.get(i + 1) == Some(&'=');
+                let operator = match (c, equals) {
+                    ('=', _) => Operator::Equal,
+                    ('!', true) => Operator::NotEqual,
+                    ('>', true) => Operator::GreaterEqual,
+                    ('>', false) => Operator::Greater,
+                    ('<', true) => Operator::LessEqual,
+                    ('<', false) => Operator::Less,
+                    _ => {
+                        return Err(ScreenError::Syntax {
+                            position: start,
+                            message: "expected !=".to_string(),
+                        })
+                    }
+                };
+                if equals {
+                    i += 1;
+                }
+                Token::Operator(operator)
+            }
+            '"' | '\'' => {
+                let close = chars[i + 1..].iter().position(|d| *d == c).ok_or_else(|| {
+                    ScreenError::Syntax {
+                        position: start,
+                        message: "unterminated quote".to_string(),
+                    }
+                })?;
+                let quoted: String = chars[i + 1..i + 1 + close].iter().collect();
+                i += close + 1;
+                Token::Quoted(quoted)
+            }
+            _ => {
+                let length = chars[i..]
+                    .iter()
+                    .take_while(|d| d.is_alphanumeric() || matches!(d, '_' | '.' | '-' | '+' | '/'))
+                    .count();
+                if length == 0 {
+                    return Err(ScreenError::Syntax {
+                        position: start,
+                        message: format!("unexpected character '{}'", c),
+                    });
+                }
+                i += length - 1;
+                Token::Word(chars[start..start + length].iter().collect())
+            }
+        };
+        tokens.push((start, token));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+// Recursive descent parser over the tokens, `or` binding loosest and `not` tightest.
+struct Parser {
+    tokens: Vec<(usize, Token)>, // Tokens with their positions.
+    next: usize,                 // Index of the next token.
+    end: usize,                  // Position reported at the end of the text.
+}
+
+impl Parser {
+    // Returns the next token without consuming it.
+    fn peek(&self) -> Option<&(usize, Token)> {
+        self.tokens.get(self.next)
+    }
+
+    // Consumes the next token if it is the given keyword.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some((_, Token::Word(word))) if word.eq_ignore_ascii_case(keyword) => {
+                self.next += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Builds a syntax error at the next token, or at the end of the text.
+    fn error(&self, message: &str) -> ScreenError {
+        ScreenError::Syntax {
+            position: self.peek().map_or(self.end, |(position, _)| *position),
+            message: message.to_string(),
+        }
+    }
+
+    // or := and ("or" and)*
+    fn or(&mut self) -> Result<Filter, ScreenError> {
+        let mut filter = self.and()?;
+        while self.keyword("or") {
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+        Ok(filter)
+    }
+
+    // and := not ("and" not)*
+    fn and(&mut self) -> Result<Filter, ScreenError> {
+        let mut filter = self.not()?;
+        while self.keyword("and") {
+            filter = Filter::And(Box::new(filter), Box::new(self.not()?));
+        }
+        Ok(filter)
+    }
+
+    // not := "not" not | "(" or ")" | comparison
+    fn not(&mut self) -> Result<Filter, ScreenError> {
+        if self.keyword("not") {
+            return Ok(Filter::Not(Box::new(self.not()?)));
+        }
+        if let Some((_, Token::Open)) = self.peek() {
+            self.next += 1;
+            let filter = self.or()?;
+            return match self.peek() {
+                Some((_, Token::Close)) => {
+                    self.next += 1;
+                    Ok(filter)
+                }
+                _ => Err(self.error("expected ')'")),
+            };
+        }
+        self.comparison()
+    }
+
+    // comparison := field (operator value)?
+    fn comparison(&mut self) -> Result<Filter, ScreenError> {
+        let field = match self.peek() {
+            Some((_, Token::Word(word))) => resolve_field(word)?,
+            _ => return Err(self.error("expected a field name")),
+        };
+        self.next += 1;
+        let Some((_, Token::Operator(operator))) = self.peek().cloned() else {
+            return Ok(Filter::Flag(field));
+        };
+        self.next += 1;
+        let value = match self.peek() {
+            Some((_, Token::Word(value))) | Some((_, Token::Quoted(value))) => value.clone(),
+            _ => return Err(self.error("expected a value")),
+        };
+        self.next += 1;
+        Ok(Filter::Compare {
+            field,
+            operator,
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(field: &'static str, operator: Operator, value: &str) -> Box<Filter> {
+        Box::new(Filter::Compare {
+            field,
+            operator,
+            value: value.to_string(),
+        })
+    }
+
+    fn syntax(text: &str) -> (usize, String) {
+        match Filter::parse(text) {
+            Err(ScreenError::Syntax { position, message }) => (position, message),
+            other => panic!("expected a syntax error for {:?}, got {:?}", text, other),
+        }
+    }
+
+    // Builds a product with a 24h change and volume.
+    fn product(product_id: &str, change: &str, volume: &str) -> Product {
+        let mut product = Product::for_test(product_id, "1");
+        product.price_percentage_change_24h = change.to_string();
+        product.volume_24h = volume.to_string();
+        product
+    }
+
+    fn ids<'a>(products: &[&'a Product]) -> Vec<&'a str> {
+        products
+            .iter()
+            .map(|product| product.product_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            Filter::parse("quote=USD or quote=EUR and volume_24h > 1e6").unwrap(),
+            Filter::Or(
+                compare("quote_currency_id", Operator::Equal, "USD"),
+                Box::new(Filter::And(
+                    compare("quote_currency_id", Operator::Equal, "EUR"),
+                    compare("volume_24h", Operator::Greater, "1e6"),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn not_binds_tightest_and_parentheses_group() {
+        assert_eq!(
+            Filter::parse("NOT new and quote != USD").unwrap(),
+            Filter::And(
+                Box::new(Filter::Not(Box::new(Filter::Flag("new")))),
+                compare("quote_currency_id", Operator::NotEqual, "USD"),
+            )
+        );
+        assert_eq!(
+            Filter::parse("not (quote=USD or quote=EUR) and new").unwrap(),
+            Filter::And(
+                Box::new(Filter::Not(Box::new(Filter::Or(
+                    compare("quote_currency_id", Operator::Equal, "USD"),
+                    compare("quote_currency_id", Operator::Equal, "EUR"),
+                )))),
+                Box::new(Filter::Flag("new")),
+            )
+        );
+    }
+
+    #[test]
+    fn values_may_be_negative_or_quoted() {
+        assert_eq!(
+            Filter::parse("change_24h < -5").unwrap(),
+            *compare("price_percentage_change_24h", Operator::Less, "-5")
+        );
+        assert_eq!(
+            Filter::parse("change_24h<=-5").unwrap(),
+            *compare("price_percentage_change_24h", Operator::LessEqual, "-5")
+        );
+        assert_eq!(
+            Filter::parse("base_name ~ \"Bitcoin Cash\"").unwrap(),
+            *compare("base_name", Operator::Contains, "Bitcoin Cash")
+        );
+        assert_eq!(
+            Filter::parse("display_name == 'and'").unwrap(),
+            *compare("display_name", Operator::Equal, "and")
+        );
+    }
+
+    #[test]
+    fn filters_match_numbers_text_and_flags() {
+        let mut new = product("SOL-USD", "-7.5", "");
+        new.new = true;
+        let products = [
+            product("BTC-USD", "-6", "1200"),
+            product("BTC-EUR", "2", "300"),
+            new,
+        ];
+        let screen = |filter: &str| {
+            let screen = Screen {
+                filter: filter.to_string(),
+                ..Screen::default()
+            };
+            ids(&screen.compile().unwrap().apply(&products))
+        };
+        assert_eq!(screen(""), ["BTC-USD", "BTC-EUR", "SOL-USD"]);
+        assert_eq!(screen("change_24h < -5"), ["BTC-USD", "SOL-USD"]);
+        assert_eq!(screen("id ~ btc and quote = usd"), ["BTC-USD"]);
+        assert_eq!(screen("new"), ["SOL-USD"]);
+        assert_eq!(screen("not new"), ["BTC-USD", "BTC-EUR"]);
+        // An empty volume is never ordered against a number, either way.
+        assert_eq!(screen("volume_24h < 500"), ["BTC-EUR"]);
+        assert_eq!(screen("volume_24h >= 500"), ["BTC-USD"]);
+    }
+
+    #[test]
+    fn screens_sort_numbers_before_text_and_limit_rows() {
+        let products = [
+            product("BTC-USD", "-6", "1200"),
+            product("BTC-EUR", "2", "300"),
+            product("SOL-USD", "-7.5", ""),
+        ];
+        let screen = Screen {
+            filter: String::new(),
+            sort: vec!["volume_24h:desc".to_string()],
+            limit: Some(2),
+        };
+        assert_eq!(
+            ids(&screen.compile().unwrap().apply(&products)),
+            ["BTC-USD", "BTC-EUR"]
+        );
+        let screen = Screen {
+            sort: vec!["quote".to_string(), "change_24h:ASC".to_string()],
+            ..Screen::default()
+        };
+        assert_eq!(
+            ids(&screen.compile().unwrap().apply(&products)),
+            ["BTC-EUR", "SOL-USD", "BTC-USD"]
+        );
+        assert_eq!(
+            SortKey::parse("price:up"),
+            Err(ScreenError::InvalidSort("price:up".to_string()))
+        );
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_offending_character() {
+        assert_eq!(syntax("quote = "), (8, "expected a value".to_string()));
+        assert_eq!(syntax("(new"), (4, "expected ')'".to_string()));
+        assert_eq!(syntax("new )"), (4, "unexpected ')'".to_string()));
+        assert_eq!(syntax("new and"), (7, "expected a field name".to_string()));
+        assert_eq!(syntax("price ! 5"), (6, "expected !=".to_string()));
+        assert_eq!(
+            syntax("base = \"BTC"),
+            (7, "unterminated quote".to_string())
+        );
+        assert_eq!(
+            syntax("new & quote=USD"),
+            (4, "unexpected character '&'".to_string())
+        );
+        // Positions count characters rather than bytes, and are shown from 1.
+        let error = Filter::parse("base_name ~ 'é' )").unwrap_err();
+        assert_eq!(error.to_string(), "unexpected ')' at position 17");
+    }
+
+    #[test]
+    fn unknown_fields_are_named() {
+        assert_eq!(
+            Filter::parse("foo > 1"),
+            Err(ScreenError::UnknownField("foo".to_string()))
+        );
+        assert_eq!(resolve_field("MID"), Ok("mid_market_price"));
+    }
+}
@@ -0,0 +1,336 @@
+use crate::models::{Product, ProductBook}; // Importing the REST models.
+use crate::order_book::{parse_decimal, parse_levels, BookError}; // Importing decimal parsing and book errors.
+use rust_decimal::prelude::ToPrimitive; // Importing conversions from Decimal to f64.
+use rust_decimal::Decimal; // Importing Decimal for exact prices and sizes.
+use std::fmt; // Importing fmt to describe order sides and limits.
+
+// Side of an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,  // Takes liquidity from the asks.
+    Sell, // Takes liquidity from the bids.
+}
+
+impl OrderSide {
+    // Parses an order side name.
+    pub fn parse(side: &str) -> Option<OrderSide> {
+        match side.to_lowercase().as_str() {
+            "buy" | "b" => Some(OrderSide::Buy),
+            "sell" | "s" => Some(OrderSide::Sell),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OrderSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderSide::Buy => write!(f, "BUY"),
+            OrderSide::Sell => write!(f, "SELL"),
+        }
+    }
+}
+
+// Amount of a market order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderAmount {
+    Base(Decimal),  // Size in the base currency.
+    Quote(Decimal), // Notional in the quote currency.
+}
+
+// A base size outside the product's limits.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SizeLimit {
+    BelowMinimum { size: Decimal, min: Decimal }, // Smaller than base_min_size.
+    AboveMaximum { size: Decimal, max: Decimal }, // Larger than base_max_size.
+}
+
+impl fmt::Display for SizeLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeLimit::BelowMinimum { size, min } => {
+                write!(f, "size {} is below the minimum of {}", size, min)
+            }
+            SizeLimit::AboveMaximum { size, max } => {
+                write!(f, "size {} is above the maximum of {}", size, max)
+            }
+        }
+    }
+}
+
+// Result of walking the book for a market order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlippageEstimate {
+    pub side: OrderSide,                // Side of the order.
+    pub base_size: Decimal,             // Base size walked, rounded down to base_increment.
+    pub filled_base: Decimal,           // Base size the book can fill.
+    pub filled_quote: Decimal,          // Quote notional of the fills.
+    pub average_price: Option<Decimal>, // Volume weighted fill price, None when nothing fills.
+    pub worst_price: Option<Decimal>,   // Price of the last level touched.
+    pub mid: Decimal,                   // Mid price before the order.
+    pub slippage_bps: Option<f64>, // Cost of the average price versus the mid, in basis points.
+    pub levels_consumed: usize,    // Number of levels touched.
+    pub unfilled_base: Decimal,    // Base size left when the book runs out.
+    pub unfilled_quote: Decimal,   // Quote notional left over, for quote orders.
+    pub size_limit: Option<SizeLimit>, // Set when the base size breaks the product's limits.
+}
+
+// Walks the book as a market order would, for a base size or quote notional.
+//
+// Sizes are rounded down to the product's base_increment and checked against base_min_size
+// and base_max_size. A quote notional is first converted into the base size it would buy or sell.
+pub fn estimate_market_order(
+    book: &ProductBook,
+    product: &Product,
+    side: OrderSide,
+    amount: OrderAmount,
+) -> Result<SlippageEstimate, BookError> {
+    let bids = parse_levels(&book.pricebook.bids)?;
+    let asks = parse_levels(&book.pricebook.asks)?;
+    let (best_bid, best_ask) = match (bids.first(), asks.first()) {
+        (Some(bid), Some(ask)) => (bid.0, ask.0),
+        _ => return Err(BookError::OneSided),
+    };
+    let mid = (best_bid + best_ask) / Decimal::TWO;
+    let levels = match side {
+        OrderSide::Buy => &asks,
+        OrderSide::Sell => &bids,
+    };
+
+    let increment = parse_decimal(&product.base_increment)?;
+    let requested = match amount {
+        OrderAmount::Base(size) => size,
+        OrderAmount::Quote(notional) => base_for_notional(levels, notional),
+    };
+    let base_size = round_to_increment(requested, increment);
+
+    let mut remaining = base_size;
+    let mut filled_quote = Decimal::ZERO;
+    let mut worst_price = None;
+    let mut levels_consumed = 0;
+    for &(price, size) in levels {
+        if remaining.is_zero() {
+            break;
+        }
+        let fill = remaining.min(size);
+        filled_quote += fill * price;
+        remaining -= fill;
+        worst_price = Some(price);
+        levels_consumed += 1;
+    }
+
+    let filled_base = base_size - remaining;
+    let average_price = (!filled_base.is_zero()).then(|| filled_quote / filled_base);
+    let slippage_bps = average_price.and_then(|average| {
+        let cost = match side {
+            OrderSide::Buy => average - mid,
+            OrderSide::Sell => mid - average,
+        };
+        (cost / mid * Decimal::from(10_000)).to_f64()
+    });
+
+    Ok(SlippageEstimate {
+        side,
+        base_size,
+        filled_base,
+        filled_quote,
+        average_price,
+        worst_price,
+        mid,
+        slippage_bps,
+        levels_consumed,
+        unfilled_base: remaining,
+        unfilled_quote: match amount {
+            OrderAmount::Base(_) => Decimal::ZERO,
+            OrderAmount::Quote(notional) => (notional - filled_quote).max(Decimal::ZERO),
+        },
+        size_limit: check_size_limits(product, base_size)?,
+    })
+}
+
+// Rounds a value down to a multiple of the increment, leaving it unchanged when the increment is zero.
+pub fn round_to_increment(value: Decimal, increment: Decimal) -> Decimal {
+    if increment <= Decimal::ZERO {
+        return value;
+    }
+    ((value / increment).floor() * increment).normalize()
+}
+
+// Checks a base size against the product's base_min_size and base_max_size.
+pub fn check_size_limits(product: &Product, size: Decimal) -> Result<Option<SizeLimit>, BookError> {
+    let min = parse_decimal(&product.base_min_size)?;
+    let max = parse_decimal(&product.base_max_size)?;
+    Ok(size_limit(size, min, (!max.is_zero()).then_some(max)))
+}
+
+// Checks a base size against a minimum and an optional maximum.
+pub fn size_limit(size: Decimal, min: Decimal, max: Option<Decimal>) -> Option<SizeLimit> {
+    match max {
+        _ if size < min => Some(SizeLimit::BelowMinimum { size, min }),
+        Some(max) if size > max => Some(SizeLimit::AboveMaximum { size, max }),
+        _ => None,
+    }
+}
+
+// Returns the base size a quote notional buys or sells when walking the levels.
+fn base_for_notional(levels: &[(Decimal, Decimal)], notional: Decimal) -> Decimal {
+    let mut remaining = notional;
+    let mut base = Decimal::ZERO;
+    for &(price, size) in levels {
+        if remaining <= Decimal::ZERO || price.is_zero() {
+            break;
+        }
+        let fill = size.min(remaining / price);
+        base += fill;
+        remaining -= fill * price;
+    }
+    base
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ProductBookEntry, ProductBookPriceBook};
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn levels(levels: &[(&str, &str)]) -> Vec<ProductBookEntry> {
+        levels
+            .iter()
+            .map(|(price, size)| ProductBookEntry {
+                price: price.to_string(),
+                size: size.to_string(),
+            })
+            .collect()
+    }
+
+    // Bids 99 x 1, 98 x 2, 97 x 5 and asks 101 x 1, 102 x 2, 103 x 1, so the mid is 100.
+    fn book() -> ProductBook {
+        ProductBook {
+            pricebook: ProductBookPriceBook {
+                product_id: "ETH-USD".to_string(),
+                bids: levels(&[("99", "1"), ("98", "2"), ("97", "5")]),
+                asks: levels(&[("101", "1"), ("102", "2"), ("103", "1")]),
+                time: String::new(),
+            },
+        }
+    }
+
+    fn estimate(side: OrderSide, amount: OrderAmount) -> SlippageEstimate {
+        let product = Product::for_test("ETH-USD", "100");
+        estimate_market_order(&book(), &product, side, amount).unwrap()
+    }
+
+    #[test]
+    fn buy_walks_the_asks() {
+        let estimate = estimate(OrderSide::Buy, OrderAmount::Base(dec("2")));
+        assert_eq!(estimate.mid, dec("100"));
+        assert_eq!(estimate.filled_base, dec("2"));
+        assert_eq!(estimate.filled_quote, dec("203"));
+        assert_eq!(estimate.average_price, Some(dec("101.5")));
+        assert_eq!(estimate.worst_price, Some(dec("102")));
+        assert_eq!(estimate.levels_consumed, 2);
+        assert_eq!(estimate.unfilled_base, Decimal::ZERO);
+        assert_eq!(estimate.slippage_bps, Some(150.0));
+    }
+
+    #[test]
+    fn sell_walks_the_bids_and_costs_are_positive() {
+        let estimate = estimate(OrderSide::Sell, OrderAmount::Base(dec("2")));
+        assert_eq!(estimate.filled_quote, dec("197"));
+        assert_eq!(estimate.average_price, Some(dec("98.5")));
+        assert_eq!(estimate.worst_price, Some(dec("98")));
+        assert_eq!(estimate.slippage_bps, Some(150.0));
+    }
+
+    #[test]
+    fn orders_larger_than_the_book_leave_a_remainder() {
+        let estimate = estimate(OrderSide::Buy, OrderAmount::Base(dec("5")));
+        assert_eq!(estimate.filled_base, dec("4"));
+        assert_eq!(estimate.unfilled_base, dec("1"));
+        assert_eq!(estimate.levels_consumed, 3);
+        assert_eq!(estimate.worst_price, Some(dec("103")));
+    }
+
+    #[test]
+    fn quote_notional_is_sized_from_the_book() {
+        let estimate = estimate(OrderSide::Buy, OrderAmount::Quote(dec("305")));
+        assert_eq!(estimate.base_size, dec("3"));
+        assert_eq!(estimate.filled_quote, dec("305"));
+        assert_eq!(estimate.unfilled_quote, Decimal::ZERO);
+
+        let estimate = estimate_market_order(
+            &book(),
+            &Product::for_test("ETH-USD", "100"),
+            OrderSide::Buy,
+            OrderAmount::Quote(dec("1000")),
+        )
+        .unwrap();
+        assert_eq!(estimate.base_size, dec("4"));
+        assert_eq!(estimate.filled_quote, dec("408"));
+        assert_eq!(estimate.unfilled_quote, dec("592"));
+    }
+
+    #[test]
+    fn sizes_round_down_to_the_base_increment() {
+        let mut product = Product::for_test("ETH-USD", "100");
+        product.base_increment = "0.1".to_string();
+        let estimate = estimate_market_order(
+            &book(),
+            &product,
+            OrderSide::Buy,
+            OrderAmount::Base(dec("1.57")),
+        )
+        .unwrap();
+        assert_eq!(estimate.base_size, dec("1.5"));
+        assert_eq!(estimate.filled_quote, dec("152"));
+        assert_eq!(estimate.size_limit, None);
+    }
+
+    #[test]
+    fn size_limits_are_reported() {
+        let mut product = Product::for_test("ETH-USD", "100");
+        product.base_min_size = "0.01".to_string();
+        product.base_max_size = "3".to_string();
+        let small = estimate_market_order(
+            &book(),
+            &product,
+            OrderSide::Sell,
+            OrderAmount::Base(dec("0.001")),
+        )
+        .unwrap();
+        assert_eq!(
+            small.size_limit,
+            Some(SizeLimit::BelowMinimum {
+                size: dec("0.001"),
+                min: dec("0.01"),
+            })
+        );
+        assert_eq!(
+            check_size_limits(&product, dec("4")).unwrap(),
+            Some(SizeLimit::AboveMaximum {
+                size: dec("4"),
+                max: dec("3"),
+            })
+        );
+    }
+
+    #[test]
+    fn one_sided_books_are_rejected() {
+        let mut book = book();
+        book.pricebook.asks.clear();
+        let product = Product::for_test("ETH-USD", "100");
+        assert_eq!(
+            estimate_market_order(
+                &book,
+                &product,
+                OrderSide::Sell,
+                OrderAmount::Base(dec("1"))
+            ),
+            Err(BookError::OneSided)
+        );
+    }
+}
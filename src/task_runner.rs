@@ -1,3 +1,4 @@
+use crate::clear_screen::clear_screen;
 use crate::handlers::{
     market_trades_handler::fetch_market_trades_handler,
     product_book_handler::fetch_product_book_handler,
@@ -119,8 +120,22 @@ where
     Ok(())
 }
 
-// Function to clear the screen
-pub fn clear_screen() {
-    print!("\x1B[2J\x1B[1;1H");
-    io::stdout().flush().unwrap();
+// Runs a task that needs no product until it finishes or Ctrl+C is pressed
+pub async fn run_until_ctrl_c<F, Fut>(task: F) -> Result<()>
+where
+    F: FnOnce(watch::Receiver<()>) -> Fut,
+    Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    let (tx, rx) = watch::channel(());
+    let mut handle = tokio::spawn(task(rx));
+
+    tokio::select! {
+        _ = signal::ctrl_c() => {
+            println!("Received Ctrl+C, stopping...");
+            drop(tx); // Dropping the sender to signal the task to stop
+            handle.await??;
+        },
+        result = &mut handle => result??,
+    }
+    Ok(())
 }
@@ -0,0 +1,102 @@
+use chrono::Utc; // Importing Utc to timestamp plain output lines.
+use crossterm::terminal; // Importing crossterm's terminal utilities for size detection.
+use std::io::{self, IsTerminal, Write}; // Importing IsTerminal to detect redirected output and Write for prompts.
+use std::sync::atomic::{AtomicBool, Ordering}; // Importing atomics for the global output mode.
+
+// Fallback dimensions used when the terminal size cannot be detected.
+const DEFAULT_WIDTH: usize = 120;
+const DEFAULT_HEIGHT: usize = 40;
+
+// ANSI colour codes used by the renderers.
+pub const GREEN: &str = "\x1B[32m";
+pub const RED: &str = "\x1B[31m";
+pub const REVERSE: &str = "\x1B[7m";
+pub const RESET: &str = "\x1B[0m";
+
+// Whether output is append-only lines without any escape sequences.
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+// Whether ANSI colours may be used.
+static COLOUR_OUTPUT: AtomicBool = AtomicBool::new(true);
+
+// Chooses the output mode: plain when requested or when stdout is not a terminal,
+// and without colours when plain or when the NO_COLOR environment variable is set.
+pub fn configure_output(plain_requested: bool) {
+    let plain = plain_requested || !io::stdout().is_terminal();
+    let no_colour = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    PLAIN_OUTPUT.store(plain, Ordering::Relaxed);
+    COLOUR_OUTPUT.store(!plain && !no_colour, Ordering::Relaxed);
+}
+
+// Returns true when handlers should append timestamped lines instead of repainting the screen.
+pub fn is_plain() -> bool {
+    PLAIN_OUTPUT.load(Ordering::Relaxed)
+}
+
+// Returns true when ANSI colours may be written.
+pub fn colour_enabled() -> bool {
+    COLOUR_OUTPUT.load(Ordering::Relaxed)
+}
+
+// Returns the current terminal size as (columns, rows), falling back to sensible defaults.
+pub fn terminal_size() -> (usize, usize) {
+    match terminal::size() {
+        Ok((cols, rows)) if cols > 0 && rows > 0 => (cols as usize, rows as usize),
+        _ => (DEFAULT_WIDTH, DEFAULT_HEIGHT),
+    }
+}
+
+// Wraps the given text in an ANSI colour code, unless colours are disabled.
+pub fn paint(text: &str, colour: &str) -> String {
+    if colour_enabled() {
+        format!("{}{}{}", colour, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+// Background colours for heatmap cells, from weakest to strongest.
+const HEAT_POSITIVE: [u8; 5] = [22, 28, 34, 40, 46];
+const HEAT_NEGATIVE: [u8; 5] = [52, 88, 124, 160, 196];
+
+// Shades the text's background by a value between -1 and 1, green for positive and red for
+// negative values, stronger as the value moves away from 0, unless colours are disabled.
+pub fn heat(text: &str, value: f64) -> String {
+    if !colour_enabled() || value.is_nan() {
+        return text.to_string();
+    }
+    let step = ((value.abs().min(1.0) * 5.0) as usize).min(4);
+    let colour = if value >= 0.0 {
+        HEAT_POSITIVE[step]
+    } else {
+        HEAT_NEGATIVE[step]
+    };
+    format!("\x1B[48;5;{}m\x1B[97m{}{}", colour, text, RESET)
+}
+
+// Shows the given text in reverse video, used to mark selections in interactive views.
+//
+// Without colours the text is marked with a trailing `<` instead, taking the place of a trailing
+// space when there is one so padded cells keep their width.
+pub fn highlight(text: &str) -> String {
+    if colour_enabled() {
+        format!("{}{}{}", REVERSE, text, RESET)
+    } else if let Some(text) = text.strip_suffix(' ') {
+        format!("{}<", text)
+    } else {
+        format!("{} <", text)
+    }
+}
+
+// Prints a prompt and reads one trimmed line from stdin.
+pub fn prompt(message: &str) -> String {
+    print!("{}", message);
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    input.trim().to_string()
+}
+
+// Prints a line prefixed with the current UTC time, used by the plain renderers.
+pub fn print_timestamped(line: &str) {
+    println!("{} {}", Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"), line);
+}
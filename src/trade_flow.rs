@@ -0,0 +1,338 @@
+use crate::models::MarketTrade; // Importing the MarketTrade model.
+use chrono::{DateTime, Duration, Utc}; // Importing chrono types for trade times and windows.
+use serde::{Deserialize, Serialize}; // Importing serde traits for the saved settings.
+use std::collections::VecDeque; // Importing VecDeque for the rolling windows.
+use std::sync::OnceLock; // Importing OnceLock for the windows chosen on the command line.
+
+// Number of large trades remembered.
+const LARGE_TRADES_KEPT: usize = 10;
+
+// Window lengths chosen on the command line, overriding the saved ones.
+static WINDOWS_OVERRIDE: OnceLock<Vec<u64>> = OnceLock::new();
+
+// Trade-flow settings saved in the config file. Fields left out of the file take their defaults.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TradeFlowSettings {
+    #[serde(default = "default_windows_secs")]
+    pub windows_secs: Vec<u64>, // Rolling window lengths in seconds.
+    #[serde(default)]
+    pub large_trade_size: Option<f64>, // Trades at least this large in base currency are flagged.
+    #[serde(default = "default_large_trade_notional")]
+    pub large_trade_notional: Option<f64>, // Trades worth at least this much in quote currency are flagged.
+}
+
+impl Default for TradeFlowSettings {
+    fn default() -> Self {
+        TradeFlowSettings {
+            windows_secs: default_windows_secs(),
+            large_trade_size: None,
+            large_trade_notional: default_large_trade_notional(),
+        }
+    }
+}
+
+impl TradeFlowSettings {
+    // Returns the settings with the windows chosen on the command line, if any.
+    pub fn with_window_override(mut self) -> Self {
+        if let Some(windows) = WINDOWS_OVERRIDE.get() {
+            self.windows_secs = windows.clone();
+        }
+        self
+    }
+}
+
+// Default rolling windows: one, five and fifteen minutes.
+fn default_windows_secs() -> Vec<u64> {
+    vec![60, 300, 900]
+}
+
+// Default large-trade notional threshold.
+fn default_large_trade_notional() -> Option<f64> {
+    Some(100_000.0)
+}
+
+// Uses the given window lengths instead of the saved ones for this run.
+pub fn set_window_override(windows_secs: Vec<u64>) {
+    let _ = WINDOWS_OVERRIDE.set(windows_secs);
+}
+
+// Parses a comma-separated list of window lengths such as "30s,5m,1h", plain numbers being
+// seconds. Returns None when a length is invalid or zero.
+pub fn parse_windows(text: &str) -> Option<Vec<u64>> {
+    text.split(',')
+        .map(|window| {
+            let window = window.trim();
+            let (number, unit) = match window.find(|c: char| !c.is_ascii_digit()) {
+                Some(i) => window.split_at(i),
+                None => (window, "s"),
+            };
+            let multiplier = match unit {
+                "s" => 1,
+                "m" => 60,
+                "h" => 3600,
+                _ => return None,
+            };
+            let secs = number.parse::<u64>().ok()?.checked_mul(multiplier)?;
+            (secs > 0).then_some(secs)
+        })
+        .collect()
+}
+
+// A trade reduced to the fields the analytics need.
+#[derive(Debug, Clone)]
+pub struct FlowTrade {
+    pub time: DateTime<Utc>, // Time of the trade.
+    pub price: f64,          // Trade price.
+    pub size: f64,           // Trade size in base currency.
+    pub is_buy: bool,        // True when the buyer took liquidity.
+}
+
+impl FlowTrade {
+    // Parses a market trade, returning None when its time, price or size cannot be read.
+    pub fn from_market_trade(trade: &MarketTrade) -> Option<FlowTrade> {
+        Some(FlowTrade {
+            time: DateTime::parse_from_rfc3339(&trade.time)
+                .ok()?
+                .with_timezone(&Utc),
+            price: trade.price.parse().ok()?,
+            size: trade.size.parse().ok()?,
+            is_buy: trade.side.eq_ignore_ascii_case("buy"),
+        })
+    }
+
+    // Returns the quote value of the trade.
+    pub fn notional(&self) -> f64 {
+        self.price * self.size
+    }
+}
+
+// Figures for one rolling window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowStats {
+    pub length: Duration,          // Window length.
+    pub vwap: Option<f64>,         // Volume weighted average price, None without trades.
+    pub buy_volume: f64,           // Base volume bought by takers.
+    pub sell_volume: f64,          // Base volume sold by takers.
+    pub delta: f64,                // Buy volume minus sell volume.
+    pub trade_count: usize,        // Number of trades.
+    pub average_size: Option<f64>, // Average trade size.
+    pub max_size: Option<f64>,     // Largest trade size.
+}
+
+// Running sums for one rolling window.
+#[derive(Debug)]
+struct Window {
+    length: Duration,                          // Window length.
+    trades: VecDeque<FlowTrade>,               // Trades inside the window, oldest first.
+    max_sizes: VecDeque<(DateTime<Utc>, f64)>, // Decreasing sizes, so the front is the window maximum.
+    buy_volume: f64,                           // Sum of buy sizes.
+    sell_volume: f64,                          // Sum of sell sizes.
+    notional: f64,                             // Sum of price * size.
+}
+
+impl Window {
+    // Creates an empty window.
+    fn new(length: Duration) -> Self {
+        Window {
+            length,
+            trades: VecDeque::new(),
+            max_sizes: VecDeque::new(),
+            buy_volume: 0.0,
+            sell_volume: 0.0,
+            notional: 0.0,
+        }
+    }
+
+    // Adds a trade to the running sums.
+    fn push(&mut self, trade: &FlowTrade) {
+        if trade.is_buy {
+            self.buy_volume += trade.size;
+        } else {
+            self.sell_volume += trade.size;
+        }
+        self.notional += trade.notional();
+        while self
+            .max_sizes
+            .back()
+            .is_some_and(|(_, size)| *size <= trade.size)
+        {
+            self.max_sizes.pop_back();
+        }
+        self.max_sizes.push_back((trade.time, trade.size));
+        self.trades.push_back(trade.clone());
+    }
+
+    // Drops the trades older than the window length before `now`.
+    fn evict(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - self.length;
+        while let Some(trade) = self.trades.front() {
+            if trade.time > cutoff {
+                break;
+            }
+            if trade.is_buy {
+                self.buy_volume -= trade.size;
+            } else {
+                self.sell_volume -= trade.size;
+            }
+            self.notional -= trade.notional();
+            self.trades.pop_front();
+        }
+        while self
+            .max_sizes
+            .front()
+            .is_some_and(|(time, _)| *time <= cutoff)
+        {
+            self.max_sizes.pop_front();
+        }
+        if self.trades.is_empty() {
+            // Clear rounding leftovers from the subtractions.
+            self.buy_volume = 0.0;
+            self.sell_volume = 0.0;
+            self.notional = 0.0;
+        }
+    }
+
+    // Returns the figures for the trades currently in the window.
+    fn stats(&self) -> WindowStats {
+        let volume = self.buy_volume + self.sell_volume;
+        let count = self.trades.len();
+        WindowStats {
+            length: self.length,
+            vwap: (volume > 0.0).then(|| self.notional / volume),
+            buy_volume: self.buy_volume,
+            sell_volume: self.sell_volume,
+            delta: self.buy_volume - self.sell_volume,
+            trade_count: count,
+            average_size: (count > 0).then(|| volume / count as f64),
+            max_size: self.max_sizes.front().map(|(_, size)| *size),
+        }
+    }
+}
+
+// Rolling trade-flow analytics, updated one trade at a time.
+//
+// Trades are expected in chronological order. Windows are measured back from the newest
+// trade or from the time passed to `evict`, whichever is later.
+#[derive(Debug)]
+pub struct TradeFlow {
+    windows: Vec<Window>,              // Rolling windows, in the configured order.
+    settings: TradeFlowSettings,       // Window lengths and large-trade thresholds.
+    cumulative_delta: f64,             // Buy minus sell volume since the first trade.
+    large_trades: VecDeque<FlowTrade>, // Most recent large trades, newest first.
+    latest: Option<DateTime<Utc>>,     // Time of the newest trade.
+}
+
+impl TradeFlow {
+    // Creates empty analytics for the given settings.
+    pub fn new(settings: TradeFlowSettings) -> Self {
+        TradeFlow {
+            windows: settings
+                .windows_secs
+                .iter()
+                .map(|secs| Window::new(Duration::seconds(*secs as i64)))
+                .collect(),
+            settings,
+            cumulative_delta: 0.0,
+            large_trades: VecDeque::new(),
+            latest: None,
+        }
+    }
+
+    // Adds a trade, returning it when it crosses a large-trade threshold.
+    pub fn ingest(&mut self, trade: &MarketTrade) -> Option<FlowTrade> {
+        let trade = FlowTrade::from_market_trade(trade)?;
+        for window in &mut self.windows {
+            window.push(&trade);
+        }
+        self.cumulative_delta += if trade.is_buy {
+            trade.size
+        } else {
+            -trade.size
+        };
+        self.latest = Some(self.latest.map_or(trade.time, |t| t.max(trade.time)));
+        self.evict(trade.time);
+
+        if !self.is_large(&trade) {
+            return None;
+        }
+        self.large_trades.push_front(trade.clone());
+        self.large_trades.truncate(LARGE_TRADES_KEPT);
+        Some(trade)
+    }
+
+    // Drops trades that have left their windows, e.g. when no trade arrived for a while.
+    pub fn evict(&mut self, now: DateTime<Utc>) {
+        let now = self.latest.map_or(now, |latest| latest.max(now));
+        for window in &mut self.windows {
+            window.evict(now);
+        }
+    }
+
+    // Returns the figures for every window.
+    pub fn window_stats(&self) -> Vec<WindowStats> {
+        self.windows.iter().map(Window::stats).collect()
+    }
+
+    // Returns buy minus sell volume since the first trade.
+    pub fn cumulative_delta(&self) -> f64 {
+        self.cumulative_delta
+    }
+
+    // Returns the most recent large trades, newest first.
+    pub fn large_trades(&self) -> impl Iterator<Item = &FlowTrade> {
+        self.large_trades.iter()
+    }
+
+    // Returns the settings in use.
+    pub fn settings(&self) -> &TradeFlowSettings {
+        &self.settings
+    }
+
+    // Returns true when a trade crosses either large-trade threshold.
+    fn is_large(&self, trade: &FlowTrade) -> bool {
+        self.settings
+            .large_trade_size
+            .is_some_and(|min| trade.size >= min)
+            || self
+                .settings
+                .large_trade_notional
+                .is_some_and(|min| trade.notional() >= min)
+    }
+}
+
+// Formats a window length compactly, e.g. 30s, 5m or 4h.
+pub fn window_label(length: Duration) -> String {
+    let secs = length.num_seconds();
+    if secs % 3600 == 0 {
+        format!("{}h", secs / 3600)
+    } else if secs % 60 == 0 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_settings_take_their_defaults() {
+        let settings: TradeFlowSettings =
+            serde_json::from_str(r#"{"large_trade_size": 5.0}"#).unwrap();
+        assert_eq!(settings.windows_secs, [60, 300, 900]);
+        assert_eq!(settings.large_trade_size, Some(5.0));
+        assert_eq!(settings.large_trade_notional, Some(100_000.0));
+    }
+
+    #[test]
+    fn parses_window_lists() {
+        assert_eq!(
+            parse_windows("30s, 5m,1h,90"),
+            Some(vec![30, 300, 3600, 90])
+        );
+        assert_eq!(parse_windows("0s"), None);
+        assert_eq!(parse_windows("5d"), None);
+        assert_eq!(parse_windows(""), None);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::models::MarketTrade; // Importing the MarketTrade model.
+use chrono::{DateTime, FixedOffset}; // Importing chrono types to compare trade times.
+use std::cmp::Ordering; // Importing Ordering to sort trades.
+use std::collections::{HashSet, VecDeque}; // Importing collections to track seen trades.
+
+// Number of trade IDs remembered for de-duplication, per tape capacity.
+const SEEN_IDS_PER_TRADE: usize = 5;
+
+// Result of merging one poll of trades into the tape.
+#[derive(Debug, Default)]
+pub struct TapeUpdate {
+    pub new_trades: Vec<MarketTrade>, // Trades not seen before, newest first.
+    pub possible_gap: bool,           // True when trades may have been missed since the last poll.
+}
+
+// Time-and-sales tape that keeps the most recent trades, newest first, without duplicates.
+#[derive(Debug)]
+pub struct TradeTape {
+    trades: VecDeque<MarketTrade>, // Retained trades, newest first.
+    capacity: usize,               // Maximum number of retained trades.
+    seen: HashSet<String>,         // IDs of trades already ingested.
+    seen_order: VecDeque<String>,  // Ingestion order of seen IDs, used to bound memory.
+    last_trade_id: Option<u64>,    // Highest numeric trade ID ingested so far.
+}
+
+impl TradeTape {
+    // Creates an empty tape retaining at most `capacity` trades.
+    pub fn new(capacity: usize) -> Self {
+        TradeTape {
+            trades: VecDeque::with_capacity(capacity),
+            capacity,
+            seen: HashSet::new(),
+            seen_order: VecDeque::new(),
+            last_trade_id: None,
+        }
+    }
+
+    // Merges a batch of trades, keeping only the ones not seen before.
+    //
+    // A gap is flagged when the batch does not overlap the trades already on the tape,
+    // either because trade IDs skip ahead or because none of the batch was seen before.
+    pub fn ingest(&mut self, trades: Vec<MarketTrade>) -> TapeUpdate {
+        let had_trades = !self.seen.is_empty();
+        let batch_size = trades.len();
+
+        let mut new_trades: Vec<MarketTrade> = trades
+            .into_iter()
+            .filter(|trade| !self.seen.contains(&trade.trade_id))
+            .collect();
+        new_trades.sort_by(newest_first);
+        new_trades.dedup_by(|a, b| a.trade_id == b.trade_id);
+
+        let oldest_new_id = new_trades
+            .iter()
+            .filter_map(|trade| trade.trade_id.parse::<u64>().ok())
+            .min();
+        let possible_gap = had_trades
+            && !new_trades.is_empty()
+            && match (self.last_trade_id, oldest_new_id) {
+                (Some(last), Some(oldest)) => oldest > last + 1,
+                _ => new_trades.len() == batch_size,
+            };
+
+        // Record the new trades, oldest first so the tape stays newest first.
+        for trade in new_trades.iter().rev() {
+            self.remember(&trade.trade_id);
+            if let Ok(id) = trade.trade_id.parse::<u64>() {
+                self.last_trade_id = Some(self.last_trade_id.map_or(id, |last| last.max(id)));
+            }
+            self.trades.push_front(trade.clone());
+        }
+        self.trades.truncate(self.capacity);
+
+        TapeUpdate {
+            new_trades,
+            possible_gap,
+        }
+    }
+
+    // Returns the retained trades, newest first.
+    pub fn trades(&self) -> impl Iterator<Item = &MarketTrade> {
+        self.trades.iter()
+    }
+
+    // Returns the number of retained trades.
+    pub fn len(&self) -> usize {
+        self.trades.len()
+    }
+
+    // Returns true when no trades have been retained.
+    pub fn is_empty(&self) -> bool {
+        self.trades.is_empty()
+    }
+
+    // Marks a trade ID as seen, forgetting the oldest IDs once the limit is reached.
+    fn remember(&mut self, trade_id: &str) {
+        self.seen.insert(trade_id.to_string());
+        self.seen_order.push_back(trade_id.to_string());
+        while self.seen_order.len() > self.capacity * SEEN_IDS_PER_TRADE {
+            if let Some(oldest) = self.seen_order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+    }
+}
+
+// Orders trades newest first by time, then by numeric trade ID when times are equal.
+fn newest_first(a: &MarketTrade, b: &MarketTrade) -> Ordering {
+    // Timestamps have a varying number of fractional digits, so compare them parsed.
+    let by_time = match (parse_time(&a.time), parse_time(&b.time)) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        _ => b.time.cmp(&a.time),
+    };
+    by_time.then_with(
+        || match (a.trade_id.parse::<u64>(), b.trade_id.parse::<u64>()) {
+            (Ok(a), Ok(b)) => b.cmp(&a),
+            _ => b.trade_id.cmp(&a.trade_id),
+        },
+    )
+}
+
+// Parses an RFC 3339 trade time.
+fn parse_time(time: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(time).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A trade with an ID, traded `second` seconds into the day.
+    fn trade(trade_id: &str, second: u32) -> MarketTrade {
+        MarketTrade {
+            trade_id: trade_id.to_string(),
+            product_id: "BTC-USD".to_string(),
+            price: "100".to_string(),
+            size: "1".to_string(),
+            time: format!("2024-01-01T00:00:{:02}Z", second),
+            side: "BUY".to_string(),
+            bid: None,
+            ask: None,
+        }
+    }
+
+    // Builds a batch from trade IDs, each traded at its numeric ID in seconds.
+    fn batch(trade_ids: &[u32]) -> Vec<MarketTrade> {
+        trade_ids
+            .iter()
+            .map(|id| trade(&id.to_string(), *id))
+            .collect()
+    }
+
+    fn ids<'a>(trades: impl IntoIterator<Item = &'a MarketTrade>) -> Vec<&'a str> {
+        trades
+            .into_iter()
+            .map(|trade| trade.trade_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_polls_only_add_unseen_trades() {
+        let mut tape = TradeTape::new(4);
+        let first = tape.ingest(batch(&[1, 3, 2]));
+        assert_eq!(ids(&first.new_trades), ["3", "2", "1"]);
+        assert!(!first.possible_gap);
+
+        let update = tape.ingest(batch(&[5, 4, 3, 4]));
+        assert_eq!(ids(&update.new_trades), ["5", "4"]);
+        assert!(!update.possible_gap);
+        assert_eq!(ids(tape.trades()), ["5", "4", "3", "2"]);
+
+        let repeat = tape.ingest(batch(&[5, 4]));
+        assert!(repeat.new_trades.is_empty());
+        assert!(!repeat.possible_gap);
+    }
+
+    #[test]
+    fn a_skipped_trade_id_flags_a_gap() {
+        let mut tape = TradeTape::new(10);
+        tape.ingest(batch(&[2, 1]));
+        // Trade 2 overlaps, but trade 3 was never seen.
+        let update = tape.ingest(batch(&[5, 4, 2]));
+        assert_eq!(ids(&update.new_trades), ["5", "4"]);
+        assert!(update.possible_gap);
+
+        let update = tape.ingest(batch(&[6, 5]));
+        assert!(!update.possible_gap);
+    }
+
+    #[test]
+    fn non_numeric_ids_flag_a_gap_when_nothing_overlaps() {
+        let mut tape = TradeTape::new(10);
+        tape.ingest(vec![trade("b", 2), trade("a", 1)]);
+
+        let update = tape.ingest(vec![trade("c", 3), trade("b", 2)]);
+        assert_eq!(ids(&update.new_trades), ["c"]);
+        assert!(!update.possible_gap);
+
+        let update = tape.ingest(vec![trade("e", 5), trade("d", 4)]);
+        assert_eq!(ids(&update.new_trades), ["e", "d"]);
+        assert!(update.possible_gap);
+        assert_eq!(ids(tape.trades()), ["e", "d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn trades_are_ordered_by_parsed_time_then_id() {
+        let mut tape = TradeTape::new(10);
+        let mut later = trade("1", 0);
+        later.time = "2024-01-01T00:00:00.5Z".to_string();
+        tape.ingest(vec![trade("3", 0), later, trade("2", 0)]);
+        assert_eq!(ids(tape.trades()), ["1", "3", "2"]);
+    }
+}
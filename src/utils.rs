@@ -1 +1,2 @@
 pub const BASE_URL: &str = "https://api.coinbase.com/api/v3/brokerage";
+pub const WEBSOCKET_URL: &str = "wss://advanced-trade-ws.coinbase.com";
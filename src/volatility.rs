@@ -0,0 +1,263 @@
+use crate::indicators::{Ohlcv, Series}; // Importing the numeric candles and series type.
+use std::f64::consts::LN_2; // Importing ln 2 for the range-based estimators.
+
+// Seconds in a year of round-the-clock trading, used to annualise.
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+// Realized volatility estimators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Estimator {
+    CloseToClose,   // Standard deviation of close-to-close log returns.
+    Parkinson,      // High-low range.
+    GarmanKlass,    // High-low range and open-to-close move.
+    RogersSatchell, // High, low, open and close, robust to drift.
+    YangZhang,      // Open gaps, open-to-close moves and Rogers-Satchell combined.
+}
+
+impl Estimator {
+    // Every estimator, in display order.
+    pub const ALL: [Estimator; 5] = [
+        Estimator::CloseToClose,
+        Estimator::Parkinson,
+        Estimator::GarmanKlass,
+        Estimator::RogersSatchell,
+        Estimator::YangZhang,
+    ];
+
+    // Parses an estimator name, e.g. close, parkinson, gk, rs or yz.
+    pub fn parse(name: &str) -> Option<Estimator> {
+        match name.to_lowercase().replace(['_', '-'], "").as_str() {
+            "close" | "closetoclose" | "cc" => Some(Estimator::CloseToClose),
+            "parkinson" | "p" => Some(Estimator::Parkinson),
+            "garmanklass" | "gk" => Some(Estimator::GarmanKlass),
+            "rogerssatchell" | "rs" => Some(Estimator::RogersSatchell),
+            "yangzhang" | "yz" => Some(Estimator::YangZhang),
+            _ => None,
+        }
+    }
+
+    // Returns the display name of the estimator.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Estimator::CloseToClose => "Close-to-close",
+            Estimator::Parkinson => "Parkinson",
+            Estimator::GarmanKlass => "Garman-Klass",
+            Estimator::RogersSatchell => "Rogers-Satchell",
+            Estimator::YangZhang => "Yang-Zhang",
+        }
+    }
+
+    // Returns the machine-readable name of the estimator.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Estimator::CloseToClose => "close_to_close",
+            Estimator::Parkinson => "parkinson",
+            Estimator::GarmanKlass => "garman_klass",
+            Estimator::RogersSatchell => "rogers_satchell",
+            Estimator::YangZhang => "yang_zhang",
+        }
+    }
+}
+
+// Variance per candle period of consecutive candles, oldest first.
+//
+// The first candle only supplies the previous close, so N candles give an estimate over N - 1
+// periods for every estimator. Returns None with fewer than two periods or a non-positive price.
+pub fn period_variance(bars: &[Ohlcv], estimator: Estimator) -> Option<f64> {
+    if bars.len() < 3
+        || bars
+            .iter()
+            .any(|bar| bar.open <= 0.0 || bar.high <= 0.0 || bar.low <= 0.0 || bar.close <= 0.0)
+    {
+        return None;
+    }
+    let periods = &bars[1..];
+    let n = periods.len() as f64;
+    let variance = match estimator {
+        Estimator::CloseToClose => sample_variance(
+            &bars
+                .windows(2)
+                .map(|pair| (pair[1].close / pair[0].close).ln())
+                .collect::<Vec<f64>>(),
+        ),
+        Estimator::Parkinson => {
+            periods
+                .iter()
+                .map(|bar| (bar.high / bar.low).ln().powi(2))
+                .sum::<f64>()
+                / (4.0 * LN_2 * n)
+        }
+        Estimator::GarmanKlass => {
+            periods
+                .iter()
+                .map(|bar| {
+                    0.5 * (bar.high / bar.low).ln().powi(2)
+                        - (2.0 * LN_2 - 1.0) * (bar.close / bar.open).ln().powi(2)
+                })
+                .sum::<f64>()
+                / n
+        }
+        Estimator::RogersSatchell => rogers_satchell(periods),
+        Estimator::YangZhang => {
+            let overnight: Vec<f64> = bars
+                .windows(2)
+                .map(|pair| (pair[1].open / pair[0].close).ln())
+                .collect();
+            let open_to_close: Vec<f64> = periods
+                .iter()
+                .map(|bar| (bar.close / bar.open).ln())
+                .collect();
+            let k = 0.34 / (1.34 + (n + 1.0) / (n - 1.0));
+            sample_variance(&overnight)
+                + k * sample_variance(&open_to_close)
+                + (1.0 - k) * rogers_satchell(periods)
+        }
+    };
+    // Garman-Klass can dip below zero on candles that close far from their open.
+    Some(variance.max(0.0))
+}
+
+// Converts a variance per candle period to an annualised volatility.
+pub fn annualise(variance: f64, period_secs: i64) -> f64 {
+    (variance * SECONDS_PER_YEAR / period_secs as f64).sqrt()
+}
+
+// Annualised volatility over all the candles, oldest first and `period_secs` long.
+pub fn volatility(bars: &[Ohlcv], estimator: Estimator, period_secs: i64) -> Option<f64> {
+    period_variance(bars, estimator).map(|variance| annualise(variance, period_secs))
+}
+
+// Annualised volatility over a rolling window of `window` periods, one value per candle.
+//
+// Each value covers the window ending at its candle and also reads the close before it, so the
+// first `window` values are None.
+pub fn rolling_volatility(
+    bars: &[Ohlcv],
+    estimator: Estimator,
+    window: usize,
+    period_secs: i64,
+) -> Series {
+    (0..bars.len())
+        .map(|i| {
+            if window < 2 || i < window {
+                return None;
+            }
+            volatility(&bars[i - window..=i], estimator, period_secs)
+        })
+        .collect()
+}
+
+// Annualised volatility over the periods ending with the last candle that start within
+// `horizon_secs` of its end.
+//
+// The window is chosen by time rather than candle count, so periods without trades, which have
+// no candle, do not stretch it further back.
+pub fn trailing_volatility(
+    bars: &[Ohlcv],
+    estimator: Estimator,
+    period_secs: i64,
+    horizon_secs: i64,
+) -> Option<f64> {
+    let end = bars.last()?.start + period_secs;
+    let first = bars.partition_point(|bar| bar.start < end - horizon_secs);
+    // Keep the candle before the window for its close.
+    volatility(&bars[first.checked_sub(1)?..], estimator, period_secs)
+}
+
+// Mean of the Rogers-Satchell terms of each candle.
+fn rogers_satchell(periods: &[Ohlcv]) -> f64 {
+    periods
+        .iter()
+        .map(|bar| {
+            (bar.high / bar.close).ln() * (bar.high / bar.open).ln()
+                + (bar.low / bar.close).ln() * (bar.low / bar.open).ln()
+        })
+        .sum::<f64>()
+        / periods.len() as f64
+}
+
+// Sample variance, with an n - 1 denominator.
+fn sample_variance(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Daily candles as (open, high, low, close), oldest first.
+    const CANDLES: [(f64, f64, f64, f64); 6] = [
+        (100.0, 102.0, 99.0, 101.0),
+        (101.0, 104.0, 100.0, 103.0),
+        (103.0, 103.5, 98.0, 99.0),
+        (99.5, 101.0, 97.0, 100.0),
+        (100.0, 106.0, 99.5, 105.0),
+        (104.5, 107.0, 103.0, 106.0),
+    ];
+
+    fn bars() -> Vec<Ohlcv> {
+        CANDLES
+            .iter()
+            .enumerate()
+            .map(|(i, &(open, high, low, close))| Ohlcv {
+                start: i as i64 * 86_400,
+                open,
+                high,
+                low,
+                close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    // Annualised volatility of the candles by each estimator, worked out separately from the
+    // published formulas.
+    #[test]
+    fn estimators_match_reference_values() {
+        let expected = [
+            (Estimator::CloseToClose, 0.608_499_8),
+            (Estimator::Parkinson, 0.552_859_9),
+            (Estimator::GarmanKlass, 0.543_210_5),
+            (Estimator::RogersSatchell, 0.518_336_4),
+            (Estimator::YangZhang, 0.534_503_2),
+        ];
+        for (estimator, expected) in expected {
+            let value = volatility(&bars(), estimator, 86_400).unwrap();
+            assert!(
+                (value - expected).abs() < 1e-6,
+                "{}: {} instead of {}",
+                estimator.name(),
+                value,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn rolling_volatility_leaves_the_warm_up_empty() {
+        let rolling = rolling_volatility(&bars(), Estimator::CloseToClose, 3, 86_400);
+        assert_eq!(rolling.len(), CANDLES.len());
+        assert!(rolling[..3].iter().all(Option::is_none));
+        for (value, expected) in rolling[3..]
+            .iter()
+            .zip([0.607_373_2, 0.846_578_0, 0.430_497_5])
+        {
+            assert!((value.unwrap() - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn too_few_candles_give_no_estimate() {
+        assert_eq!(volatility(&bars()[..2], Estimator::YangZhang, 86_400), None);
+        assert_eq!(
+            trailing_volatility(&bars(), Estimator::CloseToClose, 86_400, 10 * 86_400),
+            None
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::websocket::messages::{decode_message, Channel, StreamMessage, SubscriptionRequest}; // Importing feed message types.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use futures_util::{SinkExt, StreamExt}; // Importing stream extensions to send and receive frames.
+use tokio::net::TcpStream; // Importing TcpStream, the transport under the socket.
+use tokio_tungstenite::tungstenite::Message; // Importing the WebSocket frame type.
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream}; // Importing the WebSocket client.
+
+// A connection to the market data feed.
+pub struct StreamConnection {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>, // Underlying WebSocket.
+}
+
+impl StreamConnection {
+    // Opens a connection to the feed at the given URL (e.g. WEBSOCKET_URL or a local test server).
+    pub async fn connect(url: &str) -> Result<Self> {
+        let (socket, _) = connect_async(url).await?;
+        Ok(StreamConnection { socket })
+    }
+
+    // Subscribes to a channel for the given products.
+    pub async fn subscribe(&mut self, channel: Channel, product_ids: &[String]) -> Result<()> {
+        self.send_request("subscribe", channel, product_ids).await
+    }
+
+    // Unsubscribes from a channel for the given products.
+    pub async fn unsubscribe(&mut self, channel: Channel, product_ids: &[String]) -> Result<()> {
+        self.send_request("unsubscribe", channel, product_ids).await
+    }
+
+    // Subscribes to each channel in turn, as the feed expects one request per channel.
+    pub async fn subscribe_all(
+        &mut self,
+        channels: &[Channel],
+        product_ids: &[String],
+    ) -> Result<()> {
+        for channel in channels {
+            self.subscribe(*channel, product_ids).await?;
+        }
+        Ok(())
+    }
+
+    // Waits for the next decoded message, returning None once the connection is closed.
+    pub async fn next_message(&mut self) -> Option<Result<StreamMessage>> {
+        while let Some(frame) = self.socket.next().await {
+            match frame {
+                Ok(Message::Text(text)) => return Some(decode_message(&text)),
+                Ok(Message::Binary(bytes)) => {
+                    return Some(
+                        String::from_utf8(bytes)
+                            .map_err(|e| anyhow!("invalid feed frame: {}", e))
+                            .and_then(|text| decode_message(&text)),
+                    )
+                }
+                Ok(Message::Close(_)) => return None,
+                Ok(_) => continue, // Ping and pong frames are answered by tungstenite.
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        None
+    }
+
+    // Closes the connection.
+    pub async fn close(&mut self) -> Result<()> {
+        self.socket.close(None).await?;
+        Ok(())
+    }
+
+    // Sends a subscribe or unsubscribe request.
+    async fn send_request(
+        &mut self,
+        kind: &str,
+        channel: Channel,
+        product_ids: &[String],
+    ) -> Result<()> {
+        let request = SubscriptionRequest {
+            kind,
+            product_ids,
+            channel: channel.name(),
+        };
+        let text = serde_json::to_string(&request)?;
+        self.socket.send(Message::Text(text)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::websocket::messages::StreamEvent;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    // Frames as documented for the Advanced Trade market data feed.
+    const HEARTBEAT: &str = r#"{"channel":"heartbeats","client_id":"","timestamp":"2023-06-23T20:31:26.122969572Z","sequence_num":0,"events":[{"current_time":"2023-06-23 20:31:56.121961769 +0000 UTC m=+91717.525857105","heartbeat_counter":"3049"}]}"#;
+    const L2_SNAPSHOT: &str = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:50.714964855Z","sequence_num":1,"events":[{"type":"snapshot","product_id":"BTC-USD","updates":[{"side":"bid","event_time":"1970-01-01T00:00:00Z","price_level":"21921.73","new_quantity":"0.06317902"},{"side":"offer","event_time":"1970-01-01T00:00:00Z","price_level":"21921.76","new_quantity":"0.02"}]}]}"#;
+    const L2_UPDATE: &str = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:51.714964855Z","sequence_num":2,"events":[{"type":"update","product_id":"BTC-USD","updates":[{"side":"bid","event_time":"2023-02-09T20:32:51.7Z","price_level":"21921.73","new_quantity":"0"}]}]}"#;
+    const MARKET_TRADES: &str = r#"{"channel":"market_trades","client_id":"","timestamp":"2023-02-09T20:19:35.39625135Z","sequence_num":3,"events":[{"type":"snapshot","trades":[{"trade_id":"000000000","product_id":"ETH-USD","price":"1260.01","size":"0.3","side":"BUY","time":"2019-08-14T20:42:27.265Z"}]}]}"#;
+
+    #[tokio::test]
+    async fn subscribes_and_decodes_feed_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // The server records the requests it receives, then replays the sample frames.
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            let mut requests = Vec::new();
+            for _ in 0..2 {
+                match socket.next().await.unwrap().unwrap() {
+                    Message::Text(text) => {
+                        requests.push(serde_json::from_str::<serde_json::Value>(&text).unwrap())
+                    }
+                    other => panic!("unexpected frame: {:?}", other),
+                }
+            }
+            for frame in [HEARTBEAT, L2_SNAPSHOT, L2_UPDATE, MARKET_TRADES] {
+                socket.send(Message::Text(frame.to_string())).await.unwrap();
+            }
+            socket.close(None).await.unwrap();
+            requests
+        });
+
+        let mut connection = StreamConnection::connect(&format!("ws://{}", address))
+            .await
+            .unwrap();
+        let products = vec!["BTC-USD".to_string()];
+        connection
+            .subscribe_all(&[Channel::Heartbeats, Channel::Level2], &products)
+            .await
+            .unwrap();
+
+        let mut messages = Vec::new();
+        while let Some(message) = connection.next_message().await {
+            messages.push(message.unwrap());
+        }
+
+        let requests = server.await.unwrap();
+        assert_eq!(
+            requests,
+            [
+                serde_json::json!({"type": "subscribe", "product_ids": ["BTC-USD"], "channel": "heartbeats"}),
+                serde_json::json!({"type": "subscribe", "product_ids": ["BTC-USD"], "channel": "level2"}),
+            ]
+        );
+
+        assert_eq!(messages.len(), 4);
+        assert_eq!(
+            messages.iter().map(|m| m.sequence_num).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        match &messages[0].events[..] {
+            [StreamEvent::Heartbeat(heartbeat)] => assert_eq!(heartbeat.heartbeat_counter, 3049),
+            other => panic!("expected a heartbeat, got {:?}", other),
+        }
+        match &messages[1].events[..] {
+            [StreamEvent::Level2(snapshot)] => {
+                assert_eq!(snapshot.kind, "snapshot");
+                assert_eq!(snapshot.product_id, "BTC-USD");
+                assert_eq!(snapshot.updates.len(), 2);
+                assert_eq!(snapshot.updates[1].side, "offer");
+                assert_eq!(snapshot.updates[1].price_level, "21921.76");
+            }
+            other => panic!("expected a level2 snapshot, got {:?}", other),
+        }
+        match &messages[2].events[..] {
+            [StreamEvent::Level2(update)] => {
+                assert_eq!(update.kind, "update");
+                assert_eq!(update.updates[0].new_quantity, "0");
+            }
+            other => panic!("expected a level2 update, got {:?}", other),
+        }
+        match &messages[3].events[..] {
+            [StreamEvent::MarketTrades(trades)] => {
+                assert_eq!(trades.trades.len(), 1);
+                assert_eq!(trades.trades[0].product_id, "ETH-USD");
+                assert_eq!(trades.trades[0].price, "1260.01");
+                assert_eq!(trades.trades[0].side, "BUY");
+                assert_eq!(trades.trades[0].bid, None);
+            }
+            other => panic!("expected market trades, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decodes_feed_errors_and_unknown_channels_as_errors() {
+        let error = decode_message(r#"{"type":"error","message":"failure to subscribe"}"#);
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("failure to subscribe"));
+        assert!(decode_message(r#"{"channel":"nope","events":[{}]}"#).is_err());
+    }
+}
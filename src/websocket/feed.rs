@@ -0,0 +1,328 @@
+use crate::order_book::{BookError, SequenceTracker}; // Importing the feed sequence tracker.
+use crate::websocket::client::StreamConnection; // Importing the feed connection.
+use crate::websocket::messages::{Channel, StreamEvent, StreamMessage}; // Importing feed message types.
+use std::collections::{HashMap, HashSet}; // Importing collections for per-product tracking.
+use std::time::Duration; // Importing Duration for timeouts and backoff.
+use tokio::sync::mpsc; // Importing mpsc to hand events to the consumer.
+use tokio::time::{self, Instant}; // Importing tokio time utilities.
+use tokio_tungstenite::tungstenite; // Importing tungstenite to recognise transport errors.
+
+// Capacity of the channel between the feed task and its consumer.
+const FEED_CHANNEL_CAPACITY: usize = 1024;
+// How often heartbeats and product activity are checked.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// Settings for a supervised feed.
+#[derive(Debug, Clone)]
+pub struct FeedConfig {
+    pub url: String,                 // Feed URL.
+    pub channels: Vec<Channel>,      // Channels to subscribe to, heartbeats are always added.
+    pub product_ids: Vec<String>,    // Products to subscribe to.
+    pub heartbeat_timeout: Duration, // Reconnect when no heartbeat arrives for this long.
+    pub stale_after: Duration,       // Flag a product once it has been silent for this long.
+    pub initial_backoff: Duration,   // Delay before the first reconnection attempt.
+    pub max_backoff: Duration,       // Longest delay between reconnection attempts.
+}
+
+impl FeedConfig {
+    // Creates a config with default timeouts for the given subscription.
+    pub fn new(url: &str, channels: &[Channel], product_ids: &[String]) -> Self {
+        FeedConfig {
+            url: url.to_string(),
+            channels: channels.to_vec(),
+            product_ids: product_ids.to_vec(),
+            heartbeat_timeout: Duration::from_secs(10),
+            stale_after: Duration::from_secs(30),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+// Why consumers must rebuild their state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResetReason {
+    Reconnected,                                  // The connection was re-established.
+    SequenceGap { expected: u64, received: u64 }, // Messages were skipped on the connection.
+}
+
+// Events delivered by a supervised feed.
+#[derive(Debug, Clone)]
+pub enum FeedEvent {
+    // Subscribed and receiving data.
+    Connected,
+    // A decoded feed message.
+    Message(StreamMessage),
+    // Books and tapes must be rebuilt from the snapshots that follow.
+    Reset(ResetReason),
+    // No data for a product for a while.
+    Stale {
+        product_id: String,
+        silent_for: Duration,
+    },
+    // Data resumed for a stale product.
+    Recovered {
+        product_id: String,
+    },
+    // Connection lost, retrying later.
+    Disconnected {
+        reason: String,
+        retry_in: Duration,
+    },
+    // A message could not be decoded.
+    Error(String),
+}
+
+// Why a connection ended.
+enum ConnectionEnd {
+    // The consumer went away.
+    Stopped,
+    // The connection failed and should be retried.
+    Lost {
+        reason: String,     // What went wrong.
+        reset: ResetReason, // Reset to report once reconnected.
+        healthy: bool,      // Whether a heartbeat was received before the failure.
+    },
+}
+
+impl ConnectionEnd {
+    // Builds the end of a connection lost for a reason other than a sequence gap.
+    fn lost(reason: impl Into<String>, healthy: bool) -> Self {
+        ConnectionEnd::Lost {
+            reason: reason.into(),
+            reset: ResetReason::Reconnected,
+            healthy,
+        }
+    }
+}
+
+// Starts a supervised feed that reconnects with backoff, resubscribes to the same channels,
+// and reports resets and stale products. The feed stops when the receiver is dropped.
+pub fn spawn_feed(config: FeedConfig) -> mpsc::Receiver<FeedEvent> {
+    let (tx, rx) = mpsc::channel(FEED_CHANNEL_CAPACITY);
+    tokio::spawn(run_feed(config, tx));
+    rx
+}
+
+// Connects and reconnects until the consumer goes away.
+async fn run_feed(mut config: FeedConfig, tx: mpsc::Sender<FeedEvent>) {
+    if !config.channels.contains(&Channel::Heartbeats) {
+        config.channels.push(Channel::Heartbeats); // Heartbeats drive the liveness check.
+    }
+
+    let mut backoff = config.initial_backoff;
+    let mut pending_reset: Option<ResetReason> = None; // Reset owed to the consumer, one per resync.
+
+    loop {
+        let reason = match connect(&config).await {
+            Ok(connection) => {
+                if tx.send(FeedEvent::Connected).await.is_err() {
+                    return;
+                }
+                if let Some(reset) = pending_reset.take() {
+                    if tx.send(FeedEvent::Reset(reset)).await.is_err() {
+                        return;
+                    }
+                }
+
+                match run_connection(connection, &config, &tx).await {
+                    ConnectionEnd::Stopped => return,
+                    ConnectionEnd::Lost {
+                        reason,
+                        reset,
+                        healthy,
+                    } => {
+                        // Only a connection that delivered heartbeats counts as recovered, so a
+                        // server that accepts and then drops connections keeps backing off.
+                        if healthy {
+                            backoff = config.initial_backoff;
+                        }
+                        pending_reset = Some(reset);
+                        reason
+                    }
+                }
+            }
+            Err(e) => format!("connection failed: {}", e),
+        };
+
+        let event = FeedEvent::Disconnected {
+            reason,
+            retry_in: backoff,
+        };
+        if tx.send(event).await.is_err() {
+            return;
+        }
+
+        // Wait before retrying, unless the consumer goes away first.
+        tokio::select! {
+            _ = tx.closed() => return,
+            _ = time::sleep(backoff) => {}
+        }
+        backoff = (backoff * 2).min(config.max_backoff);
+    }
+}
+
+// Opens a connection and subscribes to every configured channel.
+async fn connect(config: &FeedConfig) -> anyhow::Result<StreamConnection> {
+    let mut connection = StreamConnection::connect(&config.url).await?;
+    connection
+        .subscribe_all(&config.channels, &config.product_ids)
+        .await?;
+    Ok(connection)
+}
+
+// Forwards messages from one connection while watching heartbeats, sequence numbers and product activity.
+async fn run_connection(
+    mut connection: StreamConnection,
+    config: &FeedConfig,
+    tx: &mpsc::Sender<FeedEvent>,
+) -> ConnectionEnd {
+    let mut sequence = SequenceTracker::default();
+    let mut last_heartbeat = Instant::now();
+    let mut healthy = false; // Set once the first heartbeat arrives.
+    let mut last_seen: HashMap<String, Instant> = config
+        .product_ids
+        .iter()
+        .map(|id| (id.clone(), Instant::now()))
+        .collect();
+    let mut stale: HashSet<String> = HashSet::new();
+    let mut health_check = time::interval(HEALTH_CHECK_INTERVAL);
+
+    loop {
+        let mut events = Vec::new(); // Events to hand to the consumer for this iteration.
+
+        tokio::select! {
+            _ = tx.closed() => return ConnectionEnd::Stopped,
+            message = connection.next_message() => match message {
+                None => return ConnectionEnd::lost("connection closed", healthy),
+                Some(Err(e)) if e.downcast_ref::<tungstenite::Error>().is_some() => {
+                    return ConnectionEnd::lost(e.to_string(), healthy);
+                }
+                Some(Err(e)) => events.push(FeedEvent::Error(e.to_string())),
+                Some(Ok(message)) => {
+                    if let Err(BookError::SequenceGap { expected, received }) =
+                        sequence.observe(message.sequence_num)
+                    {
+                        // Reconnecting brings fresh snapshots for every subscribed channel, and the
+                        // gap is reported as the reset once reconnected.
+                        return ConnectionEnd::Lost {
+                            reason: format!("sequence gap: expected {}, received {}", expected, received),
+                            reset: ResetReason::SequenceGap { expected, received },
+                            healthy,
+                        };
+                    }
+
+                    let now = Instant::now();
+                    for event in &message.events {
+                        if let StreamEvent::Heartbeat(_) = event {
+                            last_heartbeat = now;
+                            healthy = true;
+                        }
+                        for product_id in event.product_ids() {
+                            last_seen.insert(product_id.to_string(), now);
+                            if stale.remove(product_id) {
+                                events.push(FeedEvent::Recovered {
+                                    product_id: product_id.to_string(),
+                                });
+                            }
+                        }
+                    }
+                    events.push(FeedEvent::Message(message));
+                }
+            },
+            _ = health_check.tick() => {
+                if last_heartbeat.elapsed() > config.heartbeat_timeout {
+                    return ConnectionEnd::lost("heartbeat timeout", healthy);
+                }
+                for (product_id, seen) in &last_seen {
+                    let silent_for = seen.elapsed();
+                    if silent_for > config.stale_after && stale.insert(product_id.clone()) {
+                        events.push(FeedEvent::Stale {
+                            product_id: product_id.clone(),
+                            silent_for,
+                        });
+                    }
+                }
+            }
+        }
+
+        for event in events {
+            if tx.send(event).await.is_err() {
+                return ConnectionEnd::Stopped;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    // Heartbeat frame with the given sequence number.
+    fn heartbeat(sequence: u64) -> Message {
+        Message::Text(format!(
+            r#"{{"channel":"heartbeats","timestamp":"2023-06-23T20:31:26Z","sequence_num":{},"events":[{{"current_time":"2023-06-23 20:31:26 +0000 UTC","heartbeat_counter":{}}}]}}"#,
+            sequence, sequence
+        ))
+    }
+
+    #[tokio::test]
+    async fn sequence_gap_reports_a_single_reset() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // The first connection skips sequence numbers, the second behaves.
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            for frames in [vec![heartbeat(0), heartbeat(5)], vec![heartbeat(0)]] {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut socket = accept_async(stream).await.unwrap();
+                socket.next().await; // The heartbeats subscription.
+                for frame in frames {
+                    socket.send(frame).await.unwrap();
+                }
+                sockets.push(socket); // Kept open so only the gap ends the connection.
+            }
+            std::future::pending::<()>().await;
+        });
+
+        let mut config = FeedConfig::new(&format!("ws://{}", address), &[], &[]);
+        config.initial_backoff = Duration::from_millis(10);
+        let mut feed = spawn_feed(config);
+
+        let mut events = Vec::new();
+        let mut connections = 0;
+        while connections < 2 || !matches!(events.last(), Some(FeedEvent::Message(_))) {
+            let event = time::timeout(Duration::from_secs(5), feed.recv())
+                .await
+                .expect("feed stalled")
+                .unwrap();
+            if let FeedEvent::Connected = event {
+                connections += 1;
+            }
+            events.push(event);
+        }
+
+        let resets: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                FeedEvent::Reset(reason) => Some(reason.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            resets,
+            [ResetReason::SequenceGap {
+                expected: 1,
+                received: 5
+            }]
+        );
+        assert!(matches!(
+            events.iter().find(|event| matches!(event, FeedEvent::Disconnected { .. })),
+            Some(FeedEvent::Disconnected { retry_in, .. }) if *retry_in == Duration::from_millis(10)
+        ));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::models::{Candle, MarketTrade}; // Importing the REST models reused by the feed.
+use anyhow::{anyhow, Result}; // Importing anyhow utilities for error handling.
+use serde::de::{self, Deserializer}; // Importing serde helpers for custom field decoding.
+use serde::{Deserialize, Serialize}; // Importing serde traits for feed messages.
+use std::collections::HashMap; // Importing HashMap for subscription lists.
+
+// Channels available on the public market data feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Ticker,
+    TickerBatch,
+    Level2,
+    MarketTrades,
+    Candles,
+    Status,
+    Heartbeats,
+}
+
+impl Channel {
+    // Returns the name used when subscribing to the channel.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Channel::Ticker => "ticker",
+            Channel::TickerBatch => "ticker_batch",
+            Channel::Level2 => "level2",
+            Channel::MarketTrades => "market_trades",
+            Channel::Candles => "candles",
+            Channel::Status => "status",
+            Channel::Heartbeats => "heartbeats",
+        }
+    }
+}
+
+// Struct to represent a subscribe or unsubscribe request sent to the feed.
+#[derive(Serialize, Debug)]
+pub struct SubscriptionRequest<'a> {
+    #[serde(rename = "type")]
+    pub kind: &'a str, // "subscribe" or "unsubscribe".
+    pub product_ids: &'a [String], // Products to (un)subscribe.
+    pub channel: &'a str,          // Channel name.
+}
+
+// Struct to represent one decoded feed message.
+#[derive(Debug, Clone)]
+pub struct StreamMessage {
+    pub channel: String,   // Channel name as sent by the server (e.g. "l2_data").
+    pub timestamp: String, // Server timestamp of the message.
+    pub sequence_num: u64, // Per-connection message sequence number.
+    pub events: Vec<StreamEvent>, // Typed events carried by the message.
+}
+
+// Typed events carried by feed messages.
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    Ticker(TickerEvent),               // Real-time ticker updates.
+    TickerBatch(TickerEvent),          // Ticker updates batched every few seconds.
+    Level2(Level2Event),               // Order book snapshot or updates.
+    MarketTrades(MarketTradesEvent),   // Trades.
+    Candles(CandlesEvent),             // Five minute candles.
+    Status(StatusEvent),               // Product status changes.
+    Heartbeat(HeartbeatEvent),         // Connection heartbeats.
+    Subscriptions(SubscriptionsEvent), // Current subscriptions.
+}
+
+impl StreamEvent {
+    // Returns the IDs of the products this event carries data for.
+    pub fn product_ids(&self) -> Vec<&str> {
+        match self {
+            StreamEvent::Ticker(e) | StreamEvent::TickerBatch(e) => {
+                e.tickers.iter().map(|t| t.product_id.as_str()).collect()
+            }
+            StreamEvent::Level2(e) => vec![e.product_id.as_str()],
+            StreamEvent::MarketTrades(e) => {
+                e.trades.iter().map(|t| t.product_id.as_str()).collect()
+            }
+            StreamEvent::Candles(e) => e.candles.iter().map(|c| c.product_id.as_str()).collect(),
+            StreamEvent::Status(e) => e.products.iter().map(|p| p.id.as_str()).collect(),
+            StreamEvent::Heartbeat(_) | StreamEvent::Subscriptions(_) => Vec::new(),
+        }
+    }
+}
+
+// Struct to represent a ticker entry.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Ticker {
+    pub product_id: String, // ID of the product.
+    pub price: String,      // Last traded price.
+    #[serde(default)]
+    pub volume_24_h: String, // 24h volume.
+    #[serde(default)]
+    pub low_24_h: String, // 24h low.
+    #[serde(default)]
+    pub high_24_h: String, // 24h high.
+    #[serde(default)]
+    pub low_52_w: String, // 52 week low.
+    #[serde(default)]
+    pub high_52_w: String, // 52 week high.
+    #[serde(default)]
+    pub price_percent_chg_24_h: String, // 24h price change percentage.
+    #[serde(default)]
+    pub volume_percent_chg_24_h: String, // 24h volume change percentage, only known when polling.
+    #[serde(default)]
+    pub best_bid: String, // Best bid price.
+    #[serde(default)]
+    pub best_bid_quantity: String, // Size at the best bid.
+    #[serde(default)]
+    pub best_ask: String, // Best ask price.
+    #[serde(default)]
+    pub best_ask_quantity: String, // Size at the best ask.
+}
+
+// Struct to represent a ticker or ticker_batch event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TickerEvent {
+    #[serde(rename = "type")]
+    pub kind: String, // "snapshot" or "update".
+    pub tickers: Vec<Ticker>, // Ticker entries.
+}
+
+// Struct to represent a single order book level change.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Level2Update {
+    pub side: String, // "bid" or "offer".
+    #[serde(default)]
+    pub event_time: String, // Time of the change.
+    pub price_level: String, // Price of the level.
+    pub new_quantity: String, // New size at the level, zero when removed.
+}
+
+// Struct to represent a level2 event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Level2Event {
+    #[serde(rename = "type")]
+    pub kind: String, // "snapshot" or "update".
+    pub product_id: String,         // ID of the product.
+    pub updates: Vec<Level2Update>, // Level changes.
+}
+
+// Struct to represent a market_trades event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarketTradesEvent {
+    #[serde(rename = "type")]
+    pub kind: String, // "snapshot" or "update".
+    pub trades: Vec<MarketTrade>, // Trades.
+}
+
+// Struct to represent a candle received from the feed, which also names its product.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CandleUpdate {
+    pub product_id: String, // ID of the product.
+    #[serde(flatten)]
+    pub candle: Candle, // Candle data.
+}
+
+// Struct to represent a candles event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CandlesEvent {
+    #[serde(rename = "type")]
+    pub kind: String, // "snapshot" or "update".
+    pub candles: Vec<CandleUpdate>, // Candles.
+}
+
+// Struct to represent a product in a status event.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ProductStatus {
+    pub id: String, // ID of the product.
+    #[serde(default)]
+    pub product_type: String, // Type of the product.
+    #[serde(default)]
+    pub base_currency: String, // Base currency.
+    #[serde(default)]
+    pub quote_currency: String, // Quote currency.
+    #[serde(default)]
+    pub base_name: String, // Name of the base currency, only known when polling.
+    #[serde(default)]
+    pub quote_name: String, // Name of the quote currency, only known when polling.
+    #[serde(default)]
+    pub base_increment: String, // Increment for the base currency.
+    #[serde(default)]
+    pub quote_increment: String, // Increment for the quote currency.
+    #[serde(default)]
+    pub display_name: String, // Display name of the product.
+    #[serde(default)]
+    pub status: String, // Trading status.
+    #[serde(default)]
+    pub status_message: String, // Status message.
+    #[serde(default)]
+    pub min_market_funds: String, // Minimum market order funds.
+}
+
+// Struct to represent a status event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct StatusEvent {
+    #[serde(rename = "type")]
+    pub kind: String, // "snapshot" or "update".
+    pub products: Vec<ProductStatus>, // Product statuses.
+}
+
+// Struct to represent a heartbeat event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct HeartbeatEvent {
+    pub current_time: String, // Server time of the heartbeat.
+    #[serde(deserialize_with = "number_or_string")]
+    pub heartbeat_counter: u64, // Counter incremented by one per heartbeat.
+}
+
+// Struct to represent a subscriptions event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SubscriptionsEvent {
+    pub subscriptions: HashMap<String, Vec<String>>, // Subscribed products per channel.
+}
+
+// Struct to represent the envelope shared by every feed message.
+#[derive(Deserialize, Debug)]
+struct Envelope {
+    #[serde(rename = "type")]
+    kind: Option<String>, // Only set on error messages.
+    message: Option<String>, // Error description.
+    channel: Option<String>, // Channel the message belongs to.
+    #[serde(default)]
+    timestamp: String, // Server timestamp.
+    #[serde(default)]
+    sequence_num: u64, // Message sequence number.
+    #[serde(default)]
+    events: Vec<serde_json::Value>, // Undecoded events.
+}
+
+// Decodes a raw text frame from the feed into a typed message.
+pub fn decode_message(text: &str) -> Result<StreamMessage> {
+    let envelope: Envelope = serde_json::from_str(text)?;
+
+    if envelope.kind.as_deref() == Some("error") {
+        return Err(anyhow!(
+            "feed error: {}",
+            envelope.message.unwrap_or_default()
+        ));
+    }
+    let channel = envelope
+        .channel
+        .ok_or_else(|| anyhow!("feed message without a channel: {}", text))?;
+
+    let events = envelope
+        .events
+        .into_iter()
+        .map(|event| decode_event(&channel, event))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(StreamMessage {
+        channel,
+        timestamp: envelope.timestamp,
+        sequence_num: envelope.sequence_num,
+        events,
+    })
+}
+
+// Decodes a single event according to the channel it was received on.
+fn decode_event(channel: &str, event: serde_json::Value) -> Result<StreamEvent> {
+    let event = match channel {
+        "ticker" => StreamEvent::Ticker(serde_json::from_value(event)?),
+        "ticker_batch" => StreamEvent::TickerBatch(serde_json::from_value(event)?),
+        "l2_data" | "level2" => StreamEvent::Level2(serde_json::from_value(event)?),
+        "market_trades" => StreamEvent::MarketTrades(serde_json::from_value(event)?),
+        "candles" => StreamEvent::Candles(serde_json::from_value(event)?),
+        "status" => StreamEvent::Status(serde_json::from_value(event)?),
+        "heartbeats" => StreamEvent::Heartbeat(serde_json::from_value(event)?),
+        "subscriptions" => StreamEvent::Subscriptions(serde_json::from_value(event)?),
+        _ => return Err(anyhow!("unknown feed channel: {}", channel)),
+    };
+    Ok(event)
+}
+
+// Accepts a counter sent either as a JSON number or as a numeric string.
+fn number_or_string<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| de::Error::custom("counter is not a positive integer")),
+        serde_json::Value::String(s) => s.parse().map_err(de::Error::custom),
+        other => Err(de::Error::custom(format!("unexpected counter: {}", other))),
+    }
+}